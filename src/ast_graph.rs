@@ -0,0 +1,136 @@
+use crate::keywords::{CONST_KEYWORD, LET_KEYWORD};
+use crate::node::{assignment_operator_to_str, binary_operator_to_str, unary_operator_to_str};
+use crate::nodes::*;
+use crate::value::number_to_string;
+use crate::visitor::{walk_expression, walk_statement, Visitor};
+
+/// Renders parse tree as a Graphviz graph, e.g. `rustjs ast --dot main.js | dot -Tsvg > ast.svg`.
+/// Every statement & expression is a node labeled with its kind, operator or literal and its span
+pub fn ast_to_dot(ast: &AstStatement) -> String {
+    let mut printer = DotPrinter { output: String::new(), next_id: 0, parents: vec![] };
+    printer.visit_statement(ast);
+
+    return format!("digraph ast {{\n  node [shape=box, fontname=\"monospace\"];\n{}}}\n", printer.output);
+}
+
+struct DotPrinter {
+    output: String,
+    next_id: usize,
+    /// Nodes being visited, the last one is the parent of the next added node
+    parents: Vec<usize>,
+}
+
+impl DotPrinter {
+    fn add_node(&mut self, label: &str, span: &TextSpan) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let label = format!("{label}\n{}:{}..{}:{}", span.start.line + 1, span.start.row, span.end.line + 1, span.end.row);
+        self.output.push_str(&format!("  n{id} [label=\"{}\"];\n", escape_label(&label)));
+
+        if let Some(parent) = self.parents.last() {
+            self.output.push_str(&format!("  n{parent} -> n{id};\n"));
+        }
+
+        return id;
+    }
+}
+
+impl Visitor for DotPrinter {
+    fn visit_statement(&mut self, stmt: &AstStatement) {
+        let id = self.add_node(&statement_label(stmt), &stmt.get_span());
+        self.parents.push(id);
+        walk_statement(self, stmt);
+        self.parents.pop();
+    }
+
+    fn visit_expression(&mut self, stmt: &AstExpression) {
+        let id = self.add_node(&expression_label(stmt), &stmt.get_span());
+        self.parents.push(id);
+        walk_expression(self, stmt);
+        self.parents.pop();
+    }
+
+    fn visit_variable_declaration(&mut self, stmt: &VariableDeclarationNode) {
+        if let Some(value) = &stmt.value {
+            self.visit_expression(value);
+        }
+    }
+}
+
+fn statement_label(stmt: &AstStatement) -> String {
+    match stmt {
+        AstStatement::ProgramStatement(_) => "Program".to_string(),
+        AstStatement::VariableDeclaration(node) => {
+            let keyword = match node.kind {
+                VariableDeclarationKind::Let => LET_KEYWORD,
+                VariableDeclarationKind::Const => CONST_KEYWORD,
+            };
+            format!("VariableDeclaration {keyword} {}", node.id.id)
+        }
+        AstStatement::BlockStatement(_) => "Block".to_string(),
+        AstStatement::WhileStatement(_) => "While".to_string(),
+        AstStatement::ForStatement(_) => "For".to_string(),
+        AstStatement::FunctionDeclaration(node) => format!("FunctionDeclaration {}", node.function_signature.name.id),
+        AstStatement::ReturnStatement(_) => "Return".to_string(),
+        AstStatement::ExpressionStatement(_) => "ExpressionStatement".to_string(),
+        AstStatement::IfStatement(_) => "If".to_string(),
+        AstStatement::BreakStatement(_) => "Break".to_string(),
+        AstStatement::ContinueStatement(_) => "Continue".to_string(),
+        AstStatement::SwitchStatement(_) => "Switch".to_string(),
+        AstStatement::ThrowStatement(_) => "Throw".to_string(),
+        AstStatement::EmptyStatement(_) => "Empty".to_string(),
+    }
+}
+
+fn expression_label(expression: &AstExpression) -> String {
+    match expression {
+        AstExpression::StringLiteral(node) => format!("String {:?}", node.value),
+        AstExpression::NumberLiteral(node) => format!("Number {}", number_to_string(node.value)),
+        AstExpression::BooleanLiteral(node) => format!("Boolean {}", node.value),
+        AstExpression::NullLiteral(_) => "Null".to_string(),
+        AstExpression::UndefinedLiteral(_) => "Undefined".to_string(),
+        AstExpression::ThisExpression(_) => "This".to_string(),
+        AstExpression::Identifier(node) => format!("Identifier {}", node.id),
+        AstExpression::BinaryExpression(node) => format!("Binary {}", binary_operator_to_str(&node.operator)),
+        AstExpression::AssignmentExpression(node) => format!("Assignment {}", assignment_operator_to_str(&node.operator)),
+        AstExpression::FunctionExpression(_) => "Function".to_string(),
+        AstExpression::CallExpression(_) => "Call".to_string(),
+        AstExpression::ConditionalExpression(_) => "Conditional".to_string(),
+        AstExpression::MemberExpression(node) if node.computed => "Member [computed]".to_string(),
+        AstExpression::MemberExpression(_) => "Member".to_string(),
+        AstExpression::NewExpression(_) => "New".to_string(),
+        AstExpression::ObjectExpression(_) => "Object".to_string(),
+        AstExpression::ClassDeclaration(node) => format!("Class {}", node.name.id),
+        AstExpression::ArrayExpression(_) => "Array".to_string(),
+        AstExpression::SpreadElement(_) => "Spread".to_string(),
+        AstExpression::UnaryExpression(node) => format!("Unary {}", unary_operator_to_str(&node.operator)),
+    }
+}
+
+/// Quotes & backslashes are escaped, line breaks become DOT's centered line breaks
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[test]
+fn ast_is_rendered_as_dot_graph() {
+    let ast = crate::parser::Parser::parse_code_to_ast("let a = -1 + \"b\";").unwrap();
+
+    assert_eq!(ast_to_dot(&ast), "\
+digraph ast {
+  node [shape=box, fontname=\"monospace\"];
+  n0 [label=\"Program\\n1:4..1:16\"];
+  n1 [label=\"VariableDeclaration let a\\n1:4..1:16\"];
+  n0 -> n1;
+  n2 [label=\"Binary +\\n1:8..1:16\"];
+  n1 -> n2;
+  n3 [label=\"Unary -\\n1:8..1:10\"];
+  n2 -> n3;
+  n4 [label=\"Number 1\\n1:9..1:10\"];
+  n3 -> n4;
+  n5 [label=\"String \\\"b\\\"\\n1:13..1:16\"];
+  n2 -> n5;
+}
+");
+}
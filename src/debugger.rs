@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+use ariadne::{Color, Config, Label, Report, ReportKind, Source};
+use crate::interpreter::ast_interpreter::Interpreter;
+use crate::interpreter::engine::ExecutionEngine;
+use crate::interpreter::hooks::ExecutionHooks;
+use crate::nodes::{AstStatement, GetSpan, TextSpan};
+use crate::parser::Parser;
+use crate::value::{DisplayMode, JsValue};
+
+const HELP: &str = "\
+s, step          run until the next statement (empty line does the same)
+c, continue      run until a breakpoint
+b, break [LINE]  set breakpoint at the line, list breakpoints & their hit counts without a line
+                 `b LINE if EXPR` stops only when EXPR is truthy in the current scope
+d, delete LINE   remove breakpoint
+w, watch [EXPR]  print EXPR on every pause, list watches without EXPR
+uw, unwatch N    remove watch with the number
+v, vars          print variables of the current scope & its parents
+p, print EXPR    evaluate expression in the current scope, e.g. `p count = 0` changes a variable
+q, quit          stop the script
+h, help          print this help";
+
+/// Source level debugger of the AST interpreter, pauses before statements & reads commands.
+/// Pauses before the first statement, then before every statement while stepping or on a breakpoint line
+pub struct Debugger {
+    file_path: String,
+    source: String,
+    /// Lines counted from 1
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    /// Expressions printed on every pause
+    watches: Vec<String>,
+    is_stepping: bool,
+    display: DisplayMode,
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+}
+
+struct Breakpoint {
+    /// Expression evaluated in the scope of the paused statement
+    condition: Option<String>,
+    /// How many times the breakpoint paused the script
+    hits: usize,
+}
+
+impl Debugger {
+    pub fn new(file_path: &str, source: &str, display: DisplayMode, input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            source: source.to_string(),
+            breakpoints: BTreeMap::new(),
+            watches: vec![],
+            is_stepping: true,
+            display,
+            input,
+            output,
+        }
+    }
+
+    /// Blocks & programs only group statements, pausing on them would show the same code twice
+    fn should_pause(&mut self, interpreter: &Interpreter, statement: &AstStatement) -> bool {
+        if matches!(statement, AstStatement::ProgramStatement(_) | AstStatement::BlockStatement(_)) {
+            return false;
+        }
+
+        let is_breakpoint_hit = self.is_breakpoint_hit(interpreter, statement.get_span().start.line + 1);
+        return self.is_stepping || is_breakpoint_hit;
+    }
+
+    /// Whether the line has a breakpoint whose condition holds, counting the hit.
+    /// Condition which fails pauses too, so the error can be seen
+    fn is_breakpoint_hit(&mut self, interpreter: &Interpreter, line: usize) -> bool {
+        let Some(breakpoint) = self.breakpoints.get(&line) else {
+            return false;
+        };
+
+        let is_hit = match breakpoint.condition.clone() {
+            None => true,
+            Some(condition) => match interpreter.run_source(&condition) {
+                Ok(value) => value.is_some_and(|x| x.to_bool()),
+                Err(e) => {
+                    writeln!(self.output, "Error in condition of breakpoint at line {line}: {e}").unwrap();
+                    true
+                }
+            },
+        };
+
+        if is_hit {
+            self.breakpoints.entry(line).and_modify(|x| x.hits += 1);
+        }
+
+        return is_hit;
+    }
+
+    /// `LINE` or `LINE if EXPR`, condition is parsed right away, so a typo is reported before running
+    fn set_breakpoint(&mut self, argument: &str) {
+        let (line, condition) = argument.split_once(" if ").map_or((argument, None), |(line, condition)| (line.trim(), Some(condition.trim())));
+
+        let Ok(line) = line.parse::<usize>() else {
+            writeln!(self.output, "Expected line number, but got '{line}'").unwrap();
+            return;
+        };
+
+        if let Some(Err(e)) = condition.map(Parser::parse_code_to_ast) {
+            writeln!(self.output, "Error: {e}").unwrap();
+            return;
+        }
+
+        self.breakpoints.insert(line, Breakpoint { condition: condition.map(|x| x.to_string()), hits: 0 });
+    }
+
+    fn print_breakpoints(&mut self) {
+        let breakpoints: Vec<String> = self.breakpoints.iter()
+            .map(|(line, breakpoint)| match &breakpoint.condition {
+                Some(condition) => format!("{line} if {condition} (hits: {})", breakpoint.hits),
+                None => format!("{line} (hits: {})", breakpoint.hits),
+            })
+            .collect();
+
+        writeln!(self.output, "Breakpoints: {}", breakpoints.join(", ")).unwrap();
+    }
+
+    fn print_watches(&mut self, interpreter: &Interpreter) {
+        for (index, expression) in self.watches.clone().iter().enumerate() {
+            write!(self.output, "watch {}: {expression} = ", index + 1).unwrap();
+            self.evaluate(interpreter, expression);
+        }
+    }
+
+    fn show_location(&mut self, span: &TextSpan) {
+        let file_path = self.file_path.as_str();
+
+        Report::build(ReportKind::Custom("Paused", Color::Cyan), file_path, span.start.row)
+            .with_message(format!("line {}", span.start.line + 1))
+            .with_config(Config::default().with_color(self.display == DisplayMode::Colored))
+            .with_label(Label::new((file_path, span.start.row..span.end.row)).with_message("next statement").with_color(Color::Cyan))
+            .finish()
+            .write((file_path, Source::from(&self.source)), &mut self.output)
+            .unwrap();
+    }
+
+    /// Prints variables visible from the current scope, innermost first, shadowed ones are skipped
+    fn print_variables(&mut self, interpreter: &Interpreter) {
+        let mut environment = Some(Rc::clone(&interpreter.environment.borrow()));
+        let mut printed_names = HashSet::new();
+
+        while let Some(scope) = environment {
+            for (name, value) in scope.borrow().variables() {
+                if printed_names.insert(name.clone()) {
+                    writeln!(self.output, "{name} = {}", value.display(self.display)).unwrap();
+                }
+            }
+
+            environment = scope.borrow().get_parent();
+        }
+    }
+
+    fn evaluate(&mut self, interpreter: &Interpreter, expression: &str) {
+        match interpreter.run_source(expression) {
+            Ok(value) => writeln!(self.output, "{}", value.unwrap_or(JsValue::Undefined).display(self.display)).unwrap(),
+            Err(e) => writeln!(self.output, "Error: {e}").unwrap(),
+        }
+    }
+
+    /// Reads commands until one of them resumes the script, end of input resumes it without further pauses
+    fn run_commands(&mut self, interpreter: &Interpreter) {
+        loop {
+            write!(self.output, "(debug) ").unwrap();
+            self.output.flush().unwrap();
+
+            let mut line = String::new();
+
+            if self.input.read_line(&mut line).unwrap_or(0) == 0 {
+                self.is_stepping = false;
+                self.breakpoints.clear();
+                return;
+            }
+
+            let line = line.trim();
+            let (command, argument) = line.split_once(' ').map_or((line, ""), |(command, argument)| (command, argument.trim()));
+
+            match command {
+                "" | "s" | "step" => {
+                    self.is_stepping = true;
+                    return;
+                }
+                "c" | "continue" => {
+                    self.is_stepping = false;
+                    return;
+                }
+                "b" | "break" if argument.is_empty() => self.print_breakpoints(),
+                "b" | "break" => self.set_breakpoint(argument),
+                "d" | "delete" => match argument.parse::<usize>() {
+                    Ok(line) => {
+                        self.breakpoints.remove(&line);
+                    }
+                    Err(_) => writeln!(self.output, "Expected line number, but got '{argument}'").unwrap(),
+                },
+                "w" | "watch" if argument.is_empty() => self.print_watches(interpreter),
+                "w" | "watch" => {
+                    self.watches.push(argument.to_string());
+                    self.print_watches(interpreter);
+                }
+                "uw" | "unwatch" => match argument.parse::<usize>() {
+                    Ok(number) if number >= 1 && number <= self.watches.len() => {
+                        self.watches.remove(number - 1);
+                    }
+                    _ => writeln!(self.output, "Expected number of a watch, but got '{argument}'").unwrap(),
+                },
+                "v" | "vars" => self.print_variables(interpreter),
+                "p" | "print" => self.evaluate(interpreter, argument),
+                "q" | "quit" => std::process::exit(0),
+                "h" | "help" => writeln!(self.output, "{HELP}").unwrap(),
+                _ => writeln!(self.output, "Unknown command '{command}', type h for help").unwrap(),
+            }
+        }
+    }
+}
+
+impl ExecutionHooks for Debugger {
+    fn before_statement(&mut self, interpreter: &Interpreter, statement: &AstStatement) {
+        if !self.should_pause(interpreter, statement) {
+            return;
+        }
+
+        self.show_location(&statement.get_span());
+        self.print_watches(interpreter);
+        self.run_commands(interpreter);
+    }
+}
+
+#[test]
+fn debugger_stops_on_breakpoints_and_changes_variables() {
+    use std::cell::RefCell;
+    use std::io::Cursor;
+
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let source = "let total = 0;\nfor (let i = 0; i < 3; i += 1) {\n  total += i;\n}\ntotal;";
+    let output = Rc::new(RefCell::new(vec![]));
+    let commands = "b 3\nc\nv\nc\np total = 10\nd 3\nc\n";
+    let debugger = Debugger::new("test.js", source, DisplayMode::Plain, Box::new(Cursor::new(commands)), Box::new(SharedOutput(Rc::clone(&output))));
+
+    let interpreter = Interpreter::default();
+    interpreter.set_hooks(Box::new(debugger));
+    let result = interpreter.interpret(&Parser::parse_code_to_ast(source).unwrap()).unwrap();
+
+    // total is changed to 10 on the second iteration before 1 & 2 are added to it
+    assert_eq!(result, crate::value::JsValue::Number(13.0));
+
+    let output = String::from_utf8(output.borrow().clone()).unwrap();
+    assert_eq!(output.matches("(debug) ").count(), 7);
+    assert!(output.contains("(debug) i = 0\ntotal = 0\n"));
+    assert!(output.contains("(debug) 10\n"));
+}
+
+#[test]
+fn debugger_supports_conditional_breakpoints_and_watches() {
+    use std::cell::RefCell;
+    use std::io::Cursor;
+
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let source = "let total = 0;\nfor (let i = 0; i < 4; i += 1) {\n  total += i;\n}\ntotal;";
+    let output = Rc::new(RefCell::new(vec![]));
+    let commands = "b 3 if i ==\nb 3 if i >= 2\nc\nw total * 10\nb\nc\nuw 1\nc\n";
+    let debugger = Debugger::new("test.js", source, DisplayMode::Plain, Box::new(Cursor::new(commands)), Box::new(SharedOutput(Rc::clone(&output))));
+
+    let interpreter = Interpreter::default();
+    interpreter.set_hooks(Box::new(debugger));
+    let result = interpreter.interpret(&Parser::parse_code_to_ast(source).unwrap()).unwrap();
+    assert_eq!(result, crate::value::JsValue::Number(6.0));
+
+    let output = String::from_utf8(output.borrow().clone()).unwrap();
+    assert_eq!(output.matches("(debug) ").count(), 8);
+    assert!(output.contains("(debug) Error: "));
+    assert!(output.contains("(debug) watch 1: total * 10 = 10\n"));
+    assert!(output.contains("(debug) Breakpoints: 3 if i >= 2 (hits: 1)\n"));
+    // watch is printed when the breakpoint pauses again, before it's removed
+    assert!(output.contains("watch 1: total * 10 = 30\n(debug) "));
+}
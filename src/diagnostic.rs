@@ -1,10 +1,20 @@
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::symbol_checker::diagnostics::{ConstantAssigningDiagnostic, MultipleAssignmentDiagnostic, UnusedVariableDiagnostic, VariableNotDefinedDiagnostic, WrongBreakContextDiagnostic, WrongThisContextDiagnostic};
+use ariadne::{Color, Config, Label, Report, ReportKind, Source};
+use crate::parser::ParseError;
+use crate::scanner::TextSpan;
+use crate::symbol_checker::pragma::Suppression;
+use crate::symbol_checker::diagnostics::{ConstantAssigningDiagnostic, DuplicateKeyDiagnostic, InOperatorOnCollectionDiagnostic, MultipleAssignmentDiagnostic, ReadOnlyBuiltinDiagnostic, ShadowedBuiltinDiagnostic, UnusedVariableDiagnostic, VariableNotDefinedDiagnostic, WrongBreakContextDiagnostic, WrongContinueContextDiagnostic, WrongThisContextDiagnostic};
 
+/// Diagnostics of one or many sources, e.g. every file passed to `lint`.
+/// Each diagnostic remembers the file it was reported in, the same problem reported twice is kept once
 pub struct DiagnosticBag<'a> {
     pub warnings: Vec<Diagnostic<'a>>,
     pub errors: Vec<Diagnostic<'a>>,
+    /// Diagnostics matching any of them are dropped instead of being reported
+    suppressions: Vec<Suppression>,
+    /// File reported diagnostics are associated with
+    file: Option<Rc<str>>,
 }
 
 pub type DiagnosticBagRef<'a> = Rc<RefCell<DiagnosticBag<'a>>>;
@@ -14,15 +24,48 @@ impl<'a> DiagnosticBag<'a> {
         Self {
             warnings: vec![],
             errors: vec![],
+            suppressions: vec![],
+            file: None,
         }
     }
 
-    pub fn report_error(&mut self, diagnostic: Diagnostic<'a>) {
-        self.errors.push(diagnostic);
+    /// Associates diagnostics reported from now on with the file,
+    /// pragmas of the previous source no longer apply
+    pub fn begin_source(&mut self, file: &str) {
+        self.file = Some(file.into());
+        self.suppressions.clear();
     }
 
-    pub fn report_warning(&mut self, diagnostic: Diagnostic<'a>) {
-        self.warnings.push(diagnostic);
+    pub fn add_suppressions(&mut self, suppressions: Vec<Suppression>) {
+        self.suppressions.extend(suppressions);
+    }
+
+    fn is_suppressed(&self, diagnostic: &Diagnostic) -> bool {
+        let line = diagnostic.kind.span().start.line;
+        return self.suppressions.iter().any(|x| x.is_suppressed(diagnostic.kind.code(), line));
+    }
+
+    pub fn report_error(&mut self, mut diagnostic: Diagnostic<'a>) {
+        diagnostic.file = self.file.clone();
+
+        if !self.is_suppressed(&diagnostic) && !self.errors.contains(&diagnostic) {
+            self.errors.push(diagnostic);
+        }
+    }
+
+    pub fn report_warning(&mut self, mut diagnostic: Diagnostic<'a>) {
+        diagnostic.file = self.file.clone();
+
+        if !self.is_suppressed(&diagnostic) && !self.warnings.contains(&diagnostic) {
+            self.warnings.push(diagnostic);
+        }
+    }
+
+    /// Orders errors & warnings by file, then by position in it
+    pub fn sort(&mut self) {
+        let key = |x: &Diagnostic| (x.file.clone(), x.kind.span().start.line, x.kind.span().start.row);
+        self.errors.sort_by_key(key);
+        self.warnings.sort_by_key(key);
     }
 }
 
@@ -34,12 +77,61 @@ pub enum DiagnosticKind {
     MultipleAssignment(MultipleAssignmentDiagnostic),
     WrongThisContext(WrongThisContextDiagnostic),
     WrongBreakContext(WrongBreakContextDiagnostic),
+    WrongContinueContext(WrongContinueContextDiagnostic),
+    DuplicateKey(DuplicateKeyDiagnostic),
+    ShadowedBuiltin(ShadowedBuiltinDiagnostic),
+    /// Assignment to a builtin global without `--mutable-globals`
+    ReadOnlyBuiltin(ReadOnlyBuiltinDiagnostic),
+    InOperatorOnCollection(InOperatorOnCollectionDiagnostic),
+}
+
+impl DiagnosticKind {
+    /// Name used to refer to the diagnostic in pragma comments, e.g. `// rustjs-disable-next-line unused-variable`
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiagnosticKind::UnusedVariable(_) => "unused-variable",
+            DiagnosticKind::ConstantAssigning(_) => "constant-assigning",
+            DiagnosticKind::VariableNotDefined(_) => "variable-not-defined",
+            DiagnosticKind::MultipleAssignment(_) => "multiple-assignment",
+            DiagnosticKind::WrongThisContext(_) => "wrong-this-context",
+            DiagnosticKind::WrongBreakContext(_) => "wrong-break-context",
+            DiagnosticKind::WrongContinueContext(_) => "wrong-continue-context",
+            DiagnosticKind::DuplicateKey(_) => "duplicate-key",
+            DiagnosticKind::ShadowedBuiltin(_) => "shadowed-builtin",
+            DiagnosticKind::ReadOnlyBuiltin(_) => "read-only-builtin",
+            DiagnosticKind::InOperatorOnCollection(_) => "in-operator-on-collection",
+        }
+    }
+
+    pub fn span(&self) -> &TextSpan {
+        match self {
+            DiagnosticKind::UnusedVariable(diagnostic) => &diagnostic.id_span,
+            DiagnosticKind::ConstantAssigning(diagnostic) => &diagnostic.id_span,
+            DiagnosticKind::VariableNotDefined(diagnostic) => &diagnostic.id_span,
+            DiagnosticKind::MultipleAssignment(diagnostic) => &diagnostic.id_span,
+            DiagnosticKind::WrongThisContext(diagnostic) => &diagnostic.span,
+            DiagnosticKind::WrongBreakContext(diagnostic) => &diagnostic.span,
+            DiagnosticKind::WrongContinueContext(diagnostic) => &diagnostic.span,
+            DiagnosticKind::DuplicateKey(diagnostic) => &diagnostic.key_span,
+            DiagnosticKind::ShadowedBuiltin(diagnostic) => &diagnostic.id_span,
+            DiagnosticKind::ReadOnlyBuiltin(diagnostic) => &diagnostic.id_span,
+            DiagnosticKind::InOperatorOnCollection(diagnostic) => &diagnostic.span,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Diagnostic<'a> {
     kind: DiagnosticKind,
-    source: &'a str
+    source: &'a str,
+    file: Option<Rc<str>>,
+}
+
+impl PartialEq for Diagnostic<'_> {
+    /// Same problem at the same place of the same file
+    fn eq(&self, other: &Self) -> bool {
+        self.file == other.file && self.kind.code() == other.kind.code() && self.kind.span() == other.kind.span()
+    }
 }
 
 impl<'a> Diagnostic<'a> {
@@ -47,21 +139,60 @@ impl<'a> Diagnostic<'a> {
         Self {
             kind,
             source,
+            file: None,
         }
     }
 
-    pub fn print_diagnostic(&self) {
+    pub fn kind(&self) -> &DiagnosticKind {
+        &self.kind
+    }
+
+    /// File the diagnostic was reported in, `None` if the bag was used without `begin_source`
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    pub fn print_diagnostic(&self, filename: &str) {
         match &self.kind {
-            DiagnosticKind::UnusedVariable(diagnostic) => diagnostic.print_diagnostic(self.source),
-            DiagnosticKind::ConstantAssigning(diagnostic) => diagnostic.print_diagnostic(self.source),
-            DiagnosticKind::VariableNotDefined(diagnostic) => diagnostic.print_diagnostic(self.source),
-            DiagnosticKind::MultipleAssignment(diagnostic) => diagnostic.print_diagnostic(self.source),
-            DiagnosticKind::WrongThisContext(diagnostic) => diagnostic.print_diagnostic(self.source),
-            DiagnosticKind::WrongBreakContext(diagnostic) => diagnostic.print_diagnostic(self.source),
+            DiagnosticKind::UnusedVariable(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::ConstantAssigning(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::VariableNotDefined(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::MultipleAssignment(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::WrongThisContext(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::WrongBreakContext(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::WrongContinueContext(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::DuplicateKey(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::ShadowedBuiltin(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::ReadOnlyBuiltin(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
+            DiagnosticKind::InOperatorOnCollection(diagnostic) => diagnostic.print_diagnostic(self.source, filename),
         }
     }
 }
 
 pub trait PrintDiagnostic {
-    fn print_diagnostic(&self, source: &str);
+    fn print_diagnostic(&self, source: &str, filename: &str);
+}
+
+/// Prints error thrown while running a script with the excerpt of source where it happened
+pub fn print_runtime_error(message: &str, span: &TextSpan, filename: &str, source: &str, is_colored: bool) {
+    print_error_report(message, "thrown here", span, filename, source, is_colored);
+}
+
+/// Prints syntax error collected by error tolerant parsing
+pub fn print_parse_error(error: &ParseError, filename: &str, source: &str, is_colored: bool) {
+    print_error_report(&error.message, "syntax error", &error.span, filename, source, is_colored);
+}
+
+fn print_error_report(message: &str, label: &str, span: &TextSpan, filename: &str, source: &str, is_colored: bool) {
+    Report::build(ReportKind::Error, filename, span.start.row)
+        .with_config(Config::default().with_color(is_colored))
+        .with_message(message)
+        .with_label(
+            Label::new((filename, span.start.row..span.end.row))
+                .with_message(label)
+                .with_color(Color::Red),
+        )
+        .finish()
+        .eprint((filename, Source::from(source)))
+        .unwrap();
 }
@@ -0,0 +1,529 @@
+use std::fmt::{Display, Formatter};
+use crate::parser::Parser;
+use crate::scanner::{Scanner, TextSpan, Token, TokenKind};
+
+/// Quotes of string literals in formatted code
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum QuoteStyle {
+    #[default]
+    Preserve,
+    Double,
+    Single,
+}
+
+impl QuoteStyle {
+    pub fn parse(style: &str) -> Result<Self, String> {
+        match style {
+            "preserve" => Ok(Self::Preserve),
+            "double" => Ok(Self::Double),
+            "single" => Ok(Self::Single),
+            _ => Err(format!("Unknown quote style '{style}', expected preserve, double or single")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// Spaces per nesting level
+    pub indent_width: usize,
+    /// Strings containing the preferred quote keep their quotes, there are no escape sequences to rewrite them
+    pub quotes: QuoteStyle,
+    /// Statements which end at a line break without `;` get one
+    pub insert_semicolons: bool,
+    /// Lines longer than that are broken after commas & binary operators, 0 disables breaking
+    pub max_line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            quotes: QuoteStyle::Preserve,
+            insert_semicolons: true,
+            max_line_width: 100,
+        }
+    }
+}
+
+/// Source which can't be formatted, e.g. because of a syntax error
+#[derive(Debug, Clone, PartialEq)]
+pub struct FmtError {
+    pub message: String,
+    pub span: TextSpan,
+}
+
+impl Display for FmtError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at line {}", self.message, self.span.start.line + 1)
+    }
+}
+
+/// Formats source token by token, so comments are kept & nothing but whitespace, semicolons & quotes changes.
+/// Editors & CI can call it directly instead of running the CLI
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, FmtError> {
+    let (_, parse_errors) = Parser::default().parse_tolerant(source);
+
+    if let Some(error) = parse_errors.into_iter().next() {
+        return Err(FmtError { message: error.message, span: error.span });
+    }
+
+    let mut formatter = TokenFormatter::new(source, options);
+    let mut scanner = Scanner::new(source.to_string());
+
+    while let Some(token) = scanner.next_token() {
+        formatter.push(token);
+    }
+
+    return Ok(formatter.finish());
+}
+
+/// Bracket written to the output which isn't closed yet
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Open {
+    /// `is_header` is set for conditions of `if`, `while`, `for`, `switch` & `catch`, a statement doesn't end after them
+    Paren { is_for: bool, is_header: bool },
+    Square,
+    /// Statements, e.g. body of a function or of an `if`
+    Block { is_do: bool },
+    /// Object literal is multiline when its first token starts on a new line in the source, unknown until then
+    Object { is_multiline: Option<bool> },
+    /// Statements of a `case` clause, indented under it
+    Case,
+}
+
+struct TokenFormatter<'a> {
+    source: Vec<char>,
+    options: &'a FormatOptions,
+    output: String,
+    open: Vec<Open>,
+    level: usize,
+    /// Last written token which isn't a comment
+    prev: Option<TokenKind>,
+    /// Bracket closed by the previous token
+    prev_closed: Option<Open>,
+    is_prev_unary: bool,
+    /// Line of the source the last written token or comment ends on, blank lines between statements are kept
+    last_line: usize,
+    is_newline_pending: bool,
+    /// Comments since the last token with their lines & whether they follow the token on its line,
+    /// they are written once it's known whether the statement before them needs a semicolon
+    comments: Vec<(String, usize, bool)>,
+    ternary_depth: usize,
+    is_case_pending: bool,
+    is_last_colon_of_case: bool,
+}
+
+impl<'a> TokenFormatter<'a> {
+    fn new(source: &str, options: &'a FormatOptions) -> Self {
+        let mut output = String::new();
+
+        // scanner skips shebang line, it's kept as is
+        if source.starts_with("#!") {
+            output += source.lines().next().unwrap_or_default();
+        }
+
+        Self {
+            source: source.chars().collect(),
+            options,
+            is_newline_pending: !output.is_empty(),
+            output,
+            open: vec![],
+            level: 0,
+            prev: None,
+            prev_closed: None,
+            is_prev_unary: false,
+            last_line: 0,
+            comments: vec![],
+            ternary_depth: 0,
+            is_case_pending: false,
+            is_last_colon_of_case: false,
+        }
+    }
+
+    fn push(&mut self, token: Token) {
+        if let TokenKind::Comment(text) = &token.token {
+            let is_trailing = self.prev.is_some() && !token.flags.is_after_line_break;
+            self.comments.push((format!("//{text}"), token.span.start.line, is_trailing));
+            return;
+        }
+
+        if let Some(Open::Object { is_multiline: None }) = self.open.last() {
+            let is_multiline = token.flags.is_after_line_break && token.token != TokenKind::CloseBrace;
+            self.open.pop();
+            self.open.push(Open::Object { is_multiline: Some(is_multiline) });
+
+            if is_multiline {
+                self.level += 1;
+                self.is_newline_pending = true;
+            }
+        }
+
+        if self.is_statement_start(&token) {
+            if self.options.insert_semicolons {
+                self.output.push(';');
+                self.prev = Some(TokenKind::Semicolon);
+            }
+
+            self.is_newline_pending = true;
+        }
+
+        self.flush_comments();
+
+        if matches!(token.token, TokenKind::CaseKeyword | TokenKind::DefaultKeyword) && self.open.last() == Some(&Open::Case) {
+            self.open.pop();
+            self.level -= 1;
+        }
+
+        let closed = match token.token {
+            TokenKind::CloseBrace | TokenKind::CloseParen | TokenKind::CloseSquareBracket => self.close(&token.token),
+            _ => None,
+        };
+
+        let text = self.token_text(&token);
+        self.write_separator(&token, &text);
+        self.output += &text;
+        self.after(&token);
+
+        if matches!(closed, Some(Open::Block { .. })) {
+            self.is_newline_pending = true;
+        }
+
+        self.prev_closed = closed;
+        self.prev = Some(token.token);
+        self.last_line = token.span.end.line;
+    }
+
+    fn finish(mut self) -> String {
+        if self.options.insert_semicolons && self.is_statement_level() && self.is_statement_end() {
+            self.output.push(';');
+        }
+
+        self.flush_comments();
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+
+        return self.output;
+    }
+
+    /// Text of the token in the source, strings get the preferred quotes
+    fn token_text(&self, token: &Token) -> String {
+        let text: String = self.source[token.span.start.row..token.span.end.row].iter().collect();
+
+        let quote = match self.options.quotes {
+            QuoteStyle::Preserve => return text,
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+        };
+
+        match &token.token {
+            TokenKind::String(value) if !value.contains(quote) => format!("{quote}{value}{quote}"),
+            _ => text,
+        }
+    }
+
+    /// Pops the bracket closed by the token, closing `}` of a block goes to its own line
+    fn close(&mut self, token: &TokenKind) -> Option<Open> {
+        if *token == TokenKind::CloseBrace && self.open.last() == Some(&Open::Case) {
+            self.open.pop();
+            self.level -= 1;
+        }
+
+        let closed = self.open.pop();
+
+        if matches!(closed, Some(Open::Block { .. } | Open::Object { is_multiline: Some(true) })) {
+            self.level = self.level.saturating_sub(1);
+            self.is_newline_pending = self.prev != Some(TokenKind::OpenBrace);
+        }
+
+        return closed;
+    }
+
+    fn write_separator(&mut self, token: &Token, text: &str) {
+        if self.is_newline_pending && self.joins_closed_block(&token.token) {
+            self.is_newline_pending = false;
+        }
+
+        if self.is_newline_pending {
+            self.is_newline_pending = false;
+            self.start_line(token.span.start.line, token.token != TokenKind::CloseBrace);
+            return;
+        }
+
+        if !self.needs_space(token) {
+            return;
+        }
+
+        let max_line_width = self.options.max_line_width;
+
+        if max_line_width > 0 && self.line_width() + 1 + text.chars().count() > max_line_width && self.is_break_allowed() {
+            self.output.push('\n');
+            self.write_indent(self.level + 1);
+            return;
+        }
+
+        self.output.push(' ');
+    }
+
+    /// Starts a new line of the output, a blank line of the source before it is kept
+    fn start_line(&mut self, line: usize, is_blank_line_kept: bool) {
+        if self.output.is_empty() {
+            return;
+        }
+
+        self.output.push('\n');
+
+        if is_blank_line_kept && line > self.last_line + 1 {
+            self.output.push('\n');
+        }
+
+        self.write_indent(self.level);
+    }
+
+    fn write_indent(&mut self, level: usize) {
+        self.output += &" ".repeat(level * self.options.indent_width);
+    }
+
+    fn line_width(&self) -> usize {
+        let line_start = self.output.rfind('\n').map_or(0, |x| x + 1);
+        self.output[line_start..].chars().count()
+    }
+
+    fn flush_comments(&mut self) {
+        for (text, line, is_trailing) in std::mem::take(&mut self.comments) {
+            if is_trailing {
+                self.output.push(' ');
+            } else {
+                self.start_line(line, true);
+            }
+
+            self.output += &text;
+            self.last_line = line;
+            self.is_newline_pending = true;
+            self.prev_closed = None;
+        }
+    }
+
+    /// Tokens which continue the line of a closing `}`, e.g. `} else {` or `})`
+    fn joins_closed_block(&self, token: &TokenKind) -> bool {
+        if self.prev != Some(TokenKind::CloseBrace) || !self.comments.is_empty() {
+            return false;
+        }
+
+        match token {
+            TokenKind::ElseKeyword | TokenKind::CatchKeyword => true,
+            TokenKind::WhileKeyword => self.prev_closed == Some(Open::Block { is_do: true }),
+            TokenKind::CloseParen | TokenKind::CloseSquareBracket | TokenKind::Comma | TokenKind::Semicolon | TokenKind::Dot => {
+                !self.is_statement_level()
+            }
+            _ => false,
+        }
+    }
+
+    fn needs_space(&self, token: &Token) -> bool {
+        let Some(prev) = &self.prev else {
+            return false;
+        };
+
+        match (prev, &token.token) {
+            (_, TokenKind::Semicolon | TokenKind::Comma | TokenKind::CloseParen | TokenKind::CloseSquareBracket | TokenKind::Dot) => false,
+            (TokenKind::OpenParen | TokenKind::OpenSquareBracket | TokenKind::Dot | TokenKind::DotDotDot, _) => false,
+            (TokenKind::OpenBrace, TokenKind::CloseBrace) => false,
+            (_, TokenKind::Colon) => self.ternary_depth > 0 && !self.is_case_pending,
+            (TokenKind::FunctionKeyword, TokenKind::OpenParen) => false,
+            (prev, TokenKind::OpenParen | TokenKind::OpenSquareBracket | TokenKind::PlusPlus | TokenKind::MinusMinus) if prev.ends_operand() => false,
+            _ => !self.is_prev_unary,
+        }
+    }
+
+    /// Long line can be broken after the previous token
+    fn is_break_allowed(&self) -> bool {
+        !self.is_prev_unary && matches!(
+            self.prev,
+            Some(TokenKind::Comma | TokenKind::And | TokenKind::Or | TokenKind::Plus | TokenKind::Minus | TokenKind::Mul
+                | TokenKind::Div | TokenKind::Percent | TokenKind::MulMul | TokenKind::Equality | TokenKind::Inequality
+                | TokenKind::LessThan | TokenKind::LessThanOrEqual | TokenKind::MoreThan | TokenKind::MoreThanOrEqual
+                | TokenKind::Equal | TokenKind::Question)
+        )
+    }
+
+    fn is_statement_level(&self) -> bool {
+        matches!(self.open.last(), None | Some(Open::Block { .. } | Open::Case))
+    }
+
+    /// Previous token can end a statement, e.g. `a = b` or `return`, but not `if (a)`
+    fn is_statement_end(&self) -> bool {
+        match &self.prev {
+            Some(TokenKind::CloseBrace) => matches!(self.prev_closed, Some(Open::Object { .. })),
+            Some(TokenKind::CloseParen) => !matches!(self.prev_closed, Some(Open::Paren { is_header: true, .. })),
+            Some(TokenKind::BreakKeyword | TokenKind::ContinueKeyword | TokenKind::ReturnKeyword) => true,
+            Some(prev) => prev.ends_operand() && !self.is_prev_unary,
+            None => false,
+        }
+    }
+
+    /// Token starts a new statement after one without `;`, like automatic semicolon insertion of JS
+    fn is_statement_start(&self, token: &Token) -> bool {
+        if !self.is_statement_level() || !self.is_statement_end() {
+            return false;
+        }
+
+        if token.token == TokenKind::CloseBrace {
+            return true;
+        }
+
+        let continues_statement = matches!(
+            token.token,
+            TokenKind::Dot | TokenKind::Comma | TokenKind::Question | TokenKind::Colon | TokenKind::Semicolon
+                | TokenKind::OpenParen | TokenKind::OpenSquareBracket | TokenKind::OpenBrace
+                | TokenKind::CloseParen | TokenKind::CloseSquareBracket
+                | TokenKind::Plus | TokenKind::Minus | TokenKind::Mul | TokenKind::Div | TokenKind::Percent | TokenKind::MulMul
+                | TokenKind::And | TokenKind::Or | TokenKind::BitwiseAnd | TokenKind::BitwiseOr
+                | TokenKind::Equality | TokenKind::Inequality | TokenKind::LessThan | TokenKind::LessThanOrEqual
+                | TokenKind::MoreThan | TokenKind::MoreThanOrEqual
+                | TokenKind::Equal | TokenKind::PlusEqual | TokenKind::MinusEqual | TokenKind::MulEqual | TokenKind::DivEqual
+                | TokenKind::PercentEqual | TokenKind::MulMulEqual
+                | TokenKind::InstanceOfKeyword | TokenKind::InKeyword | TokenKind::ElseKeyword
+        );
+
+        return token.flags.is_after_line_break && !continues_statement;
+    }
+
+    fn is_block_start(&self) -> bool {
+        match &self.prev {
+            None => true,
+            Some(TokenKind::Colon) => self.is_last_colon_of_case,
+            Some(prev) => matches!(
+                prev,
+                TokenKind::CloseParen | TokenKind::Identifier(_) | TokenKind::Semicolon | TokenKind::OpenBrace
+                    | TokenKind::CloseBrace | TokenKind::ElseKeyword | TokenKind::DoKeyword | TokenKind::TryKeyword
+            ),
+        }
+    }
+
+    /// Updates nesting & layout state once the token is written
+    fn after(&mut self, token: &Token) {
+        match token.token {
+            TokenKind::OpenParen => {
+                let is_header = matches!(
+                    self.prev,
+                    Some(TokenKind::IfKeyword | TokenKind::WhileKeyword | TokenKind::ForKeyword | TokenKind::SwitchKeyword | TokenKind::CatchKeyword)
+                );
+                self.open.push(Open::Paren { is_for: self.prev == Some(TokenKind::ForKeyword), is_header });
+            }
+            TokenKind::OpenSquareBracket => self.open.push(Open::Square),
+            TokenKind::OpenBrace if self.is_block_start() => {
+                self.open.push(Open::Block { is_do: self.prev == Some(TokenKind::DoKeyword) });
+                self.level += 1;
+                self.is_newline_pending = true;
+            }
+            TokenKind::OpenBrace => self.open.push(Open::Object { is_multiline: None }),
+            TokenKind::Semicolon => {
+                self.is_newline_pending = !matches!(self.open.last(), Some(Open::Paren { is_for: true, .. }));
+            }
+            TokenKind::Comma => {
+                self.is_newline_pending = self.open.last() == Some(&Open::Object { is_multiline: Some(true) });
+            }
+            TokenKind::CaseKeyword | TokenKind::DefaultKeyword => self.is_case_pending = true,
+            TokenKind::Question => self.ternary_depth += 1,
+            TokenKind::Colon => {
+                self.is_last_colon_of_case = self.is_case_pending;
+
+                if self.is_case_pending {
+                    self.is_case_pending = false;
+                    self.open.push(Open::Case);
+                    self.level += 1;
+                    self.is_newline_pending = true;
+                } else if self.ternary_depth > 0 {
+                    self.ternary_depth -= 1;
+                }
+            }
+            _ => {}
+        }
+
+        // prefix operators stick to their operand, `regex allowed` means nothing before them ends an operand
+        self.is_prev_unary = match token.token {
+            TokenKind::Exclamatory => true,
+            TokenKind::Minus | TokenKind::Plus | TokenKind::PlusPlus | TokenKind::MinusMinus => token.flags.is_regex_allowed,
+            _ => false,
+        };
+    }
+}
+
+#[test]
+fn source_is_formatted_by_tokens() {
+    let source = "\
+// counts items
+let items=[1,2,-3]
+function sum(list){let total=0
+  for(let i=0;i<3;i=i+1){total+=list[i]} // running total
+
+
+return total}
+if(sum(items)>0){console.log('positive')}else{console.log(\"not\")}
+let config = {
+  name: 'a', nested: {x: 1}
+}
+switch (items[0]) {
+case 1: sum(items)
+default: break
+}
+";
+
+    let formatted = format_source(source, &FormatOptions::default()).unwrap();
+
+    assert_eq!(formatted, "\
+// counts items
+let items = [1, 2, -3];
+function sum(list) {
+    let total = 0;
+    for (let i = 0; i < 3; i = i + 1) {
+        total += list[i];
+    } // running total
+
+    return total;
+}
+if (sum(items) > 0) {
+    console.log('positive');
+} else {
+    console.log(\"not\");
+}
+let config = {
+    name: 'a',
+    nested: { x: 1 }
+};
+switch (items[0]) {
+    case 1:
+        sum(items);
+    default:
+        break;
+}
+");
+
+    let print = |code: &str| crate::node::Printer::new(2).print(&Parser::parse_code_to_ast(code).unwrap());
+    assert_eq!(print(&formatted), print(source));
+    assert_eq!(format_source(&formatted, &FormatOptions::default()).unwrap(), formatted);
+}
+
+#[test]
+fn format_options_are_applied() {
+    let options = FormatOptions {
+        indent_width: 2,
+        quotes: QuoteStyle::Double,
+        insert_semicolons: false,
+        max_line_width: 30,
+    };
+    let source = "let s = 'a' + 'b\"c'\nfunction f() { return g(first, second, third, fourth) }";
+
+    assert_eq!(format_source(source, &options).unwrap(), "\
+let s = \"a\" + 'b\"c'
+function f() {
+  return g(first, second,
+    third, fourth)
+}
+");
+
+    let error = format_source("let = 1;", &options).unwrap_err();
+    assert_eq!(error.to_string(), "Identifier is missing in variable declaration at line 1");
+}
@@ -1,730 +1,2763 @@
-use std::cell::RefCell;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
-use crate::interpreter::environment::{Environment, EnvironmentRef};
-use crate::nodes::{AstExpression, AstStatement, FunctionArgument};
-use crate::value::function::{Callable, JsFunction, JsFunctionArg};
-use crate::value::JsValue;
-use crate::value::object::{JsObject, ObjectKind};
+use crate::interpreter::coverage::Coverage;
+use crate::interpreter::engine::BACKEND_NAME;
+use crate::interpreter::environment::{Environment, EnvironmentRef, GlobalProvider};
+use crate::interpreter::hooks::{ExecutionHooks, YieldAction, YieldCallback, YieldPoint};
+use crate::interpreter::intrinsics::Intrinsics;
+use crate::interpreter::locale::{format_date, Locale};
+use crate::interpreter::random::Random;
+use crate::node::Printer;
+use crate::nodes::{static_property_key, AstExpression, AstStatement, FunctionArgument, GetSpan, TextSpan};
+use crate::value::function::{Callable, JsFunction, JsFunctionArg, OrdinaryFunction};
+use crate::value::{DisplayLimits, DisplayMode, JsValue, number_exponentiation};
+use crate::value::object::{array_index, is_valid_array_length, JsObject, JsObjectRef, ObjectKind, PrimitiveHint, LENGTH_PROPERTY, PROTO_PROPERTY};
+use crate::value::proxy::JsProxy;
+
+/// How the last executed statement finished, `break`, `continue` & `return` are abrupt completions.
+/// Statement lists stop at an abrupt completion, it's consumed by the enclosing loop, switch or function
+#[derive(Debug, Clone, PartialEq)]
+pub enum Completion {
+    Normal,
+    Break,
+    Continue,
+    Return(JsValue),
+    /// `return f(...)` of an ordinary function, the call is made by the returning function after its body is left,
+    /// so self recursion reuses the frame instead of growing the stack
+    TailCall { function: JsValue, this: JsValue, arguments: Vec<JsValue> },
+}
+
+/// Global bindings & random generator state at some point, see `Interpreter::snapshot`
+#[derive(Debug, Clone)]
+pub struct InterpreterSnapshot {
+    environment: Environment,
+    global_properties: HashMap<String, JsValue>,
+    random: Random,
+}
+
+/// What happens when an array is indexed with a fractional number or NaN, e.g. `items[i / 2]`.
+/// Such an index is used as an ordinary property key like `"1.5"`, which is usually a bug
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum FractionalIndexCheck {
+    #[default]
+    Allow,
+    /// Warning is printed to stderr & the access proceeds
+    Warn,
+    /// Access throws a RangeError
+    Deny,
+}
+
+impl FractionalIndexCheck {
+    pub fn parse(check: &str) -> Result<Self, String> {
+        match check {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(format!("Unknown fractional index check '{check}', expected allow, warn or deny")),
+        }
+    }
+}
+
+/// Path & code of a script, errors are shown with an excerpt of the script where the failed node is
+#[derive(Debug, PartialEq)]
+pub struct ScriptSource {
+    pub path: String,
+    pub code: String,
+}
+
+/// Span of the node where evaluation failed & the script it belongs to, which is unknown for code run
+/// without a source, e.g. by `eval`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorLocation {
+    pub span: TextSpan,
+    pub source: Option<Rc<ScriptSource>>,
+}
 
 pub struct Interpreter {
     pub environment: RefCell<EnvironmentRef>,
+    coverage: RefCell<Option<Coverage>>,
+    random: RefCell<Random>,
+    display_mode: Cell<DisplayMode>,
+    display_limits: Cell<DisplayLimits>,
+    locale: Cell<Locale>,
+    /// Builtin globals & their properties are read only unless it's set
+    mutable_globals: Cell<bool>,
+    fractional_index_check: Cell<FractionalIndexCheck>,
+    /// Arithmetic on operands of different types throws instead of converting them
+    strict_arithmetic: Cell<bool>,
+    intrinsics: Intrinsics,
+    /// Script being executed, functions remember the one they are declared in
+    source: RefCell<Option<Rc<ScriptSource>>>,
+    /// Innermost node which failed during the last `interpret` call
+    error_location: RefCell<Option<ErrorLocation>>,
+    completion: RefCell<Completion>,
+    /// Number of ordinary function bodies being executed, tail calls are made only inside of them
+    function_depth: Cell<usize>,
+    hooks: RefCell<Option<Box<dyn ExecutionHooks>>>,
+    yield_point: RefCell<Option<YieldPoint>>,
 }
 
 impl Interpreter {
+    /// Interpreter with builtins & additional globals, e.g. natives provided by an embedder
+    pub fn with_globals<T: Into<HashMap<String, JsValue>>>(globals: T) -> Self {
+        let interpreter = Self::default();
+        let global_object = interpreter.global_environment().borrow().get_global_object();
+
+        if let Some(global_object) = global_object {
+            // globals of the host may replace builtin ones, which are read only for scripts
+            interpreter.mutable_globals.set(true);
+
+            for (key, value) in globals.into() {
+                interpreter.set_object_property(&global_object, &key, value)
+                    .expect("Global object should accept globals of the host");
+            }
+
+            interpreter.mutable_globals.set(false);
+        }
+
+        return interpreter;
+    }
+
+    /// Registers a global, e.g. a heavy builtin, which is created only when a script accesses it
+    pub fn define_lazy_global(&self, name: &str, provider: GlobalProvider) {
+        self.global_environment().borrow_mut().define_lazy_global(name, provider);
+    }
+
     pub fn interpret(&self, statement: &AstStatement) -> Result<JsValue, String> {
-        statement.execute(self)
+        self.run_script(|| statement.execute(self))
     }
 
-    pub fn set_environment(&self, environment: Environment) {
-        self.environment.replace(Rc::new(RefCell::new(environment)));
+    /// Runs a program & returns its completion value, `None` if it has none, e.g. it only declares something.
+    /// `on_result` is called with every top level statement producing a value
+    pub fn interpret_program(
+        &self,
+        statement: &AstStatement,
+        on_result: &mut dyn FnMut(&AstStatement, &JsValue),
+    ) -> Result<Option<JsValue>, String> {
+        match statement {
+            AstStatement::ProgramStatement(program) => self.run_script(|| program.completion_value(self, on_result)),
+            statement => self.interpret(statement).map(Some),
+        }
     }
 
-    pub(crate) fn create_new_environment(&self) -> Environment {
-        return Environment::new(Rc::clone(&self.environment.borrow().clone()));
+    fn run_script<T>(&self, run: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+        self.error_location.replace(None);
+
+        // evaluated code, e.g. by `eval` inside of a function, is not a function body
+        let function_depth = self.function_depth.replace(0);
+        let result = run();
+        self.function_depth.set(function_depth);
+
+        // top level `return` stops the script, it mustn't leak into the next one
+        self.completion.replace(Completion::Normal);
+        return result;
     }
 
-    pub(crate) fn pop_environment(&self) {
-        let parent_environment = self
-            .environment
-            .borrow()
-            .borrow()
-            .get_parent()
-            .unwrap()
-            .borrow()
-            .to_owned();
+    pub(crate) fn completion(&self) -> Completion {
+        self.completion.borrow().clone()
+    }
 
-        self.set_environment(parent_environment);
+    pub(crate) fn set_completion(&self, completion: Completion) {
+        self.completion.replace(completion);
     }
 
-    pub(crate) fn logical_or(&self, left: &JsValue, right: &JsValue) -> Result<JsValue, String> {
-        if left.to_bool() {
-            return Ok(left.clone());
+    /// Whether `break`, `continue` or `return` was executed & not handled yet
+    pub(crate) fn is_abrupt_completion(&self) -> bool {
+        *self.completion.borrow() != Completion::Normal
+    }
+
+    pub(crate) fn take_completion(&self) -> Completion {
+        self.completion.replace(Completion::Normal)
+    }
+
+    /// Consumes `break` & `continue` after an iteration of a loop body, returns whether the loop should stop.
+    /// `return` is left for the enclosing function, but stops the loop too
+    pub(crate) fn should_exit_loop(&self) -> bool {
+        match self.completion() {
+            Completion::Normal => false,
+            Completion::Break => {
+                self.take_completion();
+                true
+            }
+            Completion::Continue => {
+                self.take_completion();
+                false
+            }
+            Completion::Return(_) | Completion::TailCall { .. } => true,
         }
-        return Ok(right.clone());
     }
 
-    pub(crate) fn logical_and(&self, left: &JsValue, right: &JsValue) -> Result<JsValue, String> {
-        if !left.to_bool() {
-            return Ok(left.clone());
+    /// Runs body of an ordinary function in the given environment
+    pub(crate) fn execute_function_body(&self, environment: Environment, body: &AstStatement) -> Result<JsValue, String> {
+        self.function_depth.set(self.function_depth.get() + 1);
+        let result = self.with_environment(environment, |interpreter| body.execute(interpreter));
+        self.function_depth.set(self.function_depth.get() - 1);
+        return result;
+    }
+
+    pub(crate) fn is_inside_function(&self) -> bool {
+        self.function_depth.get() > 0
+    }
+
+    /// Remembers where evaluation failed, the error passes through all enclosing nodes,
+    /// so only the first, innermost one is kept
+    pub(crate) fn record_error_span(&self, node: &impl GetSpan) {
+        if self.error_location.borrow().is_none() {
+            let source = self.source.borrow().clone();
+            self.error_location.replace(Some(ErrorLocation { span: node.get_span(), source }));
         }
-        return Ok(right.clone());
     }
 
-    pub(crate) fn call_function(&self, callee: &AstExpression, arguments: &Vec<AstExpression>, is_new: bool) -> Result<JsValue, String> {
-        // println!("call_function {callee:?}");
-        let calleer = callee.execute(self)?;
+    /// Location of the error returned by the last `interpret` call
+    pub fn take_error_location(&self) -> Option<ErrorLocation> {
+        self.error_location.take()
+    }
 
-        // println!("calleer {calleer:?}");
+    /// Sets script which is executed next, functions declared by it are reported against it even if called by other scripts
+    pub fn set_source(&self, source: Option<Rc<ScriptSource>>) {
+        self.source.replace(source);
+    }
 
-        if let JsValue::Object(object) = &calleer {
-            if let ObjectKind::Function(function) = &object.borrow().kind {
-                let mut function_execution_environment = self.create_new_environment();
+    pub(crate) fn current_source(&self) -> Option<Rc<ScriptSource>> {
+        self.source.borrow().clone()
+    }
 
-                // println!("expr {callee:?}");
+    /// Runs code of another script, e.g. body of a function declared in it, the current script is restored afterwards
+    pub(crate) fn with_source<T>(&self, source: &Option<Rc<ScriptSource>>, run: impl FnOnce(&Self) -> T) -> T {
+        let previous = self.source.replace(source.clone());
+        let result = run(self);
+        self.source.replace(previous);
+        return result;
+    }
 
-                if let AstExpression::MemberExpression(expr) = &callee {
-                    function_execution_environment.set_context(expr.object.execute(self)?);
+    /// Property of a primitive value, looked up on its wrapper prototype like on a boxed object
+    pub(crate) fn get_primitive_property(&self, value: &JsValue, key: &str) -> JsValue {
+        let prototype = match value {
+            JsValue::String(string) => {
+                if key == "length" {
+                    return JsValue::Number(string.encode_utf16().count() as f64);
                 }
 
-                // TODO: refactor, ugly as hell
-                if is_new {
-                    function_execution_environment.set_context(JsObject::empty().into());
+                // indexes address UTF-16 code units like in JS, a half of a surrogate pair can't be
+                // stored in a Rust string, so it's replaced with U+FFFD
+                if let Some(index) = array_index(key) {
+                    return string.encode_utf16().nth(index as usize)
+                        .map_or(JsValue::Undefined, |x| JsValue::String(String::from_utf16_lossy(&[x]).into()));
                 }
 
-                let values: Vec<JsValue> = arguments
-                    .iter()
-                    .map(|param| param.execute(self).unwrap())
-                    .collect();
-
-                match function {
-                    JsFunction::Ordinary(function) => {
-                        function
-                            .arguments
-                            .iter()
-                            .zip(arguments)
-                            .for_each(|(arg, node)| {
-                                let value = node.execute(self).unwrap();
-
-                                function_execution_environment
-                                    .define_variable(arg.name.clone(), value, false)
-                                    .unwrap();
-                            });
-                        self.set_environment(function_execution_environment);
-                        let result = function.call(self, &values).unwrap();
-
-                        if let JsValue::Object(result_object) = &result {
-                            let proto = object.borrow().get_prototype();
-
-                            if let JsValue::Object(object) = proto {
-                                result_object.borrow_mut().set_proto(object);
-                            }
-                        }
-
-                        // println!("{result:?}");
-                        self.pop_environment();
-                        return Ok(result);
-                    }
-                    JsFunction::Native(function) => {
-                        self.set_environment(function_execution_environment);
-                        let result = function.call(self, &values);
-                        self.pop_environment();
-                        return result;
-                    }
-                }
+                &self.intrinsics.string_prototype
             }
-        }
+            JsValue::Number(_) => &self.intrinsics.number_prototype,
+            JsValue::Boolean(_) => &self.intrinsics.boolean_prototype,
+            _ => return JsValue::Undefined,
+        };
 
-        Err(format!("{} is not callable", calleer.get_type_as_str()))
+        return prototype.borrow().get_property_value(key);
     }
 
-    pub(crate) fn create_js_function(
-        &self,
-        function_arguments: &Vec<FunctionArgument>,
-        body: AstStatement,
-    ) -> JsFunction {
-        let mut arguments = Vec::with_capacity(function_arguments.len());
+    pub(crate) fn intrinsics(&self) -> &Intrinsics {
+        &self.intrinsics
+    }
 
-        for fn_arg_node in function_arguments {
-            let default_value = fn_arg_node
-                .default_value
-                .as_ref()
-                .map(|node| node.execute(self).unwrap())
-                .unwrap_or(JsValue::Undefined);
+    /// Selects how `console.log` prints values
+    pub fn set_display_mode(&self, mode: DisplayMode) {
+        self.display_mode.set(mode);
+    }
 
-            arguments.push(JsFunctionArg {
-                name: fn_arg_node.name.id.clone(),
-                default_value,
-            });
-        }
+    pub fn display_mode(&self) -> DisplayMode {
+        self.display_mode.get()
+    }
 
-        JsFunction::ordinary_function(
-            arguments,
-            Box::new(body.clone()),
-            self.environment.borrow().clone()
-        )
+    /// Selects how much of long strings & arrays `console.log` prints
+    pub fn set_display_limits(&self, limits: DisplayLimits) {
+        self.display_limits.set(limits);
     }
 
-    pub(crate) fn eval_member_expression_key(
-        &self,
-        node: &AstExpression,
-        computed: bool,
-    ) -> Result<String, String> {
-        if computed {
-            let computed_key = node.execute(self)?;
+    pub fn display_limits(&self) -> DisplayLimits {
+        self.display_limits.get()
+    }
 
-            return match computed_key {
-                JsValue::String(value) => Ok(value),
-                JsValue::Number(value) => Ok(value.to_string()),
-                _ => Err("".to_string()),
-            };
-        } else {
-            return match node {
-                AstExpression::StringLiteral(value) => Ok(value.value.clone()),
-                AstExpression::NumberLiteral(node) => Ok(node.value.to_string()),
-                AstExpression::Identifier(node) => Ok(node.id.clone()),
-                _ => Err("Object key should be an identifier".to_string()),
-            };
-        }
+    /// Selects separators of numbers formatted by `toLocaleString` without a locale argument
+    pub fn set_locale(&self, locale: Locale) {
+        self.locale.set(locale);
     }
-}
 
-pub trait Execute {
-    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String>;
-}
+    pub fn locale(&self) -> Locale {
+        self.locale.get()
+    }
 
-fn get_global_environment() -> Environment {
-    fn console_log(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
-        let result = arguments
-            .iter()
-            .map(|arg| format!("{}", arg))
-            .collect::<Vec<String>>()
-            .join(" ");
-        println!("{result}");
-        return Ok(JsValue::Undefined);
+    /// Lets scripts reassign builtin globals & properties of builtin objects, e.g. `Math.random = ...`
+    pub fn set_mutable_globals(&self, is_mutable: bool) {
+        self.mutable_globals.set(is_mutable);
     }
 
-    fn set_prototype(
-        _: &Interpreter,
-        arguments: &Vec<JsValue>,
-    ) -> Result<JsValue, String> {
-        let target = arguments
-            .get(0)
-            .expect("Expected first argument to be a target");
-
-        if let JsValue::Object(target_obj) = target {
-            let prototype = arguments
-                .get(1)
-                .expect("Expected second argument to be a prototype object");
-
-            if let JsValue::Object(prototype_obj) = prototype {
-                target_obj
-                    .borrow_mut()
-                    .set_proto(prototype_obj.clone());
-            } else {
-                return Err(format!(
-                    "Second arguments should be of type object, but got: {}",
-                    target.get_type_as_str()
-                ));
-            }
-        } else {
-            return Err(format!(
-                "First arguments should be of type object, but got: {}",
-                target.get_type_as_str()
-            ));
+    pub fn set_fractional_index_check(&self, check: FractionalIndexCheck) {
+        self.fractional_index_check.set(check);
+    }
+
+    /// Reports computed key of an array access which came from a fractional number or NaN, see [`FractionalIndexCheck`]
+    pub(crate) fn check_array_index(&self, object: &JsValue, key: &str) -> Result<(), String> {
+        let check = self.fractional_index_check.get();
+
+        if check == FractionalIndexCheck::Allow {
+            return Ok(());
         }
 
-        return Ok(JsValue::Undefined);
-    }
+        let is_array = matches!(object, JsValue::Object(object) if matches!(object.borrow().kind, ObjectKind::Array));
+        let is_fractional = key == "NaN" || key.parse::<f64>().is_ok_and(|x| x.is_finite() && x.fract() != 0.0);
 
-    fn performance_now(_: &Interpreter, _: &Vec<JsValue>) -> Result<JsValue, String> {
-        return Ok(JsValue::Number(
-            std::time::SystemTime::now()
-                .duration_since( std::time::SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as f64,
-        ));
-    }
+        if !is_array || !is_fractional {
+            return Ok(());
+        }
 
-    fn object_keys(_: &Interpreter, args: &Vec<JsValue>) -> Result<JsValue, String> {
-        assert_eq!(args.len(), 1);
+        let message = format!("array is indexed with {key}, it's used as a property key instead of an index");
 
-        if let JsValue::Object(object) = &args[0] {
-            let keys: Vec<JsValue> = object.borrow().properties.keys().map(|x| JsValue::String(x.clone())).collect();
-            return Ok(JsValue::Object(JsObject::array(keys).to_ref()));
+        if check == FractionalIndexCheck::Deny {
+            return Err(format!("RangeError: {message}"));
         }
 
-        return Err("First arguments should be an object".to_string());
+        eprintln!("Warning: {message}");
+        return Ok(());
     }
 
-    fn object_values(_: &Interpreter, args: &Vec<JsValue>) -> Result<JsValue, String> {
-        assert_eq!(args.len(), 1);
+    /// Makes arithmetic on mixed types, e.g. `"5" * 2` or `"n = " + 1`, throw a TypeError instead of converting operands
+    pub fn set_strict_arithmetic(&self, is_strict: bool) {
+        self.strict_arithmetic.set(is_strict);
+    }
 
-        if let JsValue::Object(object) = &args[0] {
-            let values: Vec<JsValue> = object.borrow().properties.values().map(|x| x.clone()).collect();
-            return Ok(JsValue::Object(JsObject::array(values).to_ref()));
+    /// Checks primitive operands of an arithmetic operator in strict arithmetic mode:
+    /// both have to be numbers, `+` also accepts two strings
+    pub(crate) fn check_arithmetic_operands(&self, operator: &str, left: &JsValue, right: &JsValue) -> Result<(), String> {
+        if !self.strict_arithmetic.get() {
+            return Ok(());
         }
 
-        return Err("First arguments should be an object".to_string());
+        match (left, right) {
+            (JsValue::Number(_), JsValue::Number(_)) => Ok(()),
+            (JsValue::String(_), JsValue::String(_)) if operator == "+" || operator == "+=" => Ok(()),
+            _ => Err(format!(
+                "TypeError: Cannot apply '{operator}' to {} and {} in strict arithmetic mode",
+                left.get_type_as_str(),
+                right.get_type_as_str(),
+            )),
+        }
     }
 
-    fn object_entries(_: &Interpreter, args: &Vec<JsValue>) -> Result<JsValue, String> {
-        assert_eq!(args.len(), 1);
-
-        if let JsValue::Object(object) = &args[0] {
-            let properties = &object.borrow().properties;
-            let values: Vec<JsValue> = properties.keys()
-                .zip(properties.values())
-                .map(|(key, value)| {
-                    JsObject::array(vec![JsValue::String(key.clone()), value.clone()]).to_js_value()
-                })
-                .collect();
-            return Ok(JsValue::Object(JsObject::array(values).to_ref()));
+    /// Assignments made by scripts are checked, builtins are protected from accidental overwrite
+    pub(crate) fn check_writable(&self, object: &JsObjectRef, key: &str) -> Result<(), String> {
+        if self.mutable_globals.get() || object.borrow().is_writable(key) {
+            return Ok(());
         }
 
-        return Err("First arguments should be an object".to_string());
+        return Err(format!("TypeError: Cannot assign to read only property '{key}'"));
     }
 
-    Environment::new_with_variables([
-        (
-            "console".to_string(),
-            (true, JsValue::object([
-                ("log".to_string(), JsValue::native_function(console_log)),
-            ])),
-        ),
-        (
-            "setPrototypeOf".to_string(),
-            (true, JsValue::native_function(set_prototype),)
-        ),
-        (
-            "performance".to_string(),
-            (true, JsValue::object([
-                ("now".to_string(), JsValue::native_function(performance_now))
-            ]),)
-        ),
-        (
-            "Object".to_string(),
-            (true, JsValue::object([
-                ("keys".to_string(), JsValue::native_function(object_keys)),
-                ("values".to_string(), JsValue::native_function(object_values)),
-                ("entries".to_string(), JsValue::native_function(object_entries)),
-            ])),
-        )
-    ])
-}
+    /// Variable which is not declared by scripts is a property of the global object, builtin ones are read only
+    pub(crate) fn check_variable_writable(&self, variable_name: &str) -> Result<(), String> {
+        if self.environment.borrow().borrow().is_declared(variable_name) {
+            return Ok(());
+        }
 
-impl Default for Interpreter {
-    fn default() -> Self {
-        let environment = get_global_environment();
-        Self {
-            environment: RefCell::new(Rc::new(RefCell::new(environment))),
+        match self.global_environment().borrow().get_global_object() {
+            Some(global_object) => self.check_writable(&global_object, variable_name),
+            None => Ok(()),
         }
     }
-}
 
-pub fn eval_code(code: &str) -> JsValue {
-    let mut interpreter = Interpreter::default();
+    /// Makes `Math.random` produce the same sequence on every run with the same seed
+    pub fn set_random_seed(&self, seed: u64) {
+        self.random.replace(Random::new(seed));
+    }
 
-    let ast = crate::parser::Parser::parse_code_to_ast(code)
-        .expect(format!("Error occurred during parsing").as_str());
+    pub(crate) fn next_random(&self) -> f64 {
+        self.random.borrow_mut().next_f64()
+    }
 
-    interpreter.interpret(&ast).unwrap()
-}
+    /// Starts counting executions of statements & branches of the given program
+    pub fn enable_coverage(&self, ast: &AstStatement) {
+        self.coverage.replace(Some(Coverage::new(ast)));
+    }
 
-fn interpret(interpreter: &mut Interpreter, code: &str) -> JsValue {
-    let ast = crate::parser::Parser::parse_code_to_ast(code)
-        .expect(format!("Error occurred during parsing").as_str());
+    pub fn take_coverage(&self) -> Option<Coverage> {
+        self.coverage.take()
+    }
 
-    interpreter.interpret(&ast).unwrap()
-}
+    pub(crate) fn record_statement_coverage(&self, statement: &AstStatement) {
+        if let Some(coverage) = self.coverage.borrow_mut().as_mut() {
+            coverage.record_statement(statement);
+        }
+    }
 
-#[test]
-fn get_variable_value_from_parent_environment() {
-    let variable_name = "abc";
-    let variable_value = JsValue::Number(123.0);
+    pub(crate) fn record_branch_coverage(&self, node: &impl GetSpan, is_consequent: bool) {
+        if let Some(coverage) = self.coverage.borrow_mut().as_mut() {
+            coverage.record_branch(&node.get_span(), is_consequent);
+        }
+    }
 
-    let mut parent_env = Environment::default();
-    parent_env.define_variable(variable_name.to_string(), variable_value.clone(), false).unwrap();
+    /// Installs callbacks invoked on statements & function calls, replacing the previous ones
+    pub fn set_hooks(&self, hooks: Box<dyn ExecutionHooks>) {
+        self.hooks.replace(Some(hooks));
+    }
 
-    let child_env = Environment::new(Rc::new(RefCell::new(parent_env)));
-    let value_from_parent_env = child_env.get_variable_value(variable_name);
+    pub fn take_hooks(&self) -> Option<Box<dyn ExecutionHooks>> {
+        self.hooks.take()
+    }
 
-    assert_eq!(value_from_parent_env, variable_value);
-}
+    /// Returns control to the embedder every `statements` executed statements, so a long script can be interleaved
+    /// with other work without threads. Scripts can't be suspended, the callback runs in the middle of the script
+    pub fn set_yield_interval(&self, statements: usize, callback: YieldCallback) {
+        let interval = statements.max(1);
+        self.yield_point.replace(Some(YieldPoint { interval, remaining: interval, callback }));
+    }
 
-#[test]
-fn try_to_get_undefined_variable_from_environment() {
-    let env = Environment::default();
-    assert_eq!(env.get_variable_value("abc"), JsValue::Undefined);
-}
+    pub fn clear_yield_interval(&self) {
+        self.yield_point.take();
+    }
 
-#[test]
-fn add_operator_works() {
-    let code = "2 + 2;";
-    assert_eq!(eval_code(code), JsValue::Number(4.0));
+    /// Counts executed statement, callback is taken out while it runs, so code it executes doesn't yield
+    pub(crate) fn count_statement(&self) -> Result<(), String> {
+        match self.yield_point.borrow_mut().as_mut() {
+            Some(yield_point) if yield_point.remaining > 1 => {
+                yield_point.remaining -= 1;
+                return Ok(());
+            }
+            Some(_) => {}
+            None => return Ok(()),
+        }
 
-    let code = "'Hello ' + 'world!';";
-    assert_eq!(eval_code(code), JsValue::String("Hello world!".to_string()));
-}
+        let Some(mut yield_point) = self.yield_point.take() else {
+            return Ok(());
+        };
 
-#[test]
-fn if_operator_works_then_branch() {
-    let code = "let a; if (true) { a = 5; } else { a = 10; } a;";
-    assert_eq!(eval_code(code), JsValue::Number(5.0));
-}
+        let action = (yield_point.callback)(self);
 
-#[test]
-fn if_operator_works_else_branch() {
-    let code = "let a; if (false) { a = 5; } else { a = 10; } a;";
-    assert_eq!(eval_code(code), JsValue::Number(10.0));
-}
+        // callback may set another interval, it takes precedence
+        if self.yield_point.borrow().is_none() {
+            yield_point.remaining = yield_point.interval;
+            self.yield_point.replace(Some(yield_point));
+        }
 
-#[test]
-fn for_loop_works() {
-    let code = "
-    let a = 5;
+        if action == YieldAction::Stop {
+            return Err("Error: Script stopped by the host".to_string());
+        }
 
-    for (let i = 1; i < 11; i+=1) {
-      a *= i;
+        return Ok(());
     }
 
-    a;";
-
-    assert_eq!(eval_code(code), JsValue::Number(18144000.0));
-}
+    /// Hooks are taken out while the callback runs, so code it executes doesn't invoke them recursively
+    pub(crate) fn run_hooks(&self, callback: impl FnOnce(&mut dyn ExecutionHooks, &Self)) {
+        let Some(mut hooks) = self.hooks.take() else {
+            return;
+        };
 
-#[test]
-fn while_loop_works() {
-    let code = "
-    let a = 0;
-    let i = 10;
+        callback(hooks.as_mut(), self);
 
-    while (i > 0) {
-        a += i;
-        i -=1 ;
+        // hook may install other hooks, they take precedence
+        if self.hooks.borrow().is_none() {
+            self.hooks.replace(Some(hooks));
+        }
     }
 
-    a;";
+    pub(crate) fn global_environment(&self) -> EnvironmentRef {
+        let mut environment = Rc::clone(&self.environment.borrow());
 
-    assert_eq!(eval_code(code), JsValue::Number(55.0));
-}
+        while let Some(parent) = environment.clone().borrow().get_parent() {
+            environment = parent;
+        }
 
-#[test]
-fn equality_expression_equal_works() {
-    let code = "5 == 5";
-    assert_eq!(eval_code(code), JsValue::Boolean(true));
-}
+        return environment;
+    }
 
-#[test]
-fn equality_expression_not_equal_works() {
-    let code = "5 == 6";
-    assert_eq!(eval_code(code), JsValue::Boolean(false));
-}
+    /// Saves global bindings, restoring them undoes declarations & assignments made afterwards.
+    /// Objects are not copied, so changes of their properties are kept
+    pub fn snapshot(&self) -> InterpreterSnapshot {
+        let environment = self.global_environment();
+        let environment = environment.borrow();
+
+        InterpreterSnapshot {
+            environment: environment.clone(),
+            global_properties: environment.get_global_object()
+                .map_or(HashMap::new(), |x| x.borrow().properties.clone()),
+            random: self.random.borrow().clone(),
+        }
+    }
 
-#[test]
-fn inequality_expression_equal_works() {
-    let code = "5 != 5";
-    assert_eq!(eval_code(code), JsValue::Boolean(false));
-}
+    /// Brings global bindings back to the state of the snapshot,
+    /// functions created before see restored values since the environment is updated in place
+    pub fn restore(&self, snapshot: &InterpreterSnapshot) {
+        let environment = self.global_environment();
+        *environment.borrow_mut() = snapshot.environment.clone();
 
-#[test]
-fn inequality_expression_not_equal_works() {
-    let code = "5 != 6";
-    assert_eq!(eval_code(code), JsValue::Boolean(true));
-}
+        if let Some(global_object) = environment.borrow().get_global_object() {
+            global_object.borrow_mut().properties = snapshot.global_properties.clone();
+        }
 
-#[test]
-fn conditional_expression_equal_works() {
-    let code = "true ? 1 : 2;";
-    assert_eq!(eval_code(code), JsValue::Number(1.0));
-}
+        self.random.replace(snapshot.random.clone());
+    }
 
-#[test]
-fn conditional_expression_not_equal_works() {
-    let code = "false ? 1 : 2;";
-    assert_eq!(eval_code(code), JsValue::Number(2.0));
-}
+    pub fn set_environment(&self, environment: Environment) {
+        self.environment.replace(Rc::new(RefCell::new(environment)));
+    }
 
-#[test]
-fn object_expression_works() {
+    pub(crate) fn create_new_environment(&self) -> Environment {
+        return Environment::new(Rc::clone(&self.environment.borrow().clone()));
+    }
+
+    /// Runs callback with the given environment as the current one,
+    /// the previous environment is restored afterwards, even if callback fails
+    pub(crate) fn with_environment<T>(&self, environment: Environment, callback: impl FnOnce(&Self) -> T) -> T {
+        let previous_environment = self.environment.replace(Rc::new(RefCell::new(environment)));
+        let result = callback(self);
+        self.environment.replace(previous_environment);
+        return result;
+    }
+
+    pub(crate) fn logical_or(&self, left: &JsValue, right: &JsValue) -> Result<JsValue, String> {
+        if left.to_bool() {
+            return Ok(left.clone());
+        }
+        return Ok(right.clone());
+    }
+
+    pub(crate) fn logical_and(&self, left: &JsValue, right: &JsValue) -> Result<JsValue, String> {
+        if !left.to_bool() {
+            return Ok(left.clone());
+        }
+        return Ok(right.clone());
+    }
+
+    pub(crate) fn call_function(&self, callee: &AstExpression, arguments: &Vec<AstExpression>, is_new: bool) -> Result<JsValue, String> {
+        let (function, context, arguments) = self.evaluate_call(callee, arguments, is_new)?;
+        return self.call_function_value(&function, context, arguments, is_new);
+    }
+
+    /// Evaluates callee, `this` & arguments of a call without making it
+    pub(crate) fn evaluate_call(&self, callee: &AstExpression, arguments: &[AstExpression], is_new: bool) -> Result<(JsValue, JsValue, Vec<JsValue>), String> {
+        // method call `a.b()` gets the object as `this`, any other call gets undefined
+        let (function, context) = match callee {
+            AstExpression::MemberExpression(node) if !is_new => {
+                let (object, function) = node.evaluate_with_object(self)?;
+                (function, object)
+            }
+            _ => (callee.execute(self)?, JsValue::Undefined),
+        };
+
+        let arguments = self.evaluate_call_arguments(arguments)?;
+
+        // arguments are evaluated before callee is checked, like in other engines
+        if !function.is_function() {
+            let kind = if is_new { "constructor" } else { "function" };
+            let callee = Printer::compact().print_expression(callee);
+            return Err(format!("TypeError: {callee} is not a {kind} (got {})", function.display(DisplayMode::Plain)));
+        }
+
+        return Ok((function, context, arguments));
+    }
+
+    /// Evaluates arguments of a call from left to right, spread arguments are expanded in place
+    fn evaluate_call_arguments(&self, arguments: &[AstExpression]) -> Result<Vec<JsValue>, String> {
+        let mut values = Vec::with_capacity(arguments.len());
+
+        for argument in arguments {
+            if let AstExpression::SpreadElement(node) = argument {
+                values.extend(self.iterate_value(&node.argument.execute(self)?)?);
+            } else {
+                values.push(argument.execute(self)?);
+            }
+        }
+
+        return Ok(values);
+    }
+
+    /// Items produced by iterating a value, as done by spread & array destructuring.
+    /// Only arrays & strings are iterable for now
+    pub(crate) fn iterate_value(&self, value: &JsValue) -> Result<Vec<JsValue>, String> {
+        match value {
+            JsValue::String(string) => Ok(string.chars().map(|x| JsValue::String(x.to_string().into())).collect()),
+            JsValue::Object(object) if matches!(object.borrow().kind, ObjectKind::Array) => Ok(object.borrow().array_items()),
+            _ => Err(format!("TypeError: {} is not iterable", value.get_type_as_str())),
+        }
+    }
+
+    /// Calls function value with evaluated arguments, the calling convention shared by calls,
+    /// `new`, spread calls & `Function.prototype.call/apply`.
+    /// `context` becomes `this` of the callee, unless it's called with `new`
+    pub(crate) fn call_function_value(&self, callee: &JsValue, context: JsValue, arguments: Vec<JsValue>, is_new: bool) -> Result<JsValue, String> {
+        let JsValue::Object(object) = callee else {
+            return Err(format!("TypeError: {} is not a function", callee.display(DisplayMode::Plain)));
+        };
+
+        // function is cloned out of the object, so its body can access the function object, e.g. `f.calls += 1`
+        let function = match &object.borrow().kind {
+            ObjectKind::Function(function) => function.clone(),
+            _ => return Err(format!("TypeError: {} is not a function", callee.display(DisplayMode::Plain))),
+        };
+
+        match &function {
+            JsFunction::Ordinary(function) if !is_new => {
+                if let Some(class_name) = &function.class_name {
+                    return Err(format!("TypeError: Class constructor {class_name} cannot be invoked without 'new'"));
+                }
+            }
+            // `new` on a bound function constructs its target, bound `this` is ignored
+            JsFunction::Bound(function) if is_new => {
+                let mut bound_arguments = function.arguments.clone();
+                bound_arguments.extend(arguments);
+                return self.call_function_value(&function.target, JsValue::Undefined, bound_arguments, true);
+            }
+            _ => {}
+        }
+
+        // instance created by `new`, returned unless constructor returns an object itself
+        let instance = if is_new {
+            let mut instance = JsObject::empty();
+
+            if let JsValue::Object(prototype) = object.borrow().get_prototype() {
+                instance.set_proto(prototype);
+            }
+
+            Some(instance.to_js_value())
+        } else {
+            None
+        };
+
+        let result = function.call(self, instance.clone().unwrap_or(context), &arguments)?;
+        return Ok(construct_result(result, instance));
+    }
+
+    /// Reads property of an object like `[[Get]]`: own properties, then the prototype chain,
+    /// which implicitly ends with `Function.prototype` for functions & with `Object.prototype` for every object.
+    /// Proxy calls `get` trap of its handler with target, key & the proxy itself
+    pub(crate) fn get_object_property(&self, object: &JsObjectRef, key: &str) -> Result<JsValue, String> {
+        let proxy = object.borrow().as_proxy().cloned();
+
+        if let Some(proxy) = proxy {
+            return match self.get_proxy_trap(&proxy, "get")? {
+                Some(trap) => self.call_proxy_trap(&proxy, &trap, vec![JsValue::String(key.into()), JsValue::Object(Rc::clone(object))]),
+                None => self.get_object_property(&proxy.target, key),
+            };
+        }
+
+        if key == PROTO_PROPERTY && !object.borrow().has_own_property(key) {
+            return Ok(self.get_object_proto(object));
+        }
+
+        let value = object.borrow().get_property_value(key);
+
+        let value = match value {
+            JsValue::Undefined if object.borrow().is_function() => self.get_function_prototype_property(key),
+            JsValue::Undefined => self.get_object_prototype_property(key),
+            value => value,
+        };
+
+        return Ok(value);
+    }
+
+    /// Stores property like `[[Set]]`, `__proto__` changes prototype unless it's an own property.
+    /// Proxy calls `set` trap with target, key, value & the proxy itself, result of the trap is ignored
+    pub(crate) fn set_object_property(&self, object: &JsObjectRef, key: &str, value: JsValue) -> Result<(), String> {
+        let proxy = object.borrow().as_proxy().cloned();
+
+        if let Some(proxy) = proxy {
+            return match self.get_proxy_trap(&proxy, "set")? {
+                Some(trap) => {
+                    self.call_proxy_trap(&proxy, &trap, vec![JsValue::String(key.into()), value, JsValue::Object(Rc::clone(object))])?;
+                    Ok(())
+                }
+                None => self.set_object_property(&proxy.target, key, value),
+            };
+        }
+
+        self.check_writable(object, key)?;
+
+        // non object values are ignored
+        if key == PROTO_PROPERTY && !object.borrow().has_own_property(key) {
+            if matches!(value, JsValue::Object(_)) {
+                self.set_object_proto(object, &value)?;
+            }
+
+            return Ok(());
+        }
+
+        if key == LENGTH_PROPERTY && matches!(object.borrow().kind, ObjectKind::Array) {
+            if !is_valid_array_length(&value) {
+                return Err("RangeError: Invalid array length".to_string());
+            }
+
+            object.borrow_mut().add_property(key, JsValue::Number(value.to_number()));
+            return Ok(());
+        }
+
+        // host is called without the object borrowed, it may read the object itself
+        let native = object.borrow().native_object().cloned();
+
+        if let Some(native) = native {
+            if native.set(key, value.clone())? {
+                return Ok(());
+            }
+        }
+
+        object.borrow_mut().add_property(key, value);
+        return Ok(());
+    }
+
+    /// Whether the object or its prototype chain has the property, like `[[HasProperty]]`.
+    /// Proxy calls `has` trap with target & key
+    pub(crate) fn has_property(&self, object: &JsObjectRef, key: &str) -> Result<bool, String> {
+        let proxy = object.borrow().as_proxy().cloned();
+
+        if let Some(proxy) = proxy {
+            return match self.get_proxy_trap(&proxy, "has")? {
+                Some(trap) => Ok(self.call_proxy_trap(&proxy, &trap, vec![JsValue::String(key.into())])?.to_bool()),
+                None => self.has_property(&proxy.target, key),
+            };
+        }
+
+        if object.borrow().has_own_property(key) {
+            return Ok(true);
+        }
+
+        match self.get_object_proto(object) {
+            JsValue::Object(prototype) => self.has_property(&prototype, key),
+            _ => Ok(false),
+        }
+    }
+
+    /// Removes own property like `[[Delete]]`, returns false if the property can't be removed, e.g. of a frozen object.
+    /// Proxy calls `deleteProperty` trap with target & key
+    pub(crate) fn delete_property(&self, object: &JsObjectRef, key: &str) -> Result<bool, String> {
+        let proxy = object.borrow().as_proxy().cloned();
+
+        if let Some(proxy) = proxy {
+            return match self.get_proxy_trap(&proxy, "deleteProperty")? {
+                Some(trap) => Ok(self.call_proxy_trap(&proxy, &trap, vec![JsValue::String(key.into())])?.to_bool()),
+                None => self.delete_property(&proxy.target, key),
+            };
+        }
+
+        if self.check_writable(object, key).is_err() {
+            return Ok(false);
+        }
+
+        object.borrow_mut().remove_property(key);
+        return Ok(true);
+    }
+
+    /// Trap of the proxy handler, `None` when the handler doesn't define it
+    fn get_proxy_trap(&self, proxy: &JsProxy, name: &str) -> Result<Option<JsValue>, String> {
+        match self.get_object_property(&proxy.handler, name)? {
+            JsValue::Undefined | JsValue::Null => Ok(None),
+            trap if trap.is_function() => Ok(Some(trap)),
+            _ => Err(format!("TypeError: proxy trap '{name}' is not a function")),
+        }
+    }
+
+    /// Calls trap with the handler as `this`, target is prepended to the arguments
+    fn call_proxy_trap(&self, proxy: &JsProxy, trap: &JsValue, arguments: Vec<JsValue>) -> Result<JsValue, String> {
+        let mut trap_arguments = vec![JsValue::Object(Rc::clone(&proxy.target))];
+        trap_arguments.extend(arguments);
+        return self.call_function_value(trap, JsValue::Object(Rc::clone(&proxy.handler)), trap_arguments, false);
+    }
+
+    /// Converts value to a primitive like ToPrimitive. Host objects use their native hook, other objects
+    /// call `valueOf` & `toString`, `toString` goes first when string is preferred.
+    /// Builtin `Object.prototype.toString` converts like the default conversion, so arrays are still joined
+    pub(crate) fn to_primitive(&self, value: &JsValue, hint: PrimitiveHint) -> Result<JsValue, String> {
+        let JsValue::Object(object) = value else {
+            return Ok(value.clone());
+        };
+
+        let hook = object.borrow().to_primitive_hook();
+
+        let result = match hook {
+            Some(hook) => Some(hook(self, object, hint)?),
+            None => self.ordinary_to_primitive(value, object, hint)?,
+        };
+
+        match result {
+            Some(result) if !matches!(result, JsValue::Object(_)) => Ok(result),
+            _ => Err("TypeError: Cannot convert object to primitive value".to_string()),
+        }
+    }
+
+    fn ordinary_to_primitive(&self, value: &JsValue, object: &JsObjectRef, hint: PrimitiveHint) -> Result<Option<JsValue>, String> {
+        let method_names = match hint {
+            PrimitiveHint::String => ["toString", "valueOf"],
+            PrimitiveHint::Default | PrimitiveHint::Number => ["valueOf", "toString"],
+        };
+
+        for name in method_names {
+            let method = self.get_object_property(object, name)?;
+            let default_to_string = self.intrinsics.object_prototype.borrow().properties.get("toString").cloned();
+
+            match (&method, default_to_string) {
+                (JsValue::Object(method), Some(JsValue::Object(default_to_string))) if Rc::ptr_eq(method, &default_to_string) => {
+                    return Ok(Some(JsValue::String(value.to_js_string().into())));
+                }
+                (method, _) if method.is_function() => {
+                    let result = self.call_function_value(method, value.clone(), vec![], false)?;
+
+                    if !matches!(result, JsValue::Object(_)) {
+                        return Ok(Some(result));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(None);
+    }
+
+    /// Prototype as seen by `Object.getPrototypeOf` & `__proto__`, including the implicit ones,
+    /// only `Object.prototype` itself has null prototype
+    pub(crate) fn get_object_proto(&self, object: &JsObjectRef) -> JsValue {
+        if let Some(prototype) = object.borrow().get_proto() {
+            return JsValue::Object(prototype);
+        }
+
+        if Rc::ptr_eq(object, &self.intrinsics.object_prototype) {
+            return JsValue::Null;
+        }
+
+        if object.borrow().is_function() {
+            return JsValue::Object(Rc::clone(&self.intrinsics.function_prototype));
+        }
+
+        return JsValue::Object(Rc::clone(&self.intrinsics.object_prototype));
+    }
+
+    /// Changes prototype like `Object.setPrototypeOf`, null prototypes are not supported
+    pub(crate) fn set_object_proto(&self, object: &JsObjectRef, prototype: &JsValue) -> Result<(), String> {
+        let JsValue::Object(prototype) = prototype else {
+            return Err(format!("TypeError: Object prototype may only be an Object, but got {}", prototype.get_type_as_str()));
+        };
+
+        self.check_writable(object, PROTO_PROPERTY)?;
+
+        // lookups walk the prototype chain, a cycle would make them never end
+        let mut chain_object = Some(Rc::clone(prototype));
+
+        while let Some(object_in_chain) = chain_object {
+            if Rc::ptr_eq(&object_in_chain, object) {
+                return Err("TypeError: Cyclic __proto__ value".to_string());
+            }
+
+            chain_object = object_in_chain.borrow().get_proto();
+        }
+
+        object.borrow_mut().set_proto(Rc::clone(prototype));
+        return Ok(());
+    }
+
+    /// Property of a function object which is not found on the function itself
+    pub(crate) fn get_function_prototype_property(&self, key: &str) -> JsValue {
+        return self.intrinsics.function_prototype.borrow().get_property_value(key);
+    }
+
+    /// Property of an object which is not found in its own prototype chain
+    pub(crate) fn get_object_prototype_property(&self, key: &str) -> JsValue {
+        return self.intrinsics.object_prototype.borrow().get_property_value(key);
+    }
+
+    pub(crate) fn create_js_function(
+        &self,
+        name: &str,
+        function_arguments: &Vec<FunctionArgument>,
+        body: &Rc<AstStatement>,
+    ) -> Result<JsFunction, String> {
+        let mut arguments = Vec::with_capacity(function_arguments.len());
+
+        for fn_arg_node in function_arguments {
+            let default_value = match &fn_arg_node.default_value {
+                Some(node) => node.execute(self)?,
+                None => JsValue::Undefined,
+            };
+
+            arguments.push(JsFunctionArg {
+                name: fn_arg_node.name.id.clone(),
+                default_value,
+                is_rest: fn_arg_node.is_rest,
+            });
+        }
+
+        let mut function = OrdinaryFunction::new(arguments, Rc::clone(body), self.environment.borrow().clone());
+        function.name = name.to_string();
+        function.source = self.current_source();
+        return Ok(function.into());
+    }
+
+    /// Key of `object.key` or `object[key]`, non computed identifier & string keys are borrowed from the node,
+    /// so property access in hot loops doesn't allocate
+    pub(crate) fn eval_member_expression_key<'a>(
+        &self,
+        node: &'a AstExpression,
+        computed: bool,
+    ) -> Result<Cow<'a, str>, String> {
+        if computed {
+            let computed_key = node.execute(self)?;
+
+            return Ok(Cow::Owned(self.to_property_key(&computed_key)?));
+        } else {
+            return static_property_key(node).ok_or("Object key should be an identifier".to_string());
+        }
+    }
+
+    /// Converts value to a property key like ToPropertyKey, objects are converted preferring `toString`.
+    /// Numbers become their canonical strings, so `a[1]`, `a[1.0]` & `a["1"]` are the same property
+    pub(crate) fn to_property_key(&self, value: &JsValue) -> Result<String, String> {
+        return Ok(self.to_primitive(value, PrimitiveHint::String)?.to_js_string());
+    }
+}
+
+/// Result of a call, `new` evaluates to the created instance unless the constructor returned an object
+fn construct_result(result: JsValue, instance: Option<JsValue>) -> JsValue {
+    match (instance, &result) {
+        (Some(instance), value) if !matches!(value, JsValue::Object(_)) => instance,
+        _ => result,
+    }
+}
+
+pub trait Execute {
+    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String>;
+}
+
+/// Globals defined by the interpreter itself, scripts can shadow them with declarations but can't assign them
+pub const BUILTIN_GLOBALS: [&str; 23] = [
+    "globalThis", "console", "eval", "setPrototypeOf", "formatDate", "performance", "NaN", "Infinity", "Math",
+    "Object", "Array", "String", "Number", "Boolean", "Error", "TypeError", "RangeError", "SyntaxError", "WeakMap", "WeakSet",
+    "Proxy", "Reflect", "rustjs",
+];
+
+fn get_global_environment() -> Environment {
+    fn format_console_arguments(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> String {
+        arguments
+            .iter()
+            .map(|arg| format!("{}", arg.display(interpreter.display_mode()).with_limits(interpreter.display_limits())))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn console_log(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        println!("{}", format_console_arguments(interpreter, arguments));
+        return Ok(JsValue::Undefined);
+    }
+
+    fn console_error(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        eprintln!("{}", format_console_arguments(interpreter, arguments));
+        return Ok(JsValue::Undefined);
+    }
+
+    /// Older global form of `Object.setPrototypeOf`, kept for existing scripts
+    fn set_prototype(
+        interpreter: &Interpreter,
+        arguments: &Vec<JsValue>,
+    ) -> Result<JsValue, String> {
+        let JsValue::Object(target) = arguments.get(0).unwrap_or(&JsValue::Undefined) else {
+            return Err("TypeError: setPrototypeOf called on non-object".to_string());
+        };
+
+        interpreter.set_object_proto(target, arguments.get(1).unwrap_or(&JsValue::Undefined))?;
+
+        return Ok(JsValue::Undefined);
+    }
+
+    fn performance_now(_: &Interpreter, _: &Vec<JsValue>) -> Result<JsValue, String> {
+        return Ok(JsValue::Number(
+            std::time::SystemTime::now()
+                .duration_since( std::time::SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as f64,
+        ));
+    }
+
+    /// Runs code in the scope of the caller, `let` & `const` declarations don't leak out of evaluated code
+    fn eval(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let code = match arguments.get(0) {
+            Some(JsValue::String(code)) => code,
+            // non string argument is returned as is
+            argument => return Ok(argument.cloned().unwrap_or(JsValue::Undefined)),
+        };
+
+        let ast = crate::parser::Parser::parse_code_to_ast(code)
+            .map_err(|e| format!("SyntaxError: {e}"))?;
+
+        // evaluated code has no script source, so errors inside its functions are reported without an excerpt
+        let result = interpreter.with_source(&None, |interpreter| interpreter.interpret(&ast));
+
+        // span inside of evaluated code doesn't match the script source, the `eval` call is reported instead
+        interpreter.take_error_location();
+        return result;
+    }
+
+    /// `formatDate(timestamp, pattern)`, timestamp is milliseconds since the epoch, e.g. `performance.now()`
+    fn format_date_native(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let pattern = arguments.get(1).map_or("YYYY-MM-DDTHH:mm:ss.SSS".to_string(), |x| x.to_js_string());
+        return Ok(JsValue::String(format_date(number_argument(arguments, 0), &pattern)?.into()));
+    }
+
+    let mut environment = Environment::with_globals([
+        (
+            "console".to_string(),
+            JsValue::object([
+                ("log".to_string(), JsValue::native_function(console_log)),
+                ("info".to_string(), JsValue::native_function(console_log)),
+                ("debug".to_string(), JsValue::native_function(console_log)),
+                ("warn".to_string(), JsValue::native_function(console_error)),
+                ("error".to_string(), JsValue::native_function(console_error)),
+            ]),
+        ),
+        (
+            "eval".to_string(),
+            JsValue::native_function(eval),
+        ),
+        (
+            "setPrototypeOf".to_string(),
+            JsValue::native_function(set_prototype),
+        ),
+        (
+            "formatDate".to_string(),
+            JsValue::native_function(format_date_native),
+        ),
+        (
+            "performance".to_string(),
+            JsValue::object([
+                ("now".to_string(), JsValue::native_function(performance_now))
+            ]),
+        ),
+        (
+            "NaN".to_string(),
+            JsValue::Number(f64::NAN),
+        ),
+        (
+            "Infinity".to_string(),
+            JsValue::Number(f64::INFINITY),
+        ),
+        (
+            "rustjs".to_string(),
+            get_engine_info_object(),
+        ),
+    ]);
+
+    environment.define_lazy_global("Math", || freeze_builtin(get_math_object()));
+    environment
+}
+
+fn freeze_builtin(value: JsValue) -> JsValue {
+    if let JsValue::Object(object) = &value {
+        object.borrow_mut().freeze();
+    }
+
+    value
+}
+
+/// Returns argument at given position converted to number, missing arguments are treated as NaN
+fn number_argument(arguments: &Vec<JsValue>, index: usize) -> f64 {
+    arguments.get(index).map_or(f64::NAN, |x| x.to_number())
+}
+
+/// `rustjs` global, so scripts & test harnesses can check what the engine supports before using it
+fn get_engine_info_object() -> JsValue {
+    let features = JsValue::object([
+        ("classes".to_string(), JsValue::Boolean(true)),
+        ("destructuring".to_string(), JsValue::Boolean(true)),
+        ("proxy".to_string(), JsValue::Boolean(true)),
+        ("weakCollections".to_string(), JsValue::Boolean(true)),
+        ("modules".to_string(), JsValue::Boolean(false)),
+        ("async".to_string(), JsValue::Boolean(false)),
+        ("generators".to_string(), JsValue::Boolean(false)),
+        ("regexp".to_string(), JsValue::Boolean(false)),
+        ("tryCatch".to_string(), JsValue::Boolean(false)),
+    ]);
+
+    JsValue::object([
+        ("version".to_string(), JsValue::String(env!("CARGO_PKG_VERSION").into())),
+        ("engine".to_string(), JsValue::String(BACKEND_NAME.into())),
+        ("features".to_string(), freeze_builtin(features)),
+    ])
+}
+
+fn get_math_object() -> JsValue {
+    fn math_min(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let mut result = f64::INFINITY;
+
+        for i in 0..arguments.len() {
+            let number = number_argument(arguments, i);
+
+            if number.is_nan() {
+                return Ok(JsValue::Number(f64::NAN));
+            }
+
+            result = result.min(number);
+        }
+
+        return Ok(JsValue::Number(result));
+    }
+
+    fn math_max(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let mut result = f64::NEG_INFINITY;
+
+        for i in 0..arguments.len() {
+            let number = number_argument(arguments, i);
+
+            if number.is_nan() {
+                return Ok(JsValue::Number(f64::NAN));
+            }
+
+            result = result.max(number);
+        }
+
+        return Ok(JsValue::Number(result));
+    }
+
+    fn math_round(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        // Unlike f64::round, Math.round rounds halves towards +Infinity, e.g. -2.5 to -2 & -0.5 to -0.
+        // `number + 0.5` isn't used, it rounds up 0.49999999999999994 because of precision loss
+        let number = number_argument(arguments, 0);
+        let rounded = number.round();
+
+        if number - rounded == 0.5 {
+            return Ok(JsValue::Number((rounded + 1.0).copysign(number)));
+        }
+
+        return Ok(JsValue::Number(rounded));
+    }
+
+    fn math_hypot(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let sum: f64 = (0..arguments.len())
+            .map(|i| number_argument(arguments, i).powi(2))
+            .sum();
+        return Ok(JsValue::Number(sum.sqrt()));
+    }
+
+    JsValue::object([
+        ("PI".to_string(), JsValue::Number(std::f64::consts::PI)),
+        ("E".to_string(), JsValue::Number(std::f64::consts::E)),
+        ("LN2".to_string(), JsValue::Number(std::f64::consts::LN_2)),
+        ("LN10".to_string(), JsValue::Number(std::f64::consts::LN_10)),
+        ("LOG2E".to_string(), JsValue::Number(std::f64::consts::LOG2_E)),
+        ("LOG10E".to_string(), JsValue::Number(std::f64::consts::LOG10_E)),
+        ("SQRT2".to_string(), JsValue::Number(std::f64::consts::SQRT_2)),
+        ("SQRT1_2".to_string(), JsValue::Number(std::f64::consts::FRAC_1_SQRT_2)),
+        ("abs".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).abs().into()))),
+        ("ceil".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).ceil().into()))),
+        ("floor".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).floor().into()))),
+        ("trunc".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).trunc().into()))),
+        ("round".to_string(), JsValue::native_function(math_round)),
+        ("sign".to_string(), JsValue::native_function(|_, args| {
+            let number = number_argument(args, 0);
+            Ok(if number == 0.0 || number.is_nan() { number } else { number.signum() }.into())
+        })),
+        ("sqrt".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).sqrt().into()))),
+        ("cbrt".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).cbrt().into()))),
+        ("pow".to_string(), JsValue::native_function(|_, args| Ok(number_exponentiation(number_argument(args, 0), number_argument(args, 1)).into()))),
+        ("exp".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).exp().into()))),
+        ("log".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).ln().into()))),
+        ("log2".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).log2().into()))),
+        ("log10".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).log10().into()))),
+        ("sin".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).sin().into()))),
+        ("cos".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).cos().into()))),
+        ("tan".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).tan().into()))),
+        ("atan".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).atan().into()))),
+        ("atan2".to_string(), JsValue::native_function(|_, args| Ok(number_argument(args, 0).atan2(number_argument(args, 1)).into()))),
+        ("hypot".to_string(), JsValue::native_function(math_hypot)),
+        ("min".to_string(), JsValue::native_function(math_min)),
+        ("max".to_string(), JsValue::native_function(math_max)),
+        ("random".to_string(), JsValue::native_function(|interpreter, _| Ok(interpreter.next_random().into()))),
+    ])
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        let intrinsics = Intrinsics::new();
+        let environment = get_global_environment();
+
+        if let Some(global_object) = environment.get_global_object() {
+            for (name, constructor) in intrinsics.get_global_constructors() {
+                global_object.borrow_mut().add_property(&name, constructor);
+            }
+
+            for name in BUILTIN_GLOBALS {
+                let value = global_object.borrow().properties.get(name).cloned();
+                global_object.borrow_mut().mark_read_only(name);
+
+                // global object itself stays extensible, scripts define their globals on it
+                match value {
+                    Some(JsValue::Object(object)) if !Rc::ptr_eq(&object, &global_object) => object.borrow_mut().freeze(),
+                    _ => {}
+                }
+            }
+        }
+
+        Self {
+            environment: RefCell::new(Rc::new(RefCell::new(environment))),
+            coverage: RefCell::new(None),
+            random: RefCell::new(Random::from_time()),
+            display_mode: Cell::new(DisplayMode::default()),
+            display_limits: Cell::new(DisplayLimits::default()),
+            locale: Cell::new(Locale::default()),
+            mutable_globals: Cell::new(false),
+            fractional_index_check: Cell::new(FractionalIndexCheck::default()),
+            strict_arithmetic: Cell::new(false),
+            intrinsics,
+            source: RefCell::new(None),
+            error_location: RefCell::new(None),
+            completion: RefCell::new(Completion::Normal),
+            function_depth: Cell::new(0),
+            hooks: RefCell::new(None),
+            yield_point: RefCell::new(None),
+        }
+    }
+}
+
+pub fn eval_code(code: &str) -> JsValue {
+    let mut interpreter = Interpreter::default();
+
+    let ast = crate::parser::Parser::parse_code_to_ast(code)
+        .expect(format!("Error occurred during parsing").as_str());
+
+    interpreter.interpret(&ast).unwrap()
+}
+
+fn interpret(interpreter: &mut Interpreter, code: &str) -> JsValue {
+    let ast = crate::parser::Parser::parse_code_to_ast(code)
+        .expect(format!("Error occurred during parsing").as_str());
+
+    interpreter.interpret(&ast).unwrap()
+}
+
+fn try_interpret(interpreter: &Interpreter, code: &str) -> Result<JsValue, String> {
+    crate::parser::Parser::parse_code_to_ast(code).and_then(|ast| interpreter.interpret(&ast))
+}
+
+#[test]
+fn get_variable_value_from_parent_environment() {
+    let variable_name = "abc";
+    let variable_value = JsValue::Number(123.0);
+
+    let mut parent_env = Environment::default();
+    parent_env.define_variable(variable_name.to_string(), variable_value.clone(), false).unwrap();
+
+    let child_env = Environment::new(Rc::new(RefCell::new(parent_env)));
+    let value_from_parent_env = child_env.get_variable_value(variable_name);
+
+    assert_eq!(value_from_parent_env, variable_value);
+}
+
+#[test]
+fn try_to_get_undefined_variable_from_environment() {
+    let env = Environment::default();
+    assert_eq!(env.get_variable_value("abc"), JsValue::Undefined);
+}
+
+#[test]
+fn add_operator_works() {
+    let code = "2 + 2;";
+    assert_eq!(eval_code(code), JsValue::Number(4.0));
+
+    let code = "'Hello ' + 'world!';";
+    assert_eq!(eval_code(code), JsValue::String("Hello world!".into()));
+}
+
+#[test]
+fn if_operator_works_then_branch() {
+    let code = "let a; if (true) { a = 5; } else { a = 10; } a;";
+    assert_eq!(eval_code(code), JsValue::Number(5.0));
+}
+
+#[test]
+fn if_operator_works_else_branch() {
+    let code = "let a; if (false) { a = 5; } else { a = 10; } a;";
+    assert_eq!(eval_code(code), JsValue::Number(10.0));
+}
+
+#[test]
+fn for_loop_works() {
+    let code = "
+    let a = 5;
+
+    for (let i = 1; i < 11; i+=1) {
+      a *= i;
+    }
+
+    a;";
+
+    assert_eq!(eval_code(code), JsValue::Number(18144000.0));
+}
+
+#[test]
+fn while_loop_works() {
+    let code = "
+    let a = 0;
+    let i = 10;
+
+    while (i > 0) {
+        a += i;
+        i -=1 ;
+    }
+
+    a;";
+
+    assert_eq!(eval_code(code), JsValue::Number(55.0));
+}
+
+#[test]
+fn equality_expression_equal_works() {
+    let code = "5 == 5";
+    assert_eq!(eval_code(code), JsValue::Boolean(true));
+}
+
+#[test]
+fn equality_expression_not_equal_works() {
+    let code = "5 == 6";
+    assert_eq!(eval_code(code), JsValue::Boolean(false));
+}
+
+#[test]
+fn inequality_expression_equal_works() {
+    let code = "5 != 5";
+    assert_eq!(eval_code(code), JsValue::Boolean(false));
+}
+
+#[test]
+fn inequality_expression_not_equal_works() {
+    let code = "5 != 6";
+    assert_eq!(eval_code(code), JsValue::Boolean(true));
+}
+
+#[test]
+fn conditional_expression_equal_works() {
+    let code = "true ? 1 : 2;";
+    assert_eq!(eval_code(code), JsValue::Number(1.0));
+}
+
+#[test]
+fn conditional_expression_not_equal_works() {
+    let code = "false ? 1 : 2;";
+    assert_eq!(eval_code(code), JsValue::Number(2.0));
+}
+
+#[test]
+fn object_expression_works() {
+    let code = "
+        let a = {
+            5: 2 + 3,
+            'qwe-123': 'string prop',
+            abc: 'identifier prop',
+            ['hello ' + 123]: 'hello 123',
+        };
+
+        a;
+    ";
+
+    let mut interpreter = Interpreter::default();
+
+    let expected = JsValue::object([
+        ("5".to_string(), JsValue::Number(5.0)),
+        (
+            "qwe-123".to_string(),
+            JsValue::String("string prop".into()),
+        ),
+        (
+            "abc".to_string(),
+            JsValue::String("identifier prop".into()),
+        ),
+        (
+            "hello 123".to_string(),
+            JsValue::String("hello 123".into()),
+        ),
+    ]);
+
+    assert_eq!(interpret(&mut interpreter, code), expected);
+    assert_eq!(interpret(&mut interpreter, "a[5];"), JsValue::Number(5.0));
+    assert_eq!(
+        interpret(&mut interpreter, "a['qwe-123'];"),
+        JsValue::String("string prop".into())
+    );
+    assert_eq!(
+        interpret(&mut interpreter, "a['abc'];"),
+        JsValue::String("identifier prop".into())
+    );
+    assert_eq!(
+        interpret(&mut interpreter, "a.abc;"),
+        JsValue::String("identifier prop".into())
+    );
+    assert_eq!(
+        interpret(&mut interpreter, "a['hello ' + 123];"),
+        JsValue::String("hello 123".into())
+    );
+}
+
+#[test]
+fn object_function_property() {
+    let code = "
+        let a = {
+            b: function(a,b) {
+                return a * 2 + b;
+            }
+        };
+
+        a.b(3, 2);
+    ";
+    assert_eq!(eval_code(code), JsValue::Number(8.0));
+}
+
+#[test]
+fn nested_member_expression_works() {
+    let code = "
+    let a = {
+        b: {
+            c: {
+                d: 'qwerty'
+            }
+        }
+    };
+    a.b.c.d;";
+    assert_eq!(eval_code(code), JsValue::String("qwerty".into()));
+}
+
+#[test]
+fn assign_to_object_property_works() {
+    let code = "
+        let a = { b: 10 };
+        a.b = 20;
+        a.b;
+    ";
+    assert_eq!(eval_code(code), JsValue::Number(20.0));
+}
+
+#[test]
+fn mutate_object_as_reference_works() {
+    let code = "
+        let a = { b: 10 };
+        let c = { d: a };
+        a.b = 25;
+        c.d.b;
+    ";
+    assert_eq!(eval_code(code), JsValue::Number(25.0));
+}
+
+#[test]
+fn object_method_this_expression() {
+    let mut interpreter = Interpreter::default();
+
+    let code = "
+        let a = {
+          abc: 10,
+          getAbc: function(a, b) {
+            return this.abc;
+          },
+          setAbc: function(newValue) {
+            this.abc = newValue;
+          }
+        };
+
+        a.getAbc();
+    ";
+    assert_eq!(interpret(&mut interpreter, code), JsValue::Number(10.0));
+    assert_eq!(
+        interpret(&mut interpreter, "a.setAbc(25); a.getAbc();"),
+        JsValue::Number(25.0)
+    );
+}
+
+#[test]
+fn comparison() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "'abc' == 'abc'"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "'abc' == 'qwe'"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "123 == 123"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "123 == 456"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "true == true"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "true == false"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "false == false"), JsValue::Boolean(true));
+    assert_eq!(eval_code("let a = {}; let b = {}; a == b;"), JsValue::Boolean(false));
+    assert_eq!(eval_code("let a = {}; let b = a; a == b;"), JsValue::Boolean(true));
+}
+
+#[test]
+fn prototype_property_access() {
+    let mut interpreter = Interpreter::default();
+
+    let code = "
+        let prototype = {
+          a: 10
+        };
+
+        let target = { b: 30 };
+        setPrototypeOf(target, prototype);
+        target.a;
+    ";
+    assert_eq!(interpret(&mut interpreter, code), JsValue::Number(10.0));
+}
+
+#[test]
+fn prototype_mutable_property_access() {
+    let mut interpreter = Interpreter::default();
+
+    let code = "
+        let prototype = {
+          a: 10
+        };
+
+        let target = { b: 30 };
+        setPrototypeOf(target, prototype);
+        prototype.a = 50;
+        target.a;
+    ";
+    assert_eq!(interpret(&mut interpreter, code), JsValue::Number(50.0));
+}
+
+#[test]
+fn two_objects_must_be_checked_for_equality_by_reference() {
+    let code = "
+       let a = { b: { c: 10 } };
+
+       let d = {
+         e: {
+           f: a
+         }
+       };
+
+       d.e.f == a;
+    ";
+    assert_eq!(eval_code(code), JsValue::Boolean(true));
+
+    let code = "
+       let a = { b: { c: 10 } };
+
+       let d = {
+         e: {
+           f: {}
+         }
+       };
+
+       d.e.f == a;
+    ";
+    assert_eq!(eval_code(code), JsValue::Boolean(false));
+}
+
+#[test]
+fn simple_class_usage() {
+    let code = "
+       class User {
+         constructor(name, age) {
+            this.name = name;
+            this.age = age;
+         }
+
+         getUserInformation() {
+            return 'Name is ' + this.name + ', ' + this.age + ' years old';
+         }
+       }
+
+       let user = new User('Anton', 26);
+       user.getUserInformation();
+    ";
+    assert_eq!(eval_code(code), JsValue::String("Name is Anton, 26 years old".into()));
+}
+
+#[test]
+fn class_proto_of_instance_should_be_equal_to_class_prototype() {
+    let mut interpreter = Interpreter::default();
+
+    let code = "
+       class User {
+         constructor(name, age) {
+            this.name = name;
+            this.age = age;
+         }
+
+         getUserInformation() {
+            return 'Name is ' + this.name + ', ' + this.age + ' years old';
+         }
+       }
+
+       let user = new User('Anton', 26);
+       user.getUserInformation();
+    ";
+    interpret(&mut interpreter, code);
+    let class = interpreter.environment.borrow().borrow().get_variable_value("User");
+    let class_instance = interpreter.environment.borrow().borrow().get_variable_value("user");
+
+    if let JsValue::Object(class_object) = &class {
+        if let JsValue::Object(instance_object) = &class_instance {
+            let class_prototype = class_object.borrow().get_prototype();
+            let class_instance_proto = instance_object.borrow().get_proto().unwrap();
+
+            if let JsValue::Object(class_prototype) = class_prototype {
+                assert!(Rc::ptr_eq(&class_prototype, &class_instance_proto));
+            }
+        }
+    }
+}
+
+#[test]
+fn prototypes_of_instances_of_same_class_equals() {
+    let mut interpreter = Interpreter::default();
+    let code = "
+        class A { constructor(a) { this.a = a; } }
+        new A();
+    ";
+    let class_instance1 = interpret(&mut interpreter, code);
+    let class_instance2 = interpret(&mut interpreter, "new A();");
+
+    if let JsValue::Object(object1) = &class_instance1 {
+        if let JsValue::Object(object2) = &class_instance2 {
+            let prototype1 = object1.borrow().get_proto().unwrap();
+            let prototype2 = object2.borrow().get_proto().unwrap();
+            assert!(Rc::ptr_eq(&prototype1, &prototype2));
+        }
+    }
+}
+
+#[test]
+fn class_constructor_requires_new() {
+    let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "class User { constructor(name) { this.name = name; } } function Plain() {}");
+
+    assert_eq!(try_interpret(&interpreter, "User('x');"), Err("TypeError: Class constructor User cannot be invoked without 'new'".to_string()));
+    assert_eq!(try_interpret(&interpreter, "User.call({}, 'x');"), Err("TypeError: Class constructor User cannot be invoked without 'new'".to_string()));
+    assert_eq!(try_interpret(&interpreter, "let Bound = User.bind(null, 'bound'); Bound();"), Err("TypeError: Class constructor User cannot be invoked without 'new'".to_string()));
+    assert_eq!(interpret(&mut interpreter, "let bound = new Bound(); bound.name;"), JsValue::String("bound".into()));
+    assert_eq!(interpret(&mut interpreter, "bound instanceof User;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Plain();"), JsValue::Undefined);
+    assert_eq!(format!("{}", interpret(&mut interpreter, "User;").display(DisplayMode::Plain)), "[class User]");
+    assert_eq!(format!("{}", interpret(&mut interpreter, "Plain;").display(DisplayMode::Plain)), "[function]");
+}
+
+#[test]
+fn own_property_helpers_skip_inherited_properties() {
+    let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "let parent = { inherited: 1 }; let child = { __proto__: parent, own: 2 };");
+
+    assert_eq!(interpret(&mut interpreter, "child.hasOwnProperty('own');"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "child.hasOwnProperty('inherited');"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.hasOwnProperty.call(parent, 'inherited');"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.keys(child)[0] + Object.keys(child)[1];"), JsValue::String("ownundefined".into()));
+    assert_eq!(interpret(&mut interpreter, "Object.values(child)[0] + Object.entries(child)[0][1];"), JsValue::Number(4.0));
+    assert_eq!(interpret(&mut interpreter, "let copy = Object.assign({}, child); copy.inherited;"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "function f() {} f.hasOwnProperty('call');"), JsValue::Boolean(false));
+    assert_eq!(format!("{}", interpret(&mut interpreter, "child;").display(DisplayMode::Plain)), "{ own: 2 }");
+    assert_eq!(format!("{}", interpret(&mut interpreter, "child;").display(DisplayMode::Json)), "{\"own\":2}");
+
+    let ast = crate::parser::Parser::parse_code_to_ast("Object.keys(5);").unwrap();
+    assert_eq!(Interpreter::default().interpret(&ast), Err("TypeError: Cannot convert number to object".to_string()));
+}
+
+#[test]
+fn object_prototype_methods_are_inherited_by_every_object() {
+    let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "class Animal {} class Dog extends Animal {} let dog = new Dog(); let plain = { count: 1 };");
+
+    assert_eq!(interpret(&mut interpreter, "plain.toString();"), JsValue::String("[object Object]".into()));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.toString.call([1]);"), JsValue::String("[object Array]".into()));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.toString.call(null);"), JsValue::String("[object Null]".into()));
+    assert_eq!(interpret(&mut interpreter, "plain.valueOf() == plain;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Animal.prototype.isPrototypeOf(dog);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Dog.prototype.isPrototypeOf(plain);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.isPrototypeOf(dog);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.isPrototypeOf(Object.prototype);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.isPrototypeOf(1);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "plain.count += plain.hasOwnProperty('count') ? 1 : 0; plain.count;"), JsValue::Number(2.0));
+
+    interpret(&mut interpreter, "let base = { shared: 10 }; let derived = { __proto__: base }; derived.shared += 1;");
+    assert_eq!(interpret(&mut interpreter, "derived.shared + base.shared;"), JsValue::Number(21.0));
+}
+
+#[test]
+fn function_constructor_as_class() {
     let code = "
-        let a = {
-            5: 2 + 3,
-            'qwe-123': 'string prop',
-            abc: 'identifier prop',
-            ['hello ' + 123]: 'hello 123',
-        };
+        function User(name, age) {
+            this.name = name;
+            this.age = age;
+        }
 
-        a;
+        console.log(User.prototype);
+
+        User.prototype.getUserInformation = function() {
+            return 'Name is ' + this.name + ', ' + this.age + ' years old';
+        }
+
+        let user = new User('Anton', 26);
+        user.getUserInformation();
+    ";
+    assert_eq!(eval_code(code), JsValue::String("Name is Anton, 26 years old".into()));
+}
+
+#[test]
+#[should_panic(expected = "Assignment to constant variable.")]
+fn attempt_to_reassign_constant_variable_should_error() {
+    let code = "
+        const a = 5;
+        a = 10;
     ";
+    eval_code(code);
+}
 
+#[test]
+fn math_object_methods() {
     let mut interpreter = Interpreter::default();
 
-    let expected = JsValue::object([
-        ("5".to_string(), JsValue::Number(5.0)),
-        (
-            "qwe-123".to_string(),
-            JsValue::String("string prop".to_string()),
-        ),
-        (
-            "abc".to_string(),
-            JsValue::String("identifier prop".to_string()),
-        ),
-        (
-            "hello 123".to_string(),
-            JsValue::String("hello 123".to_string()),
-        ),
-    ]);
+    assert_eq!(interpret(&mut interpreter, "Math.abs(3 - 10);"), JsValue::Number(7.0));
+    assert_eq!(interpret(&mut interpreter, "Math.floor(4.7) + Math.ceil(4.2);"), JsValue::Number(9.0));
+    assert_eq!(interpret(&mut interpreter, "Math.round(2.5);"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "Math.round(0.49999999999999994);"), JsValue::Number(0.0));
+    assert_eq!(interpret(&mut interpreter, "Math.round(-2.5);"), JsValue::Number(-2.0));
+    assert_eq!(interpret(&mut interpreter, "1 / Math.round(-0.5);"), JsValue::Number(f64::NEG_INFINITY));
+    assert_eq!(interpret(&mut interpreter, "Math.max(1, 8, 3);"), JsValue::Number(8.0));
+    assert_eq!(interpret(&mut interpreter, "Math.min(4, 2, 6);"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "Math.pow(2, 10);"), JsValue::Number(1024.0));
+    assert_eq!(interpret(&mut interpreter, "Math.PI;"), JsValue::Number(std::f64::consts::PI));
+}
 
-    assert_eq!(interpret(&mut interpreter, code), expected);
-    assert_eq!(interpret(&mut interpreter, "a[5];"), JsValue::Number(5.0));
+#[test]
+fn object_assign_copies_properties_into_target() {
+    let code = "
+        let target = { a: 1 };
+        Object.assign(target, { b: 2 }, { c: 3 });
+        target.a + target.b + target.c;
+    ";
+    assert_eq!(eval_code(code), JsValue::Number(6.0));
+    assert_eq!(eval_code("Object.assign([], { 3: 'x' }).length;"), JsValue::Number(4.0));
+    assert_eq!(eval_code("let source = new Proxy({ a: 1 }, { get: function () { return 5; } }); Object.assign({}, source).a;"), JsValue::Number(5.0));
+}
+
+#[test]
+fn relational_comparison_of_mixed_types() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "'apple' < 'banana';"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "'b' > 'abc';"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "'10' < '9';"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "'10' < 9;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "' 42 ' >= 42;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "true > false;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "null >= 0;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "undefined < 1;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "undefined >= 1;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "'abc' < 1;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "'0x10' > 15;"), JsValue::Boolean(true));
+}
+
+#[test]
+fn division_edge_cases() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "1 / 0;"), JsValue::Number(f64::INFINITY));
+    assert_eq!(interpret(&mut interpreter, "1 / (0 / (0 - 1));"), JsValue::Number(f64::NEG_INFINITY));
+    assert_eq!(interpret(&mut interpreter, "let nan = 0 / 0; nan == nan;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "nan != nan;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "NaN == NaN;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "(0 / (0 - 1)) == 0;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Infinity - Infinity == Infinity - Infinity;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "'6' / '2';"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "7 % 3;"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "(0 - 7) % 3;"), JsValue::Number(-1.0));
+    assert_eq!(interpret(&mut interpreter, "1 ** NaN == 1 ** NaN;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "'Result: ' + 1 / 0;"), JsValue::String("Result: Infinity".into()));
+    assert_eq!(interpret(&mut interpreter, "'Result: ' + (0 / (0 - 1));"), JsValue::String("Result: 0".into()));
+}
+
+#[test]
+fn exponentiation_is_right_associative() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "2 ** 3 ** 2;"), JsValue::Number(512.0));
+    assert_eq!(interpret(&mut interpreter, "(2 ** 3) ** 2;"), JsValue::Number(64.0));
+    assert_eq!(interpret(&mut interpreter, "(-2) ** 2;"), JsValue::Number(4.0));
+    assert_eq!(interpret(&mut interpreter, "2 ** -1;"), JsValue::Number(0.5));
+    assert_eq!(interpret(&mut interpreter, "-(2 ** 2);"), JsValue::Number(-4.0));
+    assert_eq!(interpret(&mut interpreter, "2 * 3 ** 2;"), JsValue::Number(18.0));
+    assert!(crate::parser::Parser::parse_code_to_ast("-2 ** 2;").is_err());
+    assert!(crate::parser::Parser::parse_code_to_ast("2 ** -2 ** 2;").is_err());
+}
+
+#[test]
+fn number_to_string_special_values() {
+    assert_eq!(crate::value::number_to_string(f64::NAN), "NaN");
+    assert_eq!(crate::value::number_to_string(f64::INFINITY), "Infinity");
+    assert_eq!(crate::value::number_to_string(f64::NEG_INFINITY), "-Infinity");
+    assert_eq!(crate::value::number_to_string(-0.0), "0");
+    assert_eq!(crate::value::number_to_string(2.5), "2.5");
+}
+
+#[test]
+fn number_to_string_matches_v8() {
+    let cases = [
+        (5.0, "5"),
+        (-42.0, "-42"),
+        (0.1 + 0.2, "0.30000000000000004"),
+        (123.456, "123.456"),
+        (9007199254740992.0, "9007199254740992"),
+        (1e20, "100000000000000000000"),
+        (123456789012345680000.0, "123456789012345680000"),
+        (1e21, "1e+21"),
+        (1.5e300, "1.5e+300"),
+        (f64::MAX, "1.7976931348623157e+308"),
+        (0.000001, "0.000001"),
+        (0.0000012, "0.0000012"),
+        (1e-7, "1e-7"),
+        (-1.5e-10, "-1.5e-10"),
+        (5e-324, "5e-324"),
+    ];
+
+    for (value, expected) in cases {
+        assert_eq!(crate::value::number_to_string(value), expected);
+    }
+
+    let mut interpreter = Interpreter::default();
+    assert_eq!(interpret(&mut interpreter, "10 ** 21 + ' ' + 2 * 2.5 + ' ' + 1 / 30000000;"), JsValue::String("1e+21 5 3.3333333333333334e-8".into()));
+    assert_eq!(interpret(&mut interpreter, "String(10 ** 21) + (1 / 10000000).toString();"), JsValue::String("1e+211e-7".into()));
+    assert_eq!(format!("{}", interpret(&mut interpreter, "[10 ** 21, 5];").display(DisplayMode::Plain)), "[1e+21, 5]");
+}
+
+#[test]
+fn math_random_is_reproducible_with_seed() {
+    let code = "[Math.random(), Math.random(), Math.random()];";
+    let run_with_seed = |seed| {
+        let mut interpreter = Interpreter::default();
+        interpreter.set_random_seed(seed);
+        interpret(&mut interpreter, code)
+    };
+
+    assert_eq!(run_with_seed(42), run_with_seed(42));
+    assert_ne!(run_with_seed(42), run_with_seed(43));
+
+    let mut interpreter = Interpreter::default();
+    for _ in 0..1000 {
+        let value = interpreter.next_random();
+        assert!((0.0..1.0).contains(&value));
+    }
+    interpreter.set_random_seed(7);
+    assert!(interpret(&mut interpreter, "Math.random() < 1;").to_bool());
+}
+
+#[test]
+fn addition_converts_any_value_to_string() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "'' + undefined;"), JsValue::String("undefined".into()));
+    assert_eq!(interpret(&mut interpreter, "null + 'x';"), JsValue::String("nullx".into()));
+    assert_eq!(interpret(&mut interpreter, "'is ' + true;"), JsValue::String("is true".into()));
+    assert_eq!(interpret(&mut interpreter, "5 + '';"), JsValue::String("5".into()));
+    assert_eq!(interpret(&mut interpreter, "'' + [1, null, 'a', [2, 3]];"), JsValue::String("1,,a,2,3".into()));
+    assert_eq!(interpret(&mut interpreter, "'' + { a: 1 };"), JsValue::String("[object Object]".into()));
+    assert_eq!(interpret(&mut interpreter, "[1] + [2];"), JsValue::String("12".into()));
+    assert_eq!(interpret(&mut interpreter, "true + 1;"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "null + 1;"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "[5] * 2;"), JsValue::Number(10.0));
+}
+
+#[test]
+fn object_listings_have_deterministic_order() {
+    let mut interpreter = Interpreter::default();
+
+    interpret(&mut interpreter, "let mixed = { zeta: 1, 10: 'ten', alpha: 2, 2: 'two', '01': 3, beta: { y: 1, x: 2 } };");
     assert_eq!(
-        interpret(&mut interpreter, "a['qwe-123'];"),
-        JsValue::String("string prop".to_string())
+        format!("{}", interpret(&mut interpreter, "Object.keys(mixed);").display(DisplayMode::Plain)),
+        r#"["2", "10", "01", "alpha", "beta", "zeta"]"#
     );
     assert_eq!(
-        interpret(&mut interpreter, "a['abc'];"),
-        JsValue::String("identifier prop".to_string())
+        format!("{}", interpret(&mut interpreter, "mixed;").display(DisplayMode::Json)),
+        r#"{"2":"two","10":"ten","01":3,"alpha":2,"beta":{"x":2,"y":1},"zeta":1}"#
     );
+}
+
+#[test]
+fn constant_literals_are_fresh_on_every_evaluation() {
+    let mut interpreter = Interpreter::default();
+
+    interpret(&mut interpreter, "function table() { return { name: 'unit', sizes: [1, -2, [3]], flags: { on: true, off: null } }; }");
+    assert_eq!(interpret(&mut interpreter, "let first = table(); first.sizes[2][0] = 30; first.flags.on = false; first.name = 'changed'; table().sizes[2][0];"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "table().flags.on;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "table().name;"), JsValue::String("unit".into()));
+    assert_eq!(interpret(&mut interpreter, "table() == table();"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "table().sizes[1];"), JsValue::Number(-2.0));
+
+    // literals with `__proto__` or non constant items are evaluated every time
+    assert_eq!(interpret(&mut interpreter, "let base = { kind: 'base' }; function make(x) { return [{ __proto__: base }, x]; } make(1)[0].kind;"), JsValue::String("base".into()));
+    assert_eq!(interpret(&mut interpreter, "make(7)[1];"), JsValue::Number(7.0));
+}
+
+#[test]
+fn calling_non_function_describes_callee() {
+    let interpreter = Interpreter::default();
+
+    assert_eq!(try_interpret(&interpreter, "let counter = { count: 5 }; counter.count(1);"), Err("TypeError: counter.count is not a function (got 5)".to_string()));
+    assert_eq!(try_interpret(&interpreter, "let name = 'abc'; name();"), Err("TypeError: name is not a function (got \"abc\")".to_string()));
+    assert_eq!(try_interpret(&interpreter, "let items = [1]; items[0]();"), Err("TypeError: items[0] is not a function (got 1)".to_string()));
+    assert_eq!(try_interpret(&interpreter, "let Missing; new Missing();"), Err("TypeError: Missing is not a constructor (got undefined)".to_string()));
+    assert_eq!(try_interpret(&interpreter, "function f() { return counter.count(); } f();"), Err("TypeError: counter.count is not a function (got 5)".to_string()));
+}
+
+#[test]
+fn property_access_on_null_or_undefined_is_type_error() {
+    let interpreter = Interpreter::default();
+
     assert_eq!(
-        interpret(&mut interpreter, "a.abc;"),
-        JsValue::String("identifier prop".to_string())
+        try_interpret(&interpreter, "let a;\na.x;"),
+        Err("TypeError: Cannot read properties of undefined (reading 'x')".to_string())
     );
     assert_eq!(
-        interpret(&mut interpreter, "a['hello ' + 123];"),
-        JsValue::String("hello 123".to_string())
+        try_interpret(&interpreter, "let b = null; b['key'] = 1;"),
+        Err("TypeError: Cannot set properties of null (setting 'key')".to_string())
     );
+    assert_eq!(try_interpret(&interpreter, "let c = 5; c.x;"), Ok(JsValue::Undefined));
+}
+
+#[test]
+fn primitives_are_boxed_for_member_access() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "'hello'.length;"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "'hello'[1];"), JsValue::String("e".into()));
+    assert_eq!(interpret(&mut interpreter, "'hello'.toUpperCase();"), JsValue::String("HELLO".into()));
+    assert_eq!(interpret(&mut interpreter, "let s = ' a,b '; s.trim().split(',')[1];"), JsValue::String("b".into()));
+    assert_eq!(interpret(&mut interpreter, "'abcdef'.slice(2, 5);"), JsValue::String("cde".into()));
+    assert_eq!(interpret(&mut interpreter, "'abc'.indexOf('c');"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "let n = 2.5; n.toFixed(0);"), JsValue::String("3".into()));
+    assert_eq!(interpret(&mut interpreter, "let m = 255; m.toString(16);"), JsValue::String("ff".into()));
+    assert_eq!(interpret(&mut interpreter, "true.toString();"), JsValue::String("true".into()));
+    assert_eq!(interpret(&mut interpreter, "'abc'.missing;"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "String.prototype.shout = function () { return this + '!'; }; 'hey'.shout();"), JsValue::String("hey!".into()));
+    assert_eq!(interpret(&mut interpreter, "String(12) + Number('3') + Boolean(0);"), JsValue::String("123false".into()));
+}
+
+#[test]
+fn string_methods_cover_everyday_use() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "'a-b-c'.split('-', 2)[1];"), JsValue::String("b".into()));
+    assert_eq!(interpret(&mut interpreter, "'a-b-c'.split('-', 2)[2];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "'  x  '.trimStart() + '|' + '  x  '.trimEnd();"), JsValue::String("x  |  x".into()));
+    assert_eq!(interpret(&mut interpreter, "'5'.padStart(3, '0');"), JsValue::String("005".into()));
+    assert_eq!(interpret(&mut interpreter, "'ab'.padEnd(7, 'xyz');"), JsValue::String("abxyzxy".into()));
+    assert_eq!(interpret(&mut interpreter, "'abc'.padStart(2);"), JsValue::String("abc".into()));
+    assert_eq!(interpret(&mut interpreter, "'abcabc'.lastIndexOf('b');"), JsValue::Number(4.0));
+    assert_eq!(interpret(&mut interpreter, "'abcabc'.lastIndexOf('b', 3);"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "'abc'.lastIndexOf('z');"), JsValue::Number(-1.0));
+    assert_eq!(interpret(&mut interpreter, "'abc'.includes('a', 1);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "'abcdef'.slice(-3, -1);"), JsValue::String("de".into()));
+    assert_eq!(interpret(&mut interpreter, "'abcdef'.substring(4, 1);"), JsValue::String("bcd".into()));
+}
+
+#[test]
+fn strings_are_indexed_by_utf16_code_units() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "let s = 'héllo😀'; s.length;"), JsValue::Number(7.0));
+    assert_eq!(interpret(&mut interpreter, "s[1];"), JsValue::String("é".into()));
+    assert_eq!(interpret(&mut interpreter, "s[5];"), JsValue::String("\u{FFFD}".into()));
+    assert_eq!(interpret(&mut interpreter, "s[7];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "s['01'];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "s.codePointAt(5);"), JsValue::Number(0x1F600 as f64));
+    assert_eq!(interpret(&mut interpreter, "s.codePointAt(6);"), JsValue::Number(0xDE00 as f64));
+    assert_eq!(interpret(&mut interpreter, "s.at(-3) + s.at(0);"), JsValue::String("oh".into()));
+    assert_eq!(interpret(&mut interpreter, "s.at(9);"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "[...s][5];"), JsValue::String("😀".into()));
+    assert_eq!(interpret(&mut interpreter, "[...s][6];"), JsValue::Undefined);
+}
+
+#[test]
+fn array_constructor_creates_arrays() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "Array.isArray([1]) && !Array.isArray('a') && !Array.isArray({ 0: 1 });"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Array.from('a😀')[1];"), JsValue::String("😀".into()));
+    assert_eq!(interpret(&mut interpreter, "Array.from([1, 2], function (x, i) { return x * 10 + i; })[1];"), JsValue::Number(21.0));
+    assert_eq!(interpret(&mut interpreter, "let like = Array.from({ length: 2, 0: 'a' }); like[0] + like[1];"), JsValue::String("aundefined".into()));
+    assert_eq!(interpret(&mut interpreter, "let copy = [1]; Array.from(copy) != copy;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Array.of(7)[0] + Array(1, 2)[1];"), JsValue::Number(9.0));
+    assert_eq!(interpret(&mut interpreter, "Object.keys(Array(2))[1];"), JsValue::String("1".into()));
+
+    assert_eq!(try_interpret(&Interpreter::default(), "Array(-1);"), Err("RangeError: Invalid array length".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "Array.from([], 5);"), Err("TypeError: 5 is not a function".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "Array.from(null);"), Err("TypeError: null is not iterable".to_string()));
+}
+
+#[test]
+fn declaration_errors_are_returned_instead_of_panicking() {
+
+    assert!(try_interpret(&Interpreter::default(), "class A {} class A {}").is_err());
+    assert!(try_interpret(&Interpreter::default(), "function f(a = missing()) { return a; }").is_err());
+    assert!(try_interpret(&Interpreter::default(), "let g = function (a = missing()) { return a; };").is_err());
+    assert!(try_interpret(&Interpreter::default(), "class B { method(a = missing()) {} }").is_err());
+}
+
+#[test]
+fn objects_convert_to_primitives_through_value_of_and_to_string() {
+    let mut interpreter = Interpreter::default();
+
+    interpret(&mut interpreter, "let money = { amount: 5, valueOf: function () { return this.amount; }, toString: function () { return this.amount + '$'; } };");
+    assert_eq!(interpret(&mut interpreter, "money * 2 + money;"), JsValue::Number(15.0));
+    assert_eq!(interpret(&mut interpreter, "'total: ' + money;"), JsValue::String("total: 5".into()));
+    assert_eq!(interpret(&mut interpreter, "String(money);"), JsValue::String("5$".into()));
+    assert_eq!(interpret(&mut interpreter, "money > 4 && -money == -5;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "let total = money; total += 1; total;"), JsValue::Number(6.0));
+    assert_eq!(interpret(&mut interpreter, "class Point { constructor(x) { this.x = x; } toString() { return 'P' + this.x; } } 'at ' + new Point(1);"), JsValue::String("at P1".into()));
+    assert_eq!(interpret(&mut interpreter, "[1, 2] + '|' + {};"), JsValue::String("1,2|[object Object]".into()));
+}
+
+#[test]
+fn destructuring_assignment_assigns_nested_targets() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "let a = 1; let b = 2; [a, b] = [b, a]; a * 10 + b;"), JsValue::Number(21.0));
+    assert_eq!(interpret(&mut interpreter, "let x; let y; let obj = { x: 3, nested: { z: 4 } }; ({ x, nested: { z: y } } = obj); x + y;"), JsValue::Number(7.0));
+    assert_eq!(interpret(&mut interpreter, "let first; let rest; [first, , ...rest] = [1, 2, 3, 4]; first + rest[0] + rest[1];"), JsValue::Number(8.0));
+    assert_eq!(interpret(&mut interpreter, "let d; [d = 5] = []; d;"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "let target = {}; [target.value, target['key']] = 'ab'; target.value + target.key;"), JsValue::String("ab".into()));
+    assert_eq!(interpret(&mut interpreter, "let result = [a] = [9]; result[0];"), JsValue::Number(9.0));
+    assert_eq!(interpret(&mut interpreter, "let length; ({ length } = 'four'); length;"), JsValue::Number(4.0));
+    assert_eq!(interpret(&mut interpreter, "[...[1, 2], 3][2];"), JsValue::Number(3.0));
+
+    assert_eq!(try_interpret(&Interpreter::default(), "let a; ({ a } = null);"), Err("TypeError: Cannot destructure 'null' as it is null.".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "let a; [a] = 5;"), Err("TypeError: number is not iterable".to_string()));
+    assert!(crate::parser::Parser::parse_code_to_ast("let a; [a] += [1];").is_err());
+    assert!(crate::parser::Parser::parse_code_to_ast("let a; [...a, b] = [1];").is_err());
+    assert!(crate::parser::Parser::parse_code_to_ast("[1] = [1];").is_err());
+}
+
+#[test]
+fn break_continue_and_return_unwind_to_their_construct() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "let sum = 0; for (let i = 0; i < 10; i += 1) { if (i == 2) { continue; } if (i == 5) { break; } sum += i; } sum;"), JsValue::Number(8.0));
+    assert_eq!(interpret(&mut interpreter, "let n = 0; while (true) { n += 1; if (n == 3) { break; } } n;"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "function find(limit) { for (let i = 0; i < 10; i += 1) { if (i * i > limit) { return i; } } return 0 - 1; } find(20);"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "function sign(x) { if (x < 0) { return 0 - 1; } return 1; } sign(0 - 5) + sign(5) * 10;"), JsValue::Number(9.0));
+    assert_eq!(interpret(&mut interpreter, "function noReturn() { 5; } noReturn();"), JsValue::Undefined);
+}
+
+#[test]
+fn switch_falls_through_until_break() {
+    let mut interpreter = Interpreter::default();
+
+    interpret(&mut interpreter, "
+        function describe(value) {
+            let result = '';
+            switch (value) {
+                case 1:
+                    result += 'one ';
+                case 2:
+                    result += 'two ';
+                    break;
+                case '1':
+                    result = 'string';
+                    break;
+                default:
+                    result = 'other';
+            }
+            return result;
+        }
+    ");
+
+    assert_eq!(interpret(&mut interpreter, "describe(1);"), JsValue::String("one two ".into()));
+    assert_eq!(interpret(&mut interpreter, "describe(2);"), JsValue::String("two ".into()));
+    assert_eq!(interpret(&mut interpreter, "describe('1');"), JsValue::String("string".into()));
+    assert_eq!(interpret(&mut interpreter, "describe(3);"), JsValue::String("other".into()));
+    assert_eq!(interpret(&mut interpreter, "let odd = 0; for (let i = 0; i < 5; i += 1) { switch (i % 2) { case 0: continue; } odd += 1; } odd;"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "function pick(x) { switch (x) { default: return 'd'; case 1: return 'one'; } } pick(1) + pick(2);"), JsValue::String("oned".into()));
+    assert!(crate::parser::Parser::parse_code_to_ast("switch (1) { default: break; default: break; }").is_err());
+}
+
+#[test]
+fn global_this_reflects_global_bindings() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "globalThis.console == console;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "globalThis.globalThis == globalThis;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "globalThis.x = 5; x;"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "x = 7; globalThis.x;"), JsValue::Number(7.0));
+    assert_eq!(interpret(&mut interpreter, "globalThis['Math'].max(1, 2);"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "globalThis.missing;"), JsValue::Undefined);
+}
+
+#[test]
+fn to_boolean_treats_nan_and_zeros_as_false() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "!!NaN;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "!!-0;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "!!'';"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "!!'0' && !!' ' && !!{} && !![] && !!-1 && !!Infinity;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Boolean(0 / 0);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "NaN ? 'yes' : 'no';"), JsValue::String("no".into()));
+    assert_eq!(interpret(&mut interpreter, "NaN || 'fallback';"), JsValue::String("fallback".into()));
+    assert!(interpret(&mut interpreter, "NaN && 'never';").to_number().is_nan());
+    assert_eq!(interpret(&mut interpreter, "let taken = 'no'; if (Number('abc')) { taken = 'yes'; } taken;"), JsValue::String("no".into()));
+    assert_eq!(interpret(&mut interpreter, "let loops = 0; while (NaN) { loops += 1; } loops;"), JsValue::Number(0.0));
+}
+
+#[test]
+fn self_tail_calls_do_not_grow_the_stack() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "function sum(n, acc) { if (n == 0) { return acc; } return sum(n - 1, acc + n); } sum(100000, 0);"), JsValue::Number(5000050000.0));
+    assert_eq!(interpret(&mut interpreter, "let counter = { count: 0, run: function (n) { if (n == 0) { return this.count; } this.count += 1; return this.run(n - 1); } }; counter.run(50000);"), JsValue::Number(50000.0));
+    assert_eq!(interpret(&mut interpreter, "function isEven(n) { if (n == 0) { return true; } return isOdd(n - 1); } function isOdd(n) { if (n == 0) { return false; } return isEven(n - 1); } isEven(10);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "function max(a, b) { return Math.max(a, b); } max(2, 5);"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "function viaEval() { return eval('sum(3, 0)'); } viaEval();"), JsValue::Number(6.0));
+    assert_eq!(interpret(&mut interpreter, "function loop() { let i = 0; while (true) { i += 1; if (i > 3) { return sum(i, 0); } } } loop();"), JsValue::Number(10.0));
+}
+
+#[test]
+fn builtin_globals_are_read_only() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(try_interpret(&interpreter, "console = 5;"), Err("TypeError: Cannot assign to read only property 'console'".to_string()));
+    assert_eq!(try_interpret(&interpreter, "Math.PI = 3;"), Err("TypeError: Cannot assign to read only property 'PI'".to_string()));
+    assert!(try_interpret(&interpreter, "globalThis.Object = 1;").is_err());
+    assert!(try_interpret(&interpreter, "console.log = function () {};").is_err());
+    assert!(try_interpret(&interpreter, "Object.assign(Math, { answer: 42 });").is_err());
+    assert!(try_interpret(&interpreter, "Math.__proto__ = {};").is_err());
+
+    // declarations shadow builtins, prototypes & globals of scripts stay writable
+    assert_eq!(interpret(&mut interpreter, "function f(Math) { Math = 2; return Math; } f(1);"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "let console = 1; console = 2; console;"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "String.prototype.shout = function () { return this + '!'; }; 'hi'.shout();"), JsValue::String("hi!".into()));
+
+    let interpreter = Interpreter::default();
+    interpreter.set_mutable_globals(true);
+    assert_eq!(try_interpret(&interpreter, "Math.PI = 3; Math.PI;"), Ok(JsValue::Number(3.0)));
+    assert_eq!(try_interpret(&interpreter, "Infinity = 0; Infinity;"), Ok(JsValue::Number(0.0)));
+}
+
+#[test]
+fn builtin_globals_list_matches_global_object() {
+    let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "globalThis;");
+
+    let global_object = interpreter.global_environment().borrow().get_global_object().unwrap();
+    let mut names: Vec<String> = global_object.borrow().properties.keys().cloned().collect();
+    let mut builtins: Vec<String> = BUILTIN_GLOBALS.iter().map(|x| x.to_string()).collect();
+    names.sort();
+    builtins.sort();
+    assert_eq!(names, builtins);
+}
+
+#[test]
+fn spread_rest_and_apply_share_calling_convention() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "function sum(...values) { let result = 0; for (let i = 0; i < 4; i += 1) { if (values[i]) { result += values[i]; } } return result; } sum(1, 2, 3);"), JsValue::Number(6.0));
+    assert_eq!(interpret(&mut interpreter, "let numbers = [1, 2, 3]; sum(...numbers, 4);"), JsValue::Number(10.0));
+    assert_eq!(interpret(&mut interpreter, "function tail(first, ...rest) { return rest; } tail(1, 2, 3)[1];"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "tail(1)[0];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "Math.max(...[4, 9, 2]);"), JsValue::Number(9.0));
+    assert_eq!(interpret(&mut interpreter, "sum.apply(undefined, [5, 6]);"), JsValue::Number(11.0));
+    assert_eq!(interpret(&mut interpreter, "Math.max.apply(null, numbers);"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "function getName(greeting) { return greeting + this.name; } getName.call({ name: 'Bob' }, 'Hi ');"), JsValue::String("Hi Bob".into()));
+    assert_eq!(interpret(&mut interpreter, "function withDefault(a = 2) { return a; } withDefault(undefined);"), JsValue::Number(2.0));
+}
+
+#[test]
+fn builtin_error_types_support_instanceof() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "let error = new TypeError('bad value'); error.message;"), JsValue::String("bad value".into()));
+    assert_eq!(interpret(&mut interpreter, "error.name;"), JsValue::String("TypeError".into()));
+    assert_eq!(interpret(&mut interpreter, "error.stack;"), JsValue::String("TypeError: bad value".into()));
+    assert_eq!(interpret(&mut interpreter, "error instanceof TypeError;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "error instanceof Error;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "error instanceof RangeError;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "RangeError('x').toString();"), JsValue::String("RangeError: x".into()));
+    assert_eq!(interpret(&mut interpreter, "String(Error());"), JsValue::String("Error".into()));
+    assert_eq!(interpret(&mut interpreter, "class ParseError extends SyntaxError {} new ParseError() instanceof Error;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "5 instanceof Error;"), JsValue::Boolean(false));
+
+    let ast = crate::parser::Parser::parse_code_to_ast("throw new RangeError('out of range');").unwrap();
+    assert_eq!(interpreter.interpret(&ast), Err("RangeError: out of range".to_string()));
+}
+
+#[test]
+fn values_are_displayed_in_plain_and_json_modes() {
+    let mut interpreter = Interpreter::default();
+    let value = interpret(&mut interpreter, "let value = { list: [1, 'a\"b', undefined], nested: { flag: true, nothing: null, skipped: undefined } }; value;");
+    let list = interpret(&mut interpreter, "value.list;");
+
+    assert_eq!(format!("{}", list.display(DisplayMode::Plain)), "[1, \"a\"b\", undefined]");
+    assert_eq!(format!("{}", list.display(DisplayMode::Json)), "[1,\"a\\\"b\",null]");
+    assert_eq!(format!("{}", list), "[\x1b[36m1\x1b[0m, \x1b[93m\"a\"b\"\x1b[0m, \x1b[37mundefined\x1b[0m]");
+
+    let JsValue::Object(object) = &value else { panic!("Expected object") };
+    let nested = object.borrow().get_property_value("nested");
+    let nested_json = format!("{}", nested.display(DisplayMode::Json));
+    assert!(nested_json == "{\"flag\":true,\"nothing\":null}" || nested_json == "{\"nothing\":null,\"flag\":true}");
+
+    assert_eq!(format!("{}", JsValue::Number(f64::NAN).display(DisplayMode::Json)), "null");
+    assert_eq!(format!("{}", interpret(&mut interpreter, "new TypeError('x');").display(DisplayMode::Json)), "\"TypeError: x\"");
+}
+
+#[test]
+fn functions_are_called_in_their_defining_scope() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "function makeCounter() { let count = 0; return function () { count += 1; return count; }; } let counter = makeCounter(); counter(); counter();"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "let value = 'global'; function read() { return value; } function shadow() { let value = 'local'; return read(); } shadow();"), JsValue::String("global".into()));
+    assert_eq!(interpret(&mut interpreter, "function readLater() { return later; } let later = 5; readLater();"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "function add(a, b) { return a + b; } let addTen = add.bind(null, 10); addTen(5);"), JsValue::Number(15.0));
+    assert_eq!(interpret(&mut interpreter, "let user = { name: 'Ann' }; function greet() { return this.name; } let bound = greet.bind(user); bound();"), JsValue::String("Ann".into()));
+    assert_eq!(interpret(&mut interpreter, "bound.call({ name: 'Bob' });"), JsValue::String("Ann".into()));
+}
+
+#[test]
+fn unary_operators_follow_spec_coercions() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "-'3';"), JsValue::Number(-3.0));
+    assert_eq!(interpret(&mut interpreter, "+true + +'';"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "10 - -2;"), JsValue::Number(12.0));
+    assert_eq!(interpret(&mut interpreter, "!'' && !!{};"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "typeof null;"), JsValue::String("object".into()));
+    assert_eq!(interpret(&mut interpreter, "typeof notDeclaredAnywhere;"), JsValue::String("undefined".into()));
+    assert_eq!(interpret(&mut interpreter, "typeof function () {};"), JsValue::String("function".into()));
+    assert_eq!(interpret(&mut interpreter, "let calls = 0; function count() { calls += 1; } void count();"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "calls;"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "'abcdef'.slice(-4, -1);"), JsValue::String("cde".into()));
+}
+
+#[test]
+fn empty_statements_are_allowed_anywhere() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, ";;let a = 1;; { ; } if (a) ; while (false) ; function f() {}; a;;"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "{ // nothing here\n }\n a; // trailing comment"), JsValue::Number(1.0));
+    assert_eq!(crate::parser::Parser::parse_code_to_ast("let x = (1").unwrap_err(), "Unexpected end of input, expected \")\"");
+    assert_eq!(crate::parser::Parser::parse_code_to_ast("{ let y = 1;").unwrap_err(), "Unexpected end of input, expected \"}\"");
+    assert!(crate::parser::Parser::parse_code_to_ast("let z = ;").is_err());
+}
+
+#[test]
+fn object_literal_supports_proto_key_and_trailing_commas() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "let base = { greet: function () { return 'hi ' + this.name; }, }; let user = { __proto__: base, name: 'Ann', }; user.greet();"), JsValue::String("hi Ann".into()));
+    assert_eq!(interpret(&mut interpreter, "let plain = { __proto__: 5 }; plain.__proto__ == Object.prototype;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "let computed = { ['__proto__']: 5 }; computed.__proto__;"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "function sum(a, b,) { return a + b; } sum(1, 2,);"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "sum(...[1, 2,]);"), JsValue::Number(3.0));
+}
+
+#[test]
+fn proto_accessor_and_object_prototype_functions() {
+    let mut interpreter = Interpreter::default();
+
+    interpret(&mut interpreter, "let animal = { sound: 'growl' }; let dog = { name: 'Rex' }; dog.__proto__ = animal;");
+    assert_eq!(interpret(&mut interpreter, "dog.sound;"), JsValue::String("growl".into()));
+    assert_eq!(interpret(&mut interpreter, "dog.__proto__ == animal;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.getPrototypeOf(dog) == animal;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.keys(dog);"), JsValue::Object(JsObject::array(vec![JsValue::String("name".into())]).to_ref()));
+    assert_eq!(interpret(&mut interpreter, "dog.__proto__ = 5; dog.sound;"), JsValue::String("growl".into()));
+
+    assert_eq!(interpret(&mut interpreter, "let cat = Object.setPrototypeOf({}, { sound: 'meow' }); cat.sound;"), JsValue::String("meow".into()));
+    assert_eq!(interpret(&mut interpreter, "Object.getPrototypeOf(animal) == Object.prototype;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.getPrototypeOf(Object.prototype);"), JsValue::Null);
+    assert_eq!(interpret(&mut interpreter, "function f() {} f.__proto__ == Object.getPrototypeOf(function () {});"), JsValue::Boolean(true));
+
+    let ast = crate::parser::Parser::parse_code_to_ast("animal.__proto__ = dog;").unwrap();
+    assert_eq!(interpreter.interpret(&ast), Err("TypeError: Cyclic __proto__ value".to_string()));
+    let ast = crate::parser::Parser::parse_code_to_ast("Object.setPrototypeOf(dog, null);").unwrap();
+    assert!(interpreter.interpret(&ast).is_err());
+}
+
+#[test]
+fn array_literal_supports_holes_and_nesting() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(format!("{}", interpret(&mut interpreter, "let items = [1, , [2, [3,]], 4 + 1,]; items;").display(DisplayMode::Plain)), "[1, <1 empty item>, [2, [3]], 5]");
+    assert_eq!(interpret(&mut interpreter, "items[2][1][0];"), JsValue::Number(3.0));
+    assert_eq!(format!("{}", interpret(&mut interpreter, "[, , ];").display(DisplayMode::Plain)), "[<2 empty items>]");
+    assert!(crate::parser::Parser::parse_code_to_ast("[1, 2").is_err());
+}
+
+#[test]
+fn sparse_arrays_keep_holes() {
+    let mut interpreter = Interpreter::default();
+    let display = |value: JsValue| value.display(DisplayMode::Plain).to_string();
+
+    assert_eq!(interpret(&mut interpreter, "let a = [1, , 3, ,]; a.length;"), JsValue::Number(4.0));
+    assert_eq!(display(interpret(&mut interpreter, "Object.keys(a);")), "[\"0\", \"2\"]");
+    assert_eq!(display(interpret(&mut interpreter, "a;")), "[1, <1 empty item>, 3, <1 empty item>]");
+    assert_eq!(display(interpret(&mut interpreter, "[...a];")), "[1, undefined, 3, undefined]");
+    assert_eq!(interpret(&mut interpreter, "String(a);"), JsValue::String("1,,3,".into()));
+    assert_eq!(interpret(&mut interpreter, "a;").display(DisplayMode::Json).to_string(), "[1,null,3,null]");
+
+    assert_eq!(interpret(&mut interpreter, "let b = []; b[1000] = 1; b.length;"), JsValue::Number(1001.0));
+    assert_eq!(display(interpret(&mut interpreter, "b;")), "[<1000 empty items>, 1]");
+    assert_eq!(display(interpret(&mut interpreter, "Object.entries(b);")), "[[\"1000\", 1]]");
+
+    assert_eq!(display(interpret(&mut interpreter, "b.length = 2; b[0] = 'x'; b;")), "[\"x\", <1 empty item>]");
+    assert_eq!(interpret(&mut interpreter, "b.length = 0; b[1000];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "b.length;"), JsValue::Number(0.0));
+
+    let ast = crate::parser::Parser::parse_code_to_ast("b.length = 1.5;").unwrap();
+    assert_eq!(interpreter.interpret(&ast), Err("RangeError: Invalid array length".to_string()));
 }
 
 #[test]
-fn object_function_property() {
-    let code = "
-        let a = {
-            b: function(a,b) {
-                return a * 2 + b;
-            }
-        };
+fn fractional_array_indices_are_reported_when_enabled() {
+    let mut interpreter = Interpreter::default();
 
-        a.b(3, 2);
-    ";
-    assert_eq!(eval_code(code), JsValue::Number(8.0));
+    assert_eq!(interpret(&mut interpreter, "let a = [1, 2]; a[0.5] = 3; a[0.5];"), JsValue::Number(3.0));
+
+    interpreter.set_fractional_index_check(FractionalIndexCheck::Deny);
+
+    assert_eq!(try_interpret(&interpreter, "a[3 / 2];"), Err("RangeError: array is indexed with 1.5, it's used as a property key instead of an index".to_string()));
+    assert_eq!(try_interpret(&interpreter, "a[0 / 0] = 1;"), Err("RangeError: array is indexed with NaN, it's used as a property key instead of an index".to_string()));
+    assert_eq!(try_interpret(&interpreter, "a[4 / 2] = 5; a[2];"), Ok(JsValue::Number(5.0)));
+    assert_eq!(try_interpret(&interpreter, "let o = {}; o[1.5] = 1; o[1.5];"), Ok(JsValue::Number(1.0)));
+    assert_eq!(try_interpret(&interpreter, "a.length;"), Ok(JsValue::Number(3.0)));
 }
 
 #[test]
-fn nested_member_expression_works() {
-    let code = "
-    let a = {
-        b: {
-            c: {
-                d: 'qwerty'
-            }
-        }
-    };
-    a.b.c.d;";
-    assert_eq!(eval_code(code), JsValue::String("qwerty".to_string()));
+fn object_is_uses_same_value() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "Object.is(0 / 0, 0 / 0);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.is(0, -0);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Object.is(-0, -0);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.is('a', 'a');"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.is({}, {});"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "let o = {}; Object.is(o, o);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.is(undefined);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.is(1, '1');"), JsValue::Boolean(false));
+
+    assert!(JsValue::Number(0.0).is_same_value_zero(&JsValue::Number(-0.0)));
+    assert!(JsValue::Number(f64::NAN).is_same_value_zero(&JsValue::Number(f64::NAN)));
+    assert!(!JsValue::Number(1.0).is_same_value_zero(&JsValue::String("1".into())));
 }
 
 #[test]
-fn assign_to_object_property_works() {
-    let code = "
-        let a = { b: 10 };
-        a.b = 20;
-        a.b;
-    ";
-    assert_eq!(eval_code(code), JsValue::Number(20.0));
+fn in_operator_checks_property_chain() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "let point = { x: 1 }; 'x' in point;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "'toString' in point;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "'y' in point;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "let sparse = [1, , 3]; 0 in sparse && !(1 in sparse);"), JsValue::Boolean(true));
+
+    assert_eq!(try_interpret(&interpreter, "'a' in 'abc';"), Err("TypeError: Cannot use 'in' operator to search for 'a' in abc".to_string()));
 }
 
 #[test]
-fn mutate_object_as_reference_works() {
-    let code = "
-        let a = { b: 10 };
-        let c = { d: a };
-        a.b = 25;
-        c.d.b;
-    ";
-    assert_eq!(eval_code(code), JsValue::Number(25.0));
+fn strict_arithmetic_rejects_mixed_operands() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "'5' * 2;"), JsValue::Number(10.0));
+
+    interpreter.set_strict_arithmetic(true);
+
+    assert_eq!(try_interpret(&interpreter, "'5' * 2;"), Err("TypeError: Cannot apply '*' to string and number in strict arithmetic mode".to_string()));
+    assert_eq!(try_interpret(&interpreter, "'n = ' + 1;"), Err("TypeError: Cannot apply '+' to string and number in strict arithmetic mode".to_string()));
+    assert_eq!(try_interpret(&interpreter, "let x = 1; x -= null;"), Err("TypeError: Cannot apply '-=' to number and null in strict arithmetic mode".to_string()));
+    assert_eq!(try_interpret(&interpreter, "let o = { valueOf: function () { return 2; } }; o * 3 + 2 ** 2;"), Ok(JsValue::Number(10.0)));
+    assert_eq!(try_interpret(&interpreter, "let s = 'a'; s += 'b'; s + 'c';"), Ok(JsValue::String("abc".into())));
+    assert_eq!(try_interpret(&interpreter, "1 < '2';"), Ok(JsValue::Boolean(true)));
 }
 
 #[test]
-fn object_method_this_expression() {
+fn eval_runs_code_in_caller_scope() {
     let mut interpreter = Interpreter::default();
 
-    let code = "
-        let a = {
-          abc: 10,
-          getAbc: function(a, b) {
-            return this.abc;
-          },
-          setAbc: function(newValue) {
-            this.abc = newValue;
-          }
-        };
+    assert_eq!(interpret(&mut interpreter, "let x = 2; eval('x * 3');"), JsValue::Number(6.0));
+    assert_eq!(interpret(&mut interpreter, "function scoped() { let y = 10; return eval('let z = 1; x + y + z'); } scoped();"), JsValue::Number(13.0));
+    assert_eq!(interpret(&mut interpreter, "eval('x = 7'); x;"), JsValue::Number(7.0));
+    assert_eq!(interpret(&mut interpreter, "typeof z;"), JsValue::String("undefined".into()));
+    assert_eq!(interpret(&mut interpreter, "eval(5);"), JsValue::Number(5.0));
 
-        a.getAbc();
-    ";
-    assert_eq!(interpret(&mut interpreter, code), JsValue::Number(10.0));
-    assert_eq!(
-        interpret(&mut interpreter, "a.setAbc(25); a.getAbc();"),
-        JsValue::Number(25.0)
-    );
+    let ast = crate::parser::Parser::parse_code_to_ast("eval('let x = (');").unwrap();
+    assert_eq!(interpreter.interpret(&ast), Err("SyntaxError: Unexpected end of input, expected \")\"".to_string()));
 }
 
 #[test]
-fn comparison() {
+fn this_is_bound_by_the_way_function_is_called() {
     let mut interpreter = Interpreter::default();
 
-    assert_eq!(interpret(&mut interpreter, "'abc' == 'abc'"), JsValue::Boolean(true));
-    assert_eq!(interpret(&mut interpreter, "'abc' == 'qwe'"), JsValue::Boolean(false));
-    assert_eq!(interpret(&mut interpreter, "123 == 123"), JsValue::Boolean(true));
-    assert_eq!(interpret(&mut interpreter, "123 == 456"), JsValue::Boolean(false));
-    assert_eq!(interpret(&mut interpreter, "true == true"), JsValue::Boolean(true));
-    assert_eq!(interpret(&mut interpreter, "true == false"), JsValue::Boolean(false));
-    assert_eq!(interpret(&mut interpreter, "false == false"), JsValue::Boolean(true));
-    assert_eq!(eval_code("let a = {}; let b = {}; a == b;"), JsValue::Boolean(false));
-    assert_eq!(eval_code("let a = {}; let b = a; a == b;"), JsValue::Boolean(true));
+    interpret(&mut interpreter, "
+        function self() { return this; }
+        let user = { name: 'Ann', self: self, nested: function () { function inner() { return this; } return inner(); } };
+        function Point(x) { this.x = x; }
+    ");
+
+    assert_eq!(interpret(&mut interpreter, "user.self() == user;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "user['self']() == user;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "self();"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "const extracted = user.self; extracted();"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "user.nested();"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "let point = new Point(3); point.x;"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "self.call(5);"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "self.bind(user)() == user;"), JsValue::Boolean(true));
 }
 
 #[test]
-fn prototype_property_access() {
+fn function_values_share_body_with_syntax_tree() {
     let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "function make() { return function () { return 1; }; } let first = make(); let second = make();");
+
+    let get_body = |value: JsValue| match value {
+        JsValue::Object(object) => match &object.borrow().kind {
+            ObjectKind::Function(JsFunction::Ordinary(function)) => Rc::clone(&function.body),
+            _ => panic!("Expected ordinary function"),
+        },
+        _ => panic!("Expected object"),
+    };
 
-    let code = "
-        let prototype = {
-          a: 10
-        };
+    let first_body = get_body(interpret(&mut interpreter, "first;"));
+    let second_body = get_body(interpret(&mut interpreter, "second;"));
 
-        let target = { b: 30 };
-        setPrototypeOf(target, prototype);
-        target.a;
-    ";
-    assert_eq!(interpret(&mut interpreter, code), JsValue::Number(10.0));
+    assert!(Rc::ptr_eq(&first_body, &second_body));
+    assert_eq!(interpret(&mut interpreter, "first() + second();"), JsValue::Number(2.0));
 }
 
 #[test]
-fn prototype_mutable_property_access() {
+fn restoring_snapshot_reverts_global_bindings() {
+    let mut interpreter = Interpreter::with_globals([
+        ("answer".to_string(), JsValue::Number(42.0)),
+    ]);
+
+    interpret(&mut interpreter, "let a = 1; function readA() { return a; }");
+    let snapshot = interpreter.snapshot();
+
+    interpret(&mut interpreter, "let b = 2; a = 5; answer = 0;");
+    assert_eq!(interpret(&mut interpreter, "readA();"), JsValue::Number(5.0));
+
+    interpreter.restore(&snapshot);
+    assert_eq!(interpret(&mut interpreter, "readA();"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "answer;"), JsValue::Number(42.0));
+    assert_eq!(interpret(&mut interpreter, "typeof b;"), JsValue::String("undefined".into()));
+    assert_eq!(interpret(&mut interpreter, "let b = 3; b;"), JsValue::Number(3.0));
+}
+
+#[test]
+fn runtime_error_keeps_span_of_failed_node() {
+    let interpreter = Interpreter::default();
+    let code = "let user = null;\nfunction read() { return user.name; }\nread();";
+    let ast = crate::parser::Parser::parse_code_to_ast(code).unwrap();
+
+    assert!(interpreter.interpret(&ast).is_err());
+
+    let location = interpreter.take_error_location().unwrap();
+    assert_eq!(&code[location.span.start.row..location.span.end.row], "user.name");
+    assert_eq!(location.source, None);
+    assert_eq!(interpreter.take_error_location(), None);
+}
+
+#[test]
+fn runtime_error_inside_function_keeps_source_it_was_declared_in() {
+    let interpreter = Interpreter::default();
+    let library = "function read(user) {\n    return user.name;\n}";
+    interpreter.set_source(Some(Rc::new(ScriptSource { path: "lib.js".to_string(), code: library.to_string() })));
+    assert!(try_interpret(&interpreter, library).is_ok());
+
+    let main = "let a = 1;\nread(null);";
+    interpreter.set_source(Some(Rc::new(ScriptSource { path: "main.js".to_string(), code: main.to_string() })));
+    assert!(try_interpret(&interpreter, main).is_err());
+
+    let location = interpreter.take_error_location().unwrap();
+    let source = location.source.unwrap();
+    assert_eq!(source.path, "lib.js");
+    assert_eq!(&source.code[location.span.start.row..location.span.end.row], "user.name");
+}
+
+#[test]
+fn objects_can_be_accessed_while_being_used() {
     let mut interpreter = Interpreter::default();
 
-    let code = "
-        let prototype = {
-          a: 10
-        };
+    assert_eq!(interpret(&mut interpreter, "function count() { count.calls += 1; return count.calls; } count.calls = 0; count(); count();"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "let counter = { value: 1, next: function () { this.value *= 3; return this; } }; counter.next().value;"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "let result = (counter.value -= 1); result;"), JsValue::Number(2.0));
+    assert_eq!(format!("{}", interpret(&mut interpreter, "let items = [1]; items[0] = items; items;").display(DisplayMode::Plain)), "[[Circular]]");
+    assert_eq!(format!("{}", interpret(&mut interpreter, "let self = {}; self.me = self; Object.assign(self, self);").display(DisplayMode::Plain)), "{ me: [Circular] }");
 
-        let target = { b: 30 };
-        setPrototypeOf(target, prototype);
-        prototype.a = 50;
-        target.a;
-    ";
-    assert_eq!(interpret(&mut interpreter, code), JsValue::Number(50.0));
+    let ast = crate::parser::Parser::parse_code_to_ast("let a = {}; let b = {}; setPrototypeOf(a, b); setPrototypeOf(b, a);").unwrap();
+    assert_eq!(interpreter.interpret(&ast), Err("TypeError: Cyclic __proto__ value".to_string()));
 }
 
 #[test]
-fn two_objects_must_be_checked_for_equality_by_reference() {
-    let code = "
-       let a = { b: { c: 10 } };
+fn program_result_is_last_expression_statement() {
+    let mut interpreter = Interpreter::default();
 
-       let d = {
-         e: {
-           f: a
-         }
-       };
+    assert_eq!(interpret(&mut interpreter, "1 + 1; let a = 5; function f() {} ;"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "let b = 1;"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "{ a * 2; }"), JsValue::Number(10.0));
+}
 
-       d.e.f == a;
-    ";
-    assert_eq!(eval_code(code), JsValue::Boolean(true));
+#[test]
+fn lazy_globals_are_created_on_first_access() {
+    fn answer_object() -> JsValue {
+        JsValue::object([("value".to_string(), JsValue::Number(42.0))])
+    }
 
-    let code = "
-       let a = { b: { c: 10 } };
+    let mut interpreter = Interpreter::default();
+    interpreter.define_lazy_global("Answer", answer_object);
 
-       let d = {
-         e: {
-           f: {}
-         }
-       };
+    let global_object = interpreter.global_environment().borrow().get_global_object().unwrap();
+    assert!(!global_object.borrow().properties.contains_key("Answer"));
 
-       d.e.f == a;
-    ";
-    assert_eq!(eval_code(code), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Answer.value;"), JsValue::Number(42.0));
+    assert!(global_object.borrow().properties.contains_key("Answer"));
+    assert_eq!(interpret(&mut interpreter, "Answer.value = 1; globalThis.Answer.value;"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "Math.max(1, 3);"), JsValue::Number(3.0));
 }
 
 #[test]
-fn simple_class_usage() {
-    let code = "
-       class User {
-         constructor(name, age) {
-            this.name = name;
-            this.age = age;
-         }
+fn display_truncates_long_strings_and_arrays() {
+    let mut interpreter = Interpreter::default();
+    let limits = DisplayLimits { max_items: 3, max_string_length: 4 };
 
-         getUserInformation() {
-            return 'Name is ' + this.name + ', ' + this.age + ' years old';
-         }
-       }
+    let items = interpret(&mut interpreter, "let items = []; for (let i = 0; i < 10; i += 1) { items[i] = i; } items;");
+    assert_eq!(format!("{}", items.display(DisplayMode::Plain).with_limits(limits)), "[0, 1, 2, ... 7 more items]");
+    assert_eq!(format!("{}", items.display(DisplayMode::Json).with_limits(limits)), "[0,1,2,3,4,5,6,7,8,9]");
+    assert_eq!(format!("{}", items.display(DisplayMode::Plain).with_limits(DisplayLimits { max_items: 0, ..limits })), "[... 10 more items]");
 
-       let user = new User('Anton', 26);
-       user.getUserInformation();
-    ";
-    assert_eq!(eval_code(code), JsValue::String("Name is Anton, 26 years old".to_string()));
+    let text = interpret(&mut interpreter, "['abcdefgh', 'abc'];");
+    assert_eq!(format!("{}", text.display(DisplayMode::Plain).with_limits(limits)), "[\"abcd\"... 4 more characters, \"abc\"]");
+    assert_eq!(format!("{}", text.display(DisplayMode::Plain).with_limits(DisplayLimits::unlimited())), "[\"abcdefgh\", \"abc\"]");
 }
 
 #[test]
-fn class_proto_of_instance_should_be_equal_to_class_prototype() {
+fn weak_map_and_weak_set_use_object_identity() {
     let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "let a = {}; let b = {}; let map = new WeakMap([[a, 1]]); let set = new WeakSet(); set.add(b).add(b);");
+
+    assert_eq!(interpret(&mut interpreter, "map.get(a) + map.set(b, 2).get(b);"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "map.has({}) || map.get(1) != undefined;"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "map.delete(a) && !map.has(a) && !map.delete(a);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "set.has(b) && !set.has(a) && set.delete(b) && !set.has(b);"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.prototype.toString.call(map);"), JsValue::String("[object WeakMap]".into()));
+    assert_eq!(format!("{}", interpret(&mut interpreter, "set;").display(DisplayMode::Plain)), "WeakSet { <items unknown> }");
+    assert_eq!(interpret(&mut interpreter, "map.size;"), JsValue::Undefined);
+
+    assert_eq!(try_interpret(&Interpreter::default(), "let map = new WeakMap(); map.set(1, 2);"), Err("TypeError: Invalid value used as weak map key".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "new WeakSet([1]);"), Err("TypeError: Invalid value used in weak set".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "let set = new WeakSet(); WeakMap.prototype.get.call(set, {});"), Err("TypeError: Method WeakMap.prototype.get called on incompatible receiver object".to_string()));
+}
 
-    let code = "
-       class User {
-         constructor(name, age) {
-            this.name = name;
-            this.age = age;
-         }
+#[test]
+fn numbers_and_dates_are_formatted_for_people() {
+    let mut interpreter = Interpreter::default();
 
-         getUserInformation() {
-            return 'Name is ' + this.name + ', ' + this.age + ' years old';
-         }
-       }
+    assert_eq!(interpret(&mut interpreter, "(1234567.891).toLocaleString();"), JsValue::String("1,234,567.891".into()));
+    assert_eq!(interpret(&mut interpreter, "(-1234.5).toLocaleString('de-DE', { minimumFractionDigits: 2 });"), JsValue::String("-1.234,50".into()));
+    assert_eq!(interpret(&mut interpreter, "(0.125).toLocaleString('en', { maximumFractionDigits: 2 });"), JsValue::String("0.13".into()));
+    assert_eq!(interpret(&mut interpreter, "let big = 1000; big.toLocaleString('fr');"), JsValue::String("1\u{a0}000".into()));
+    assert_eq!(interpret(&mut interpreter, "formatDate(86400000 * 365, 'DD/MM/YYYY');"), JsValue::String("01/01/1971".into()));
 
-       let user = new User('Anton', 26);
-       user.getUserInformation();
-    ";
-    interpret(&mut interpreter, code);
-    let class = interpreter.environment.borrow().borrow().get_variable_value("User");
-    let class_instance = interpreter.environment.borrow().borrow().get_variable_value("user");
+    interpreter.set_locale(Locale::parse("de").unwrap());
+    assert_eq!(interpret(&mut interpreter, "big.toLocaleString();"), JsValue::String("1.000".into()));
 
-    if let JsValue::Object(class_object) = &class {
-        if let JsValue::Object(instance_object) = &class_instance {
-            let class_prototype = class_object.borrow().get_prototype();
-            let class_instance_proto = instance_object.borrow().get_proto().unwrap();
+    let ast = crate::parser::Parser::parse_code_to_ast("big.toLocaleString('xx');").unwrap();
+    assert_eq!(interpreter.interpret(&ast), Err("RangeError: Unsupported locale 'xx'".to_string()));
+}
 
-            if let JsValue::Object(class_prototype) = class_prototype {
-                assert!(Rc::ptr_eq(&class_prototype, &class_instance_proto));
+#[test]
+fn hooks_observe_statements_and_function_calls() {
+    struct Recorder {
+        events: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl ExecutionHooks for Recorder {
+        fn before_statement(&mut self, _: &Interpreter, statement: &AstStatement) {
+            if let AstStatement::ReturnStatement(_) = statement {
+                self.events.borrow_mut().push("return".to_string());
             }
         }
+
+        fn on_function_enter(&mut self, interpreter: &Interpreter, name: &str, span: &TextSpan) {
+            // code evaluated by a hook doesn't trigger hooks
+            let ast = crate::parser::Parser::parse_code_to_ast("calls += 1;").unwrap();
+            interpreter.interpret(&ast).unwrap();
+            self.events.borrow_mut().push(format!("enter {name} at line {}", span.start.line + 1));
+        }
+
+        fn on_function_exit(&mut self, _: &Interpreter, name: &str, _: &TextSpan) {
+            self.events.borrow_mut().push(format!("exit {name}"));
+        }
     }
+
+    let mut interpreter = Interpreter::default();
+    let events = Rc::new(RefCell::new(vec![]));
+    interpreter.set_hooks(Box::new(Recorder { events: Rc::clone(&events) }));
+
+    interpret(&mut interpreter, "let calls = 0;\nclass Point { constructor() {} norm() { return 1; } }\nfunction f() {\n  let p = new Point();\n  return p.norm();\n}\nf();");
+
+    assert_eq!(*events.borrow(), vec![
+        "enter f at line 3", "enter Point at line 2", "exit Point", "return", "enter norm at line 2", "return", "exit norm", "exit f",
+    ]);
+    assert_eq!(interpret(&mut interpreter, "calls;"), JsValue::Number(3.0));
+    assert!(interpreter.take_hooks().is_some());
 }
 
 #[test]
-fn prototypes_of_instances_of_same_class_equals() {
+fn class_fields_are_initialized_before_constructor_body() {
     let mut interpreter = Interpreter::default();
-    let code = "
-        class A { constructor(a) { this.a = a; } }
-        new A();
-    ";
-    let class_instance1 = interpret(&mut interpreter, code);
-    let class_instance2 = interpret(&mut interpreter, "new A();");
-
-    if let JsValue::Object(object1) = &class_instance1 {
-        if let JsValue::Object(object2) = &class_instance2 {
-            let prototype1 = object1.borrow().get_proto().unwrap();
-            let prototype2 = object2.borrow().get_proto().unwrap();
-            assert!(Rc::ptr_eq(&prototype1, &prototype2));
+    interpret(&mut interpreter, "
+        class Counter {
+            count = 1;
+            step = this.count * 2;
+            label;
+            constructor(start) { this.count = this.count + start; }
+            increment() { this.count += this.step; }
         }
-    }
+        class NamedCounter extends Counter {
+            name = 'total';
+        }
+        let counter = new NamedCounter(10);
+        counter.increment();
+    ");
+
+    assert_eq!(interpret(&mut interpreter, "counter.count;"), JsValue::Number(13.0));
+    assert_eq!(interpret(&mut interpreter, "counter.name;"), JsValue::String("total".into()));
+    let JsValue::Object(counter) = interpret(&mut interpreter, "counter;") else { panic!("counter is not an object") };
+    let mut keys = counter.borrow().enumerable_keys();
+    keys.sort();
+    assert_eq!(keys, vec!["count", "label", "name", "step"]);
+    assert_eq!(interpret(&mut interpreter, "Object.keys(Counter.prototype)[0];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "Counter.prototype.hasOwnProperty('increment');"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "class ParseError extends SyntaxError {} let error = new ParseError('bad'); error.message;"), JsValue::String("bad".into()));
 }
 
 #[test]
-fn function_constructor_as_class() {
-    let code = "
-        function User(name, age) {
-            this.name = name;
-            this.age = age;
-        }
+fn prototypes_point_back_at_their_constructors() {
+    let mut interpreter = Interpreter::default();
+    interpret(&mut interpreter, "
+        function Plain() {}
+        let Anonymous = function() {};
+        class User { constructor(name) { this.name = name; } greet() { return 'hi'; } }
+        class Admin extends User {}
+        let admin = new Admin('root');
+        let plain = new Plain();
+    ");
+
+    assert_eq!(interpret(&mut interpreter, "Plain.prototype.constructor == Plain;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Anonymous.prototype.constructor == Anonymous;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "plain.constructor == Plain;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "User.prototype.constructor == User;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "admin.constructor == Admin;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "admin.name;"), JsValue::String("root".into()));
+    assert_eq!(interpret(&mut interpreter, "let object = {}; object.constructor == Object;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "let error = new TypeError('x'); error.constructor == TypeError;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Object.keys(User.prototype)[0];"), JsValue::Undefined);
+}
 
-        console.log(User.prototype);
+#[test]
+fn proxy_traps_and_reflect() {
+    let mut interpreter = Interpreter::default();
 
-        User.prototype.getUserInformation = function() {
-            return 'Name is ' + this.name + ', ' + this.age + ' years old';
+    interpret(&mut interpreter, "
+        let reads = 0;
+        let target = { a: 1 };
+        let proxy = new Proxy(target, {
+            get: function (target, key, receiver) { reads += 1; if (receiver == proxy && key == 'missing') { return 'default'; } return target[key]; },
+        });
+        let doubled = new Proxy({}, { set: function (target, key, value) { target[key] = value * 2; return true; } });
+        let secret = new Proxy({}, { has: function (target, key) { return key == 'secret'; }, deleteProperty: function () { return false; } });
+    ");
+
+    assert_eq!(interpret(&mut interpreter, "proxy.a;"), JsValue::Number(1.0));
+    assert_eq!(interpret(&mut interpreter, "proxy.missing;"), JsValue::String("default".into()));
+    assert_eq!(interpret(&mut interpreter, "proxy.a = 5; target.a + reads;"), JsValue::Number(7.0));
+    assert_eq!(interpret(&mut interpreter, "doubled.x = 5; doubled.x += 1; doubled.x;"), JsValue::Number(22.0));
+    assert_eq!(interpret(&mut interpreter, "Object.assign(doubled, { b: 2 }); doubled.b;"), JsValue::Number(4.0));
+    assert_eq!(interpret(&mut interpreter, "Object.assign(secret, { b: 2 }); Reflect.has(secret, 'b');"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Reflect.has(secret, 'secret');"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "Reflect.has(secret, 'toString');"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Reflect.deleteProperty(secret, 'a');"), JsValue::Boolean(false));
+
+    assert_eq!(interpret(&mut interpreter, "Reflect.has({}, 'toString');"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "let pair = { a: 1, b: 2 }; Reflect.deleteProperty(pair, 'a'); Object.keys(pair)[0];"), JsValue::String("b".into()));
+    assert_eq!(interpret(&mut interpreter, "Reflect.get(pair, 'b');"), JsValue::Number(2.0));
+    assert_eq!(interpret(&mut interpreter, "Reflect.set(pair, 'c', 3) && pair.c;"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "Reflect.set(Math, 'PI', 3);"), JsValue::Boolean(false));
+    assert_eq!(interpret(&mut interpreter, "Reflect.apply(Math.max, null, [1, 3]);"), JsValue::Number(3.0));
+    assert_eq!(interpret(&mut interpreter, "Reflect.construct(TypeError, ['bad']).message;"), JsValue::String("bad".into()));
+
+    assert_eq!(try_interpret(&Interpreter::default(), "new Proxy(1, {});"), Err("TypeError: Cannot create proxy with a non-object as target or handler".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "Reflect.get(1, 'a');"), Err("TypeError: Reflect.get called on non-object".to_string()));
+    assert_eq!(try_interpret(&Interpreter::default(), "let broken = new Proxy({}, { get: 1 }); broken.a;"), Err("TypeError: proxy trap 'get' is not a function".to_string()));
+}
+
+#[test]
+fn script_yields_to_host_every_n_statements() {
+    let mut interpreter = Interpreter::default();
+    let seen_ticks = Rc::new(RefCell::new(Vec::new()));
+    let seen = Rc::clone(&seen_ticks);
+
+    interpret(&mut interpreter, "let ticks = 0;");
+    interpreter.set_yield_interval(10, Box::new(move |interpreter| {
+        // code run by the host doesn't count
+        let ticks = try_interpret(interpreter, "ticks;").unwrap().to_number();
+        seen.borrow_mut().push(ticks);
+
+        if ticks >= 20.0 { YieldAction::Stop } else { YieldAction::Continue }
+    }));
+
+    assert_eq!(try_interpret(&interpreter, "while (true) { ticks += 1; }"), Err("Error: Script stopped by the host".to_string()));
+    // program & loop are the first two statements, then every tick is a block & an expression statement,
+    // so the host sees every 5th tick & the statement which yields doesn't run
+    assert_eq!(*seen_ticks.borrow(), vec![3.0, 8.0, 13.0, 18.0, 23.0]);
+    assert_eq!(interpret(&mut interpreter, "ticks;"), JsValue::Number(23.0));
+
+    interpreter.clear_yield_interval();
+    assert_eq!(interpret(&mut interpreter, "ticks = 0; for (let i = 0; i < 100; i += 1) { ticks += 1; } ticks;"), JsValue::Number(100.0));
+}
+
+#[test]
+fn stopped_statement_is_not_passed_to_hooks() {
+    struct Counter {
+        before: Rc<Cell<usize>>,
+        after: Rc<Cell<usize>>,
+    }
+
+    impl ExecutionHooks for Counter {
+        fn before_statement(&mut self, _: &Interpreter, _: &AstStatement) {
+            self.before.set(self.before.get() + 1);
         }
 
-        let user = new User('Anton', 26);
-        user.getUserInformation();
-    ";
-    assert_eq!(eval_code(code), JsValue::String("Name is Anton, 26 years old".to_string()));
+        fn after_statement(&mut self, _: &Interpreter, _: &AstStatement) {
+            self.after.set(self.after.get() + 1);
+        }
+    }
+
+    let interpreter = Interpreter::default();
+    let before = Rc::new(Cell::new(0));
+    let after = Rc::new(Cell::new(0));
+    interpreter.set_hooks(Box::new(Counter { before: Rc::clone(&before), after: Rc::clone(&after) }));
+    interpreter.set_yield_interval(3, Box::new(|_| YieldAction::Stop));
+
+    // host stops the script on the first `let`, only program & block which fail with it reach the hooks
+    assert!(try_interpret(&interpreter, "{ let a = 1; let b = 2; }").is_err());
+    assert_eq!(before.get(), 2);
+    assert_eq!(after.get(), 2);
 }
 
 #[test]
-#[should_panic(expected = "Assignment to constant variable.")]
-fn attempt_to_reassign_constant_variable_should_error() {
-    let code = "
-        const a = 5;
-        a = 10;
-    ";
-    eval_code(code);
+fn numeric_and_object_keys_are_canonicalized() {
+    let mut interpreter = Interpreter::default();
+
+    interpret(&mut interpreter, "let items = [10, 20]; let table = { 1.0: 'one', 0.5: 'half' };");
+    assert_eq!(interpret(&mut interpreter, "items[1] == items['1'] && items[1.0] == 20;"), JsValue::Boolean(true));
+    assert_eq!(interpret(&mut interpreter, "items['01'];"), JsValue::Undefined);
+    assert_eq!(interpret(&mut interpreter, "items[-0];"), JsValue::Number(10.0));
+    assert_eq!(interpret(&mut interpreter, "table['1'] + table[0.50] + table[1];"), JsValue::String("onehalfone".into()));
+
+    interpret(&mut interpreter, "let key = { toString: function () { return 'named'; } }; table[key] = 5;");
+    assert_eq!(interpret(&mut interpreter, "table.named;"), JsValue::Number(5.0));
+    assert_eq!(interpret(&mut interpreter, "Reflect.get(table, key) + Reflect.get(items, 1.0);"), JsValue::Number(25.0));
+    assert_eq!(interpret(&mut interpreter, "table.hasOwnProperty(key) && items.hasOwnProperty(0.0);"), JsValue::Boolean(true));
+}
+
+#[test]
+fn engine_info_is_exposed_to_scripts() {
+    let mut interpreter = Interpreter::default();
+
+    assert_eq!(interpret(&mut interpreter, "rustjs.version;"), JsValue::String(env!("CARGO_PKG_VERSION").into()));
+    assert_eq!(interpret(&mut interpreter, "rustjs.engine;"), JsValue::String("ast".into()));
+    assert_eq!(interpret(&mut interpreter, "rustjs.features.proxy && !rustjs.features.modules;"), JsValue::Boolean(true));
+
+    assert!(try_interpret(&Interpreter::default(), "rustjs.features.modules = true;").is_err());
 }
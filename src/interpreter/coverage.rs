@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use crate::nodes::*;
+use crate::visitor::{Visitor, walk_statement};
+
+/// Offsets of the first and the last character of a node, used as a stable key of a node
+type SpanKey = (usize, usize);
+
+fn span_key(span: &TextSpan) -> SpanKey {
+    (span.start.row, span.end.row)
+}
+
+/// Execution counters for every statement and branch of a script, collected with `--coverage`
+#[derive(Debug, Default)]
+pub struct Coverage {
+    statements: BTreeMap<SpanKey, Counter>,
+    branches: BTreeMap<SpanKey, BranchCounter>,
+}
+
+#[derive(Debug)]
+struct Counter {
+    line: usize,
+    hits: usize,
+}
+
+/// Counters of the consequent & alternative branches of `if` statement or conditional expression
+#[derive(Debug)]
+struct BranchCounter {
+    line: usize,
+    hits: [usize; 2],
+}
+
+impl Coverage {
+    /// Registers all statements & branches of the program with zero hits,
+    /// so code which is never reached shows up in the report
+    pub fn new(ast: &AstStatement) -> Self {
+        let mut coverage = Self::default();
+        coverage.visit_statement(ast);
+        coverage
+    }
+
+    pub fn record_statement(&mut self, statement: &AstStatement) {
+        let span = statement.get_span();
+
+        if let Some(counter) = self.statements.get_mut(&span_key(&span)) {
+            counter.hits += 1;
+        }
+    }
+
+    pub fn record_branch(&mut self, span: &TextSpan, is_consequent: bool) {
+        if let Some(counter) = self.branches.get_mut(&span_key(span)) {
+            counter.hits[if is_consequent { 0 } else { 1 }] += 1;
+        }
+    }
+
+    fn register_branch(&mut self, span: TextSpan) {
+        self.branches.insert(span_key(&span), BranchCounter { line: span.start.line + 1, hits: [0, 0] });
+    }
+
+    /// Hit count of every line with at least one statement, a line is as hot as its hottest statement
+    fn line_hits(&self) -> BTreeMap<usize, usize> {
+        let mut lines = BTreeMap::new();
+
+        for counter in self.statements.values() {
+            let hits = lines.entry(counter.line).or_insert(0);
+            *hits = counter.hits.max(*hits);
+        }
+
+        lines
+    }
+
+    /// Report in lcov tracefile format, understood by genhtml and most coverage viewers
+    pub fn to_lcov(&self, source_path: &str) -> String {
+        let mut result = format!("TN:\nSF:{source_path}\n");
+
+        for (block, counter) in self.branches.values().enumerate() {
+            let is_evaluated = counter.hits.iter().any(|x| *x > 0);
+
+            for (branch, hits) in counter.hits.iter().enumerate() {
+                let taken = if is_evaluated { hits.to_string() } else { "-".to_string() };
+                result.push_str(&format!("BRDA:{},{block},{branch},{taken}\n", counter.line));
+            }
+        }
+
+        let (branches_hit, branches_found) = self.branches_summary();
+        result.push_str(&format!("BRF:{branches_found}\nBRH:{branches_hit}\n"));
+
+        let line_hits = self.line_hits();
+
+        for (line, hits) in &line_hits {
+            result.push_str(&format!("DA:{line},{hits}\n"));
+        }
+
+        let lines_hit = line_hits.values().filter(|x| **x > 0).count();
+        result.push_str(&format!("LF:{}\nLH:{lines_hit}\nend_of_record\n", line_hits.len()));
+        result
+    }
+
+    fn branches_summary(&self) -> (usize, usize) {
+        let found = self.branches.len() * 2;
+        let hit = self.branches.values()
+            .flat_map(|x| x.hits.iter())
+            .filter(|x| **x > 0)
+            .count();
+
+        (hit, found)
+    }
+
+    pub fn summary(&self) -> String {
+        let statements_hit = self.statements.values().filter(|x| x.hits > 0).count();
+        let (branches_hit, branches_found) = self.branches_summary();
+
+        format!(
+            "Statements: {statements_hit}/{} ({}), branches: {branches_hit}/{branches_found} ({})",
+            self.statements.len(),
+            format_percentage(statements_hit, self.statements.len()),
+            format_percentage(branches_hit, branches_found),
+        )
+    }
+}
+
+fn format_percentage(hit: usize, found: usize) -> String {
+    if found == 0 {
+        return "100.00%".to_string();
+    }
+
+    format!("{:.2}%", hit as f64 / found as f64 * 100.0)
+}
+
+impl Visitor for Coverage {
+    fn visit_statement(&mut self, stmt: &AstStatement) {
+        if !matches!(stmt, AstStatement::ProgramStatement(_)) {
+            let span = stmt.get_span();
+            self.statements.insert(span_key(&span), Counter { line: span.start.line + 1, hits: 0 });
+        }
+
+        walk_statement(self, stmt);
+    }
+
+    fn visit_variable_declaration(&mut self, stmt: &VariableDeclarationNode) {
+        if let Some(value) = &stmt.value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_if_statement(&mut self, stmt: &IfStatementNode) {
+        self.register_branch(stmt.get_span());
+        self.visit_expression(&stmt.condition);
+        self.visit_statement(&stmt.then_branch);
+
+        if let Some(else_branch) = &stmt.else_branch {
+            self.visit_statement(else_branch);
+        }
+    }
+
+    fn visit_conditional_expression(&mut self, node: &ConditionalExpressionNode) {
+        self.register_branch(node.get_span());
+        self.visit_expression(&node.test);
+        self.visit_expression(&node.consequent);
+        self.visit_expression(&node.alternative);
+    }
+}
+
+#[test]
+fn coverage_counts_statements_and_branches() {
+    use crate::parser::Parser;
+
+    let code = "let a = 0;\nfor (let i = 0; i < 3; i += 1) {\n  a += i;\n}\nif (a > 10) {\n  a = 0;\n}\n";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let interpreter = Interpreter::default();
+    interpreter.enable_coverage(&ast);
+    interpreter.interpret(&ast).unwrap();
+
+    let coverage = interpreter.take_coverage().unwrap();
+    assert_eq!(coverage.summary(), "Statements: 6/8 (75.00%), branches: 1/2 (50.00%)");
+    assert_eq!(
+        coverage.to_lcov("test.js"),
+        "TN:\nSF:test.js\nBRDA:5,0,0,0\nBRDA:5,0,1,1\nBRF:2\nBRH:1\nDA:1,1\nDA:2,3\nDA:3,3\nDA:5,1\nDA:6,0\nLF:5\nLH:4\nend_of_record\n"
+    );
+}
@@ -0,0 +1,172 @@
+use crate::interpreter::ast_interpreter::Interpreter;
+use crate::nodes::AstStatement;
+use crate::parser::Parser;
+use crate::value::JsValue;
+
+/// Name of the backend scripts run on, selected by `backend` of `rustjs.toml` & seen by scripts as `rustjs.engine`
+pub const BACKEND_NAME: &str = "ast";
+
+/// Common interface of execution backends, so the CLI, the REPL & tests drive scripts
+/// without depending on a particular engine. The AST interpreter is the only backend for now.
+pub trait ExecutionEngine {
+    /// Runs a parsed program, result is the value of its last top level expression statement,
+    /// `None` if the program has no such statement.
+    /// `on_result` is called with every top level statement producing a value
+    fn run_with_results(&self, program: &AstStatement, on_result: &mut dyn FnMut(&AstStatement, &JsValue)) -> Result<Option<JsValue>, String>;
+
+    fn run(&self, program: &AstStatement) -> Result<Option<JsValue>, String> {
+        return self.run_with_results(program, &mut |_, _| {});
+    }
+
+    fn run_source(&self, source: &str) -> Result<Option<JsValue>, String> {
+        let program = Parser::parse_code_to_ast(source)?;
+        return self.run(&program);
+    }
+
+    /// Defines or overwrites a property of the global object
+    fn set_global(&self, name: &str, value: JsValue);
+
+    fn get_global(&self, name: &str) -> JsValue;
+
+    /// Calls a function value with the given `this` & arguments
+    fn call(&self, function: &JsValue, this: JsValue, arguments: Vec<JsValue>) -> Result<JsValue, String>;
+}
+
+impl ExecutionEngine for Interpreter {
+    fn run_with_results(&self, program: &AstStatement, on_result: &mut dyn FnMut(&AstStatement, &JsValue)) -> Result<Option<JsValue>, String> {
+        self.interpret_program(program, on_result)
+    }
+
+    fn set_global(&self, name: &str, value: JsValue) {
+        if let Some(global_object) = self.global_environment().borrow().get_global_object() {
+            global_object.borrow_mut().add_property(name, value);
+        }
+    }
+
+    fn get_global(&self, name: &str) -> JsValue {
+        self.global_environment().borrow().get_variable_value(name)
+    }
+
+    fn call(&self, function: &JsValue, this: JsValue, arguments: Vec<JsValue>) -> Result<JsValue, String> {
+        self.call_function_value(function, this, arguments, false)
+    }
+}
+
+#[test]
+fn engine_runs_scripts_and_calls_functions() {
+    fn run_with(engine: &impl ExecutionEngine) -> Result<JsValue, String> {
+        engine.set_global("base", JsValue::Number(10.0));
+        engine.run_source("function add(a, b) { return base + a + b; }")?;
+        let add = engine.get_global("add");
+        return engine.call(&add, JsValue::Undefined, vec![JsValue::Number(1.0), JsValue::Number(2.0)]);
+    }
+
+    assert_eq!(run_with(&Interpreter::default()), Ok(JsValue::Number(13.0)));
+    assert!(Interpreter::default().run_source("let = 1;").is_err());
+}
+
+#[test]
+fn engine_runs_programs_and_reports_top_level_results() {
+    fn run_with(engine: &impl ExecutionEngine, code: &str) -> Result<Vec<JsValue>, String> {
+        let program = Parser::parse_code_to_ast(code)?;
+        let mut results = vec![];
+        engine.run_with_results(&program, &mut |_, value| results.push(value.clone()))?;
+        return Ok(results);
+    }
+
+    let interpreter = Interpreter::with_globals([("base".to_string(), JsValue::Number(10.0))]);
+    assert_eq!(
+        run_with(&interpreter, "function add(a, b) { return base + a + b; } add(1, 2); 'done';"),
+        Ok(vec![JsValue::Number(13.0), JsValue::String("done".into())])
+    );
+    assert!(run_with(&Interpreter::default(), "let = 1;").is_err());
+}
+
+#[test]
+fn program_completion_value_is_last_expression_statement() {
+    let interpreter = Interpreter::default();
+
+    assert_eq!(interpreter.run_source("1 + 2; let a = 4; function f() {}"), Ok(Some(JsValue::Number(3.0))));
+    assert_eq!(interpreter.run_source("let b = 1; class C {}"), Ok(None));
+    assert_eq!(interpreter.run_source("b = 5; if (b > 1) { 7; }"), Ok(Some(JsValue::Undefined)));
+}
+
+#[test]
+fn host_objects_convert_through_native_hook() {
+    use crate::value::object::{JsObject, JsObjectRef, PrimitiveHint};
+
+    fn vector_to_primitive(_: &Interpreter, object: &JsObjectRef, hint: PrimitiveHint) -> Result<JsValue, String> {
+        let length = object.borrow().get_property_value("length").to_number();
+
+        match hint {
+            PrimitiveHint::String => Ok(JsValue::String(format!("Vector({length})").into())),
+            PrimitiveHint::Default | PrimitiveHint::Number => Ok(JsValue::Number(length)),
+        }
+    }
+
+    let mut vector = JsObject::empty().with_to_primitive(vector_to_primitive);
+    vector.add_property("length", JsValue::Number(3.0));
+    let interpreter = Interpreter::default();
+    interpreter.set_global("vector", vector.to_js_value());
+
+    assert_eq!(interpreter.run_source("vector * 2 + vector;"), Ok(Some(JsValue::Number(9.0))));
+    assert_eq!(interpreter.run_source("String(vector);"), Ok(Some(JsValue::String("Vector(3)".into()))));
+    assert_eq!(interpreter.run_source("vector > 2;"), Ok(Some(JsValue::Boolean(true))));
+    assert_eq!(
+        interpreter.run_source("({ valueOf: function () { return {}; }, toString: function () { return {}; } }) + 1;"),
+        Err("TypeError: Cannot convert object to primitive value".to_string())
+    );
+}
+
+#[test]
+fn host_objects_read_and_write_live_state() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::value::native_object::NativeProperties;
+    use crate::value::DisplayMode;
+    use crate::value::object::JsObject;
+
+    struct Player {
+        health: RefCell<f64>,
+    }
+
+    struct PlayerProperties(Rc<Player>);
+
+    impl NativeProperties for PlayerProperties {
+        fn get(&self, key: &str) -> Option<JsValue> {
+            match key {
+                "health" => Some(JsValue::Number(*self.0.health.borrow())),
+                _ => None,
+            }
+        }
+
+        fn set(&self, key: &str, value: JsValue) -> Result<bool, String> {
+            match (key, value) {
+                ("health", JsValue::Number(health)) => {
+                    self.0.health.replace(health);
+                    Ok(true)
+                }
+                ("health", _) => Err("TypeError: health should be a number".to_string()),
+                _ => Ok(false),
+            }
+        }
+
+        fn keys(&self) -> Vec<String> {
+            vec!["health".to_string()]
+        }
+    }
+
+    let player = Rc::new(Player { health: RefCell::new(100.0) });
+    let interpreter = Interpreter::default();
+    interpreter.set_global("player", JsObject::native(PlayerProperties(Rc::clone(&player))).to_js_value());
+
+    assert_eq!(interpreter.run_source("player.health -= 30; player.name = 'hero'; player.health;"), Ok(Some(JsValue::Number(70.0))));
+    assert_eq!(*player.health.borrow(), 70.0);
+
+    player.health.replace(5.0);
+    assert_eq!(interpreter.run_source("player.health;"), Ok(Some(JsValue::Number(5.0))));
+    let listed = interpreter.run_source("player;").unwrap().unwrap();
+    assert_eq!(format!("{}", listed.display(DisplayMode::Json)), "{\"health\":5,\"name\":\"hero\"}");
+    assert!(interpreter.run_source("player.health = 'full';").is_err());
+    assert_eq!(*player.health.borrow(), 5.0);
+}
@@ -3,11 +3,15 @@ use std::{cell::RefCell, rc::Rc};
 use std::fmt::{Formatter};
 use crate::keywords::THIS_KEYWORD;
 use crate::value::JsValue;
+use crate::value::object::{JsObject, JsObjectRef, ObjectKind};
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone)]
 pub struct Environment {
     parent: Option<EnvironmentRef>,
     variables: HashMap<String, (bool, JsValue)>,
+    /// Properties of the global object are visible as variables, only set for the global environment
+    global_object: Option<JsObjectRef>,
+    lazy_globals: HashMap<String, GlobalProvider>,
 }
 
 impl std::fmt::Debug for Environment {
@@ -18,11 +22,18 @@ impl std::fmt::Debug for Environment {
 
 pub type EnvironmentRef = Rc<RefCell<Environment>>;
 
+/// Creates value of a lazy global, see `Environment::define_lazy_global`
+pub type GlobalProvider = fn() -> JsValue;
+
+const GLOBAL_THIS: &str = "globalThis";
+
 impl Default for Environment {
     fn default() -> Self {
         Self {
             parent: None,
             variables: HashMap::new(),
+            global_object: None,
+            lazy_globals: HashMap::new(),
         }
     }
 }
@@ -32,6 +43,8 @@ impl Environment {
         Self {
             parent: Some(parent),
             variables: HashMap::new(),
+            global_object: None,
+            lazy_globals: HashMap::new(),
         }
     }
 
@@ -39,11 +52,75 @@ impl Environment {
         Self {
             parent: None,
             variables: variables.into(),
+            global_object: None,
+            lazy_globals: HashMap::new(),
         }
     }
 
+    /// Creates the root environment, which resolves unknown variables as properties of the global object
+    pub fn new_global(global_object: JsObjectRef) -> Self {
+        Self {
+            parent: None,
+            variables: HashMap::new(),
+            global_object: Some(global_object),
+            lazy_globals: HashMap::new(),
+        }
+    }
+
+    /// Root environment with a new global object holding the given properties,
+    /// `globalThis` property refers to the object itself
+    pub fn with_globals<T: Into<HashMap<String, JsValue>>>(globals: T) -> Self {
+        let global_object = JsObject::new(ObjectKind::Ordinary, globals).to_ref();
+        global_object.borrow_mut().add_property(GLOBAL_THIS, JsValue::Object(Rc::clone(&global_object)));
+        Self::new_global(global_object)
+    }
+
+    /// Registers a global which is created on first access by its name, so builtins which are never used
+    /// don't slow down startup. All of them are created once `globalThis` is accessed, so the global object looks complete
+    pub fn define_lazy_global(&mut self, name: &str, provider: GlobalProvider) {
+        self.lazy_globals.insert(name.to_string(), provider);
+    }
+
+    /// Creates lazy global with the given name & stores it in the global object, if it's not created yet
+    fn initialize_lazy_global(&self, global_object: &JsObjectRef, name: &str) {
+        if name == GLOBAL_THIS {
+            for name in self.lazy_globals.keys() {
+                self.initialize_lazy_global(global_object, name);
+            }
+
+            return;
+        }
+
+        if global_object.borrow().properties.contains_key(name) {
+            return;
+        }
+
+        if let Some(provider) = self.lazy_globals.get(name) {
+            let value = provider();
+            global_object.borrow_mut().add_property(name, value);
+        }
+    }
+
+    pub fn get_global_object(&self) -> Option<JsObjectRef> {
+        match &self.parent {
+            Some(parent) => parent.borrow().get_global_object(),
+            None => self.global_object.clone(),
+        }
+    }
+
+    /// Variables declared in this scope sorted by name, without `this`
+    pub fn variables(&self) -> Vec<(String, JsValue)> {
+        let mut variables: Vec<(String, JsValue)> = self.variables.iter()
+            .filter(|(name, _)| name.as_str() != THIS_KEYWORD)
+            .map(|(name, (_, value))| (name.clone(), value.clone()))
+            .collect();
+
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        return variables;
+    }
+
     pub fn print_variables(&self) {
-        println!("{:?}", self.variables);
+        println!("{:?}", self.variables());
     }
 
     pub fn get_parent(&self) -> Option<EnvironmentRef> {
@@ -89,6 +166,15 @@ impl Environment {
             return parent.borrow_mut().assign_variable(variable_name, value);
         }
 
+        if let Some(global_object) = &self.global_object {
+            self.initialize_lazy_global(global_object, &variable_name);
+
+            if global_object.borrow().properties.contains_key(&variable_name) {
+                global_object.borrow_mut().add_property(&variable_name, value);
+                return Ok(());
+            }
+        }
+
         if !self.variables.contains_key(&variable_name) {
             return Err(format!("Variable '{variable_name}' is not defined"));
         }
@@ -96,14 +182,26 @@ impl Environment {
         return Ok(());
     }
 
+    /// Whether the variable is declared in this or one of the parent scopes, properties of the global object don't count
+    pub fn is_declared(&self, variable_name: &str) -> bool {
+        self.variables.contains_key(variable_name)
+            || self.parent.as_ref().is_some_and(|x| x.borrow().is_declared(variable_name))
+    }
+
     pub fn get_variable_value(&self, variable_name: &str) -> JsValue {
         if self.variables.contains_key(variable_name) {
             return self.variables.get(variable_name).map_or(JsValue::Undefined, |(_, x)| x.clone());
         } else {
-            return self
-                .parent
+            if let Some(parent_env) = &self.parent {
+                return parent_env.borrow().get_variable_value(variable_name);
+            }
+
+            return self.global_object
                 .as_ref()
-                .map_or(JsValue::Undefined, |parent_env| parent_env.borrow().get_variable_value(variable_name));
+                .map_or(JsValue::Undefined, |x| {
+                    self.initialize_lazy_global(x, variable_name);
+                    x.borrow().get_property_value(variable_name)
+                });
         }
     }
 }
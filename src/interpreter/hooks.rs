@@ -0,0 +1,36 @@
+use crate::interpreter::ast_interpreter::Interpreter;
+use crate::nodes::{AstStatement, TextSpan};
+
+/// Callbacks invoked while a script runs, so embedders can build profilers, debuggers or audit logs
+/// without changes in the engine. Every callback does nothing by default.
+/// Code which a hook runs through the interpreter, e.g. evaluating a watch expression, doesn't trigger hooks
+pub trait ExecutionHooks {
+    fn before_statement(&mut self, _interpreter: &Interpreter, _statement: &AstStatement) {}
+
+    /// Called even when the statement fails
+    fn after_statement(&mut self, _interpreter: &Interpreter, _statement: &AstStatement) {}
+
+    /// Called when a script function starts, `name` is empty for anonymous functions & `span` is the span of the body
+    fn on_function_enter(&mut self, _interpreter: &Interpreter, _name: &str, _span: &TextSpan) {}
+
+    /// Called when a script function returns or throws
+    fn on_function_exit(&mut self, _interpreter: &Interpreter, _name: &str, _span: &TextSpan) {}
+}
+
+/// Decision of the embedder when a script yields, see `Interpreter::set_yield_interval`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YieldAction {
+    Continue,
+    /// Script fails with an uncaught error, e.g. when it runs for too long.
+    /// Statement which yielded doesn't start, so it isn't passed to `ExecutionHooks::before_statement`
+    Stop,
+}
+
+/// Called every N statements, e.g. to render a frame of a game loop between chunks of a long script
+pub type YieldCallback = Box<dyn FnMut(&Interpreter) -> YieldAction>;
+
+pub(crate) struct YieldPoint {
+    pub interval: usize,
+    pub remaining: usize,
+    pub callback: YieldCallback,
+}
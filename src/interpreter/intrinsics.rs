@@ -0,0 +1,1026 @@
+use std::rc::Rc;
+use crate::interpreter::ast_interpreter::Interpreter;
+use crate::interpreter::locale::Locale;
+use crate::value::function::{BoundFunction, JsFunction};
+use crate::value::object::{JsObject, JsObjectRef, ObjectKind, PrimitiveHint, link_constructor_prototype};
+use crate::value::weak_collection::WeakCollection;
+use crate::value::{DisplayMode, JsValue, number_to_string};
+
+/// Objects the engine relies on regardless of what globals currently hold,
+/// e.g. property access on a primitive looks up its wrapper prototype here
+pub struct Intrinsics {
+    pub string_prototype: JsObjectRef,
+    pub number_prototype: JsObjectRef,
+    pub boolean_prototype: JsObjectRef,
+    pub function_prototype: JsObjectRef,
+    /// Properties of `Object.prototype` are found on every object, after its own prototype chain
+    pub object_prototype: JsObjectRef,
+    pub error_prototype: JsObjectRef,
+    pub type_error_prototype: JsObjectRef,
+    pub range_error_prototype: JsObjectRef,
+    pub syntax_error_prototype: JsObjectRef,
+    pub weak_map_prototype: JsObjectRef,
+    pub weak_set_prototype: JsObjectRef,
+}
+
+impl Intrinsics {
+    pub fn new() -> Self {
+        let object_prototype = get_object_prototype().to_ref();
+
+        // builtin prototypes inherit from `Object.prototype`, like in other engines
+        let inherit_object_prototype = |mut prototype: JsObject| {
+            prototype.set_proto(JsObjectRef::clone(&object_prototype));
+            prototype.to_ref()
+        };
+
+        let error_prototype = inherit_object_prototype(get_error_prototype());
+
+        Self {
+            string_prototype: inherit_object_prototype(get_string_prototype()),
+            number_prototype: inherit_object_prototype(get_number_prototype()),
+            boolean_prototype: inherit_object_prototype(get_boolean_prototype()),
+            function_prototype: inherit_object_prototype(get_function_prototype()),
+            weak_map_prototype: inherit_object_prototype(get_weak_map_prototype()),
+            weak_set_prototype: inherit_object_prototype(get_weak_set_prototype()),
+            object_prototype,
+            type_error_prototype: get_native_error_prototype("TypeError", &error_prototype).to_ref(),
+            range_error_prototype: get_native_error_prototype("RangeError", &error_prototype).to_ref(),
+            syntax_error_prototype: get_native_error_prototype("SyntaxError", &error_prototype).to_ref(),
+            error_prototype,
+        }
+    }
+
+    /// `Object`, `Array`, `String`, `Number` & `Boolean` conversion functions, error constructors
+    /// and weak collection constructors, their `prototype` is the intrinsic one. Also `Proxy` & `Reflect`
+    pub fn get_global_constructors(&self) -> Vec<(String, JsValue)> {
+        fn object_constructor(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            match arguments.get(0) {
+                None | Some(JsValue::Undefined) | Some(JsValue::Null) => Ok(JsObject::empty().to_js_value()),
+                Some(value) => Ok(value.clone()),
+            }
+        }
+
+        fn string_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            let Some(value) = arguments.get(0) else {
+                return Ok(JsValue::String("".into()));
+            };
+
+            Ok(JsValue::String(interpreter.to_primitive(value, PrimitiveHint::String)?.to_js_string().into()))
+        }
+
+        fn number_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            let Some(value) = arguments.get(0) else {
+                return Ok(JsValue::Number(0.0));
+            };
+
+            Ok(JsValue::Number(interpreter.to_primitive(value, PrimitiveHint::Number)?.to_number()))
+        }
+
+        fn boolean_constructor(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            Ok(JsValue::Boolean(arguments.get(0).is_some_and(|x| x.to_bool())))
+        }
+
+        fn error_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            Ok(create_error(&interpreter.intrinsics().error_prototype, error_message_argument(arguments)))
+        }
+
+        fn type_error_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            Ok(create_error(&interpreter.intrinsics().type_error_prototype, error_message_argument(arguments)))
+        }
+
+        fn range_error_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            Ok(create_error(&interpreter.intrinsics().range_error_prototype, error_message_argument(arguments)))
+        }
+
+        fn syntax_error_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+            Ok(create_error(&interpreter.intrinsics().syntax_error_prototype, error_message_argument(arguments)))
+        }
+
+        let create_constructor = |function, prototype: &JsObjectRef| {
+            let constructor = JsFunction::native_function(function).to_object().to_ref();
+            link_constructor_prototype(&constructor, prototype);
+            JsValue::Object(constructor)
+        };
+
+        let mut object_constructor = JsFunction::native_function(object_constructor).to_object();
+        object_constructor.add_property("keys", JsValue::native_function(object_keys));
+        object_constructor.add_property("values", JsValue::native_function(object_values));
+        object_constructor.add_property("entries", JsValue::native_function(object_entries));
+        object_constructor.add_property("assign", JsValue::native_function(object_assign));
+        object_constructor.add_property("is", JsValue::native_function(object_is));
+        object_constructor.add_property("getPrototypeOf", JsValue::native_function(object_get_prototype_of));
+        object_constructor.add_property("setPrototypeOf", JsValue::native_function(object_set_prototype_of));
+
+        let object_constructor = object_constructor.to_ref();
+        link_constructor_prototype(&object_constructor, &self.object_prototype);
+
+        let mut array_constructor = JsFunction::native_function(array_constructor).to_object();
+        array_constructor.add_property("isArray", JsValue::native_function(array_is_array));
+        array_constructor.add_property("from", JsValue::native_function(array_from));
+        array_constructor.add_property("of", JsValue::native_function(array_of));
+
+        vec![
+            ("Object".to_string(), JsValue::Object(object_constructor)),
+            ("Array".to_string(), array_constructor.to_js_value()),
+            ("String".to_string(), create_constructor(string_constructor, &self.string_prototype)),
+            ("Number".to_string(), create_constructor(number_constructor, &self.number_prototype)),
+            ("Boolean".to_string(), create_constructor(boolean_constructor, &self.boolean_prototype)),
+            ("Error".to_string(), create_constructor(error_constructor, &self.error_prototype)),
+            ("TypeError".to_string(), create_constructor(type_error_constructor, &self.type_error_prototype)),
+            ("RangeError".to_string(), create_constructor(range_error_constructor, &self.range_error_prototype)),
+            ("SyntaxError".to_string(), create_constructor(syntax_error_constructor, &self.syntax_error_prototype)),
+            ("WeakMap".to_string(), create_constructor(weak_map_constructor, &self.weak_map_prototype)),
+            ("WeakSet".to_string(), create_constructor(weak_set_constructor, &self.weak_set_prototype)),
+            ("Proxy".to_string(), JsValue::native_function(proxy_constructor)),
+            ("Reflect".to_string(), get_reflect_object()),
+        ]
+    }
+}
+
+/// Value of `this` inside of a native function
+fn get_this(interpreter: &Interpreter) -> JsValue {
+    interpreter.environment.borrow().borrow().get_context()
+}
+
+fn this_string_units(interpreter: &Interpreter) -> Vec<u16> {
+    get_this(interpreter).to_js_string().encode_utf16().collect()
+}
+
+fn string_argument(arguments: &Vec<JsValue>, index: usize) -> String {
+    arguments.get(index).map_or(String::new(), |x| x.to_js_string())
+}
+
+/// Argument converted to a property key, missing argument is the "undefined" key
+fn key_argument(interpreter: &Interpreter, arguments: &Vec<JsValue>, index: usize) -> Result<String, String> {
+    interpreter.to_property_key(arguments.get(index).unwrap_or(&JsValue::Undefined))
+}
+
+/// Converts argument to an integer like ToIntegerOrInfinity, missing argument is `default`
+fn integer_argument(arguments: &Vec<JsValue>, index: usize, default: f64) -> f64 {
+    match arguments.get(index) {
+        None | Some(JsValue::Undefined) => default,
+        Some(value) => {
+            let number = value.to_number();
+            if number.is_nan() { 0.0 } else { number.trunc() }
+        }
+    }
+}
+
+/// Resolves relative position used by `slice`, negative values count from the end
+fn relative_index(position: f64, length: usize) -> usize {
+    if position < 0.0 {
+        (length as f64 + position).max(0.0) as usize
+    } else {
+        position.min(length as f64) as usize
+    }
+}
+
+fn find_units(haystack: &[u16], needle: &[u16], from: usize) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(from.min(haystack.len()));
+    }
+
+    (from..haystack.len().saturating_sub(needle.len() - 1))
+        .find(|&i| haystack[i..].starts_with(needle))
+}
+
+/// White space & line terminators removed by `trim`, unlike Rust it includes byte order mark
+fn is_js_whitespace(char: char) -> bool {
+    char.is_whitespace() || char == '\u{FEFF}'
+}
+
+/// Shared implementation of `padStart` & `padEnd`, filler is repeated & cut to reach target length
+fn pad_string(interpreter: &Interpreter, arguments: &Vec<JsValue>, is_start: bool) -> Result<JsValue, String> {
+    let units = this_string_units(interpreter);
+    let target_length = integer_argument(arguments, 0, 0.0);
+    let filler: Vec<u16> = match arguments.get(1) {
+        None | Some(JsValue::Undefined) => vec![' ' as u16],
+        Some(filler) => filler.to_js_string().encode_utf16().collect(),
+    };
+
+    if target_length <= units.len() as f64 || filler.is_empty() {
+        return Ok(JsValue::String(String::from_utf16_lossy(&units).into()));
+    }
+
+    let fill: Vec<u16> = filler.iter().cycle().take(target_length as usize - units.len()).copied().collect();
+    let padded = if is_start { [fill, units].concat() } else { [units, fill].concat() };
+
+    Ok(JsValue::String(String::from_utf16_lossy(&padded).into()))
+}
+
+fn get_string_prototype() -> JsObject {
+    fn char_at(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let index = integer_argument(arguments, 0, 0.0);
+
+        if index < 0.0 || index >= units.len() as f64 {
+            return Ok(JsValue::String(String::new().into()));
+        }
+
+        Ok(JsValue::String(String::from_utf16_lossy(&units[index as usize..index as usize + 1]).into()))
+    }
+
+    fn char_code_at(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let index = integer_argument(arguments, 0, 0.0);
+
+        if index < 0.0 || index >= units.len() as f64 {
+            return Ok(JsValue::Number(f64::NAN));
+        }
+
+        Ok(JsValue::Number(units[index as usize] as f64))
+    }
+
+    /// Code unit at the index, negative index counts from the end
+    fn at(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let index = integer_argument(arguments, 0, 0.0);
+        let index = if index < 0.0 { index + units.len() as f64 } else { index };
+
+        if index < 0.0 || index >= units.len() as f64 {
+            return Ok(JsValue::Undefined);
+        }
+
+        Ok(JsValue::String(String::from_utf16_lossy(&units[index as usize..index as usize + 1]).into()))
+    }
+
+    /// Whole code point when the index points at the start of a surrogate pair, otherwise the code unit
+    fn code_point_at(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let index = integer_argument(arguments, 0, 0.0);
+
+        if index < 0.0 || index >= units.len() as f64 {
+            return Ok(JsValue::Undefined);
+        }
+
+        let code_point = char::decode_utf16(units[index as usize..].iter().copied())
+            .next()
+            .map_or(0, |x| x.map_or_else(|e| e.unpaired_surrogate() as u32, |x| x as u32));
+
+        Ok(JsValue::Number(code_point as f64))
+    }
+
+    fn index_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let search: Vec<u16> = string_argument(arguments, 0).encode_utf16().collect();
+        let from = integer_argument(arguments, 1, 0.0).max(0.0) as usize;
+
+        Ok(JsValue::Number(find_units(&units, &search, from).map_or(-1.0, |x| x as f64)))
+    }
+
+    fn last_index_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let search: Vec<u16> = string_argument(arguments, 0).encode_utf16().collect();
+
+        if search.len() > units.len() {
+            return Ok(JsValue::Number(-1.0));
+        }
+
+        let from = integer_argument(arguments, 1, f64::INFINITY).clamp(0.0, (units.len() - search.len()) as f64) as usize;
+        let index = (0..=from).rev().find(|&i| units[i..].starts_with(&search));
+
+        Ok(JsValue::Number(index.map_or(-1.0, |x| x as f64)))
+    }
+
+    fn includes(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let search: Vec<u16> = string_argument(arguments, 0).encode_utf16().collect();
+        let from = integer_argument(arguments, 1, 0.0).max(0.0) as usize;
+
+        Ok(JsValue::Boolean(find_units(&units, &search, from).is_some()))
+    }
+
+    fn starts_with(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let search: Vec<u16> = string_argument(arguments, 0).encode_utf16().collect();
+        let position = integer_argument(arguments, 1, 0.0).clamp(0.0, units.len() as f64) as usize;
+
+        Ok(JsValue::Boolean(units[position..].starts_with(&search)))
+    }
+
+    fn ends_with(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let search: Vec<u16> = string_argument(arguments, 0).encode_utf16().collect();
+        let end = integer_argument(arguments, 1, units.len() as f64).clamp(0.0, units.len() as f64) as usize;
+
+        Ok(JsValue::Boolean(units[..end].ends_with(&search)))
+    }
+
+    fn slice(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let start = relative_index(integer_argument(arguments, 0, 0.0), units.len());
+        let end = relative_index(integer_argument(arguments, 1, units.len() as f64), units.len());
+
+        if start >= end {
+            return Ok(JsValue::String(String::new().into()));
+        }
+
+        Ok(JsValue::String(String::from_utf16_lossy(&units[start..end]).into()))
+    }
+
+    fn substring(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let units = this_string_units(interpreter);
+        let length = units.len() as f64;
+        let start = integer_argument(arguments, 0, 0.0).clamp(0.0, length) as usize;
+        let end = integer_argument(arguments, 1, length).clamp(0.0, length) as usize;
+
+        Ok(JsValue::String(String::from_utf16_lossy(&units[start.min(end)..start.max(end)]).into()))
+    }
+
+    /// Splits string by separator, optional second argument limits number of parts
+    fn split(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let string = get_this(interpreter).to_js_string();
+        let limit = match arguments.get(1) {
+            None | Some(JsValue::Undefined) => usize::MAX,
+            Some(limit) => limit.to_number() as u32 as usize,
+        };
+
+        let parts: Vec<JsValue> = match arguments.get(0) {
+            None | Some(JsValue::Undefined) => vec![JsValue::String(string.into())],
+            Some(separator) => {
+                let separator = separator.to_js_string();
+
+                if separator.is_empty() {
+                    string.chars().map(|x| JsValue::String(x.to_string().into())).collect()
+                } else {
+                    string.split(separator.as_str()).map(|x| JsValue::String(x.into())).collect()
+                }
+            }
+        };
+
+        Ok(JsObject::array(parts.into_iter().take(limit).collect()).to_js_value())
+    }
+
+    fn repeat(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let count = integer_argument(arguments, 0, 0.0);
+
+        if count < 0.0 || count.is_infinite() {
+            return Err(format!("RangeError: Invalid count value: {}", number_to_string(count)));
+        }
+
+        Ok(JsValue::String(get_this(interpreter).to_js_string().repeat(count as usize).into()))
+    }
+
+    fn concat(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let mut result = get_this(interpreter).to_js_string();
+        arguments.iter().for_each(|x| result.push_str(&x.to_js_string()));
+        Ok(JsValue::String(result.into()))
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("charAt".to_string(), JsValue::native_function(char_at)),
+        ("charCodeAt".to_string(), JsValue::native_function(char_code_at)),
+        ("codePointAt".to_string(), JsValue::native_function(code_point_at)),
+        ("at".to_string(), JsValue::native_function(at)),
+        ("indexOf".to_string(), JsValue::native_function(index_of)),
+        ("lastIndexOf".to_string(), JsValue::native_function(last_index_of)),
+        ("includes".to_string(), JsValue::native_function(includes)),
+        ("startsWith".to_string(), JsValue::native_function(starts_with)),
+        ("endsWith".to_string(), JsValue::native_function(ends_with)),
+        ("slice".to_string(), JsValue::native_function(slice)),
+        ("substring".to_string(), JsValue::native_function(substring)),
+        ("split".to_string(), JsValue::native_function(split)),
+        ("repeat".to_string(), JsValue::native_function(repeat)),
+        ("concat".to_string(), JsValue::native_function(concat)),
+        ("toUpperCase".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().to_uppercase().into())))),
+        ("toLowerCase".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().to_lowercase().into())))),
+        ("trim".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().trim_matches(is_js_whitespace).into())))),
+        ("trimStart".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().trim_start_matches(is_js_whitespace).into())))),
+        ("trimEnd".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().trim_end_matches(is_js_whitespace).into())))),
+        ("padStart".to_string(), JsValue::native_function(|interpreter, arguments| pad_string(interpreter, arguments, true))),
+        ("padEnd".to_string(), JsValue::native_function(|interpreter, arguments| pad_string(interpreter, arguments, false))),
+        ("toString".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().into())))),
+        ("valueOf".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().into())))),
+    ])
+}
+
+/// Converts number to string in given radix, fractional part is written with up to 52 digits
+fn number_to_radix_string(value: f64, radix: u32) -> String {
+    if radix == 10 || !value.is_finite() {
+        return number_to_string(value);
+    }
+
+    let digits = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut integer_part = value.abs().trunc();
+    let mut fraction_part = value.abs().fract();
+    let mut integer_digits = vec![];
+
+    loop {
+        integer_digits.push(digits[(integer_part % radix as f64) as usize] as char);
+        integer_part = (integer_part / radix as f64).trunc();
+
+        if integer_part == 0.0 {
+            break;
+        }
+    }
+
+    let mut result: String = integer_digits.iter().rev().collect();
+
+    if fraction_part > 0.0 {
+        result.push('.');
+
+        for _ in 0..52 {
+            fraction_part *= radix as f64;
+            result.push(digits[fraction_part.trunc() as usize] as char);
+            fraction_part = fraction_part.fract();
+
+            if fraction_part == 0.0 {
+                break;
+            }
+        }
+    }
+
+    if value < 0.0 {
+        result.insert(0, '-');
+    }
+
+    result
+}
+
+fn get_number_prototype() -> JsObject {
+    fn to_string(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let radix = integer_argument(arguments, 0, 10.0);
+
+        if !(2.0..=36.0).contains(&radix) {
+            return Err("RangeError: toString() radix must be between 2 and 36".to_string());
+        }
+
+        Ok(JsValue::String(number_to_radix_string(get_this(interpreter).to_number(), radix as u32).into()))
+    }
+
+    fn to_fixed(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let value = get_this(interpreter).to_number();
+        let fraction_digits = integer_argument(arguments, 0, 0.0);
+
+        if !(0.0..=100.0).contains(&fraction_digits) {
+            return Err("RangeError: toFixed() digits argument must be between 0 and 100".to_string());
+        }
+
+        if !value.is_finite() || value.abs() >= 1e21 {
+            return Ok(JsValue::String(number_to_string(value).into()));
+        }
+
+        let fraction_digits = fraction_digits as usize;
+        let scale = 10f64.powi(fraction_digits as i32);
+        let scaled = value.abs() * scale;
+
+        // ties are rounded away from zero, while rust formatting rounds them to even
+        let value = if scaled.fract() == 0.5 {
+            value.signum() * (scaled.trunc() + 1.0) / scale
+        } else {
+            value
+        };
+
+        Ok(JsValue::String(format!("{:.*}", fraction_digits, value).into()))
+    }
+
+    /// `toLocaleString(locale, { minimumFractionDigits, maximumFractionDigits })`,
+    /// interpreter's locale is used when locale is not given
+    fn to_locale_string(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let locale = match arguments.get(0) {
+            None | Some(JsValue::Undefined) => interpreter.locale(),
+            Some(tag) => Locale::parse(&tag.to_js_string())?,
+        };
+
+        let fraction_digits_option = |key: &str| match arguments.get(1) {
+            Some(JsValue::Object(options)) => match options.borrow().get_property_value(key) {
+                JsValue::Undefined => Ok(None),
+                value => {
+                    let digits = value.to_number();
+
+                    if !(0.0..=20.0).contains(&digits) {
+                        return Err(format!("RangeError: {key} value is out of range."));
+                    }
+
+                    Ok(Some(digits as usize))
+                }
+            },
+            _ => Ok(None),
+        };
+
+        let min_fraction_digits = fraction_digits_option("minimumFractionDigits")?.unwrap_or(0);
+        let max_fraction_digits = fraction_digits_option("maximumFractionDigits")?.unwrap_or(min_fraction_digits.max(3));
+
+        if min_fraction_digits > max_fraction_digits {
+            return Err("RangeError: maximumFractionDigits value is out of range.".to_string());
+        }
+
+        let value = get_this(interpreter).to_number();
+        Ok(JsValue::String(locale.format_number(value, min_fraction_digits, max_fraction_digits).into()))
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("toString".to_string(), JsValue::native_function(to_string)),
+        ("toFixed".to_string(), JsValue::native_function(to_fixed)),
+        ("toLocaleString".to_string(), JsValue::native_function(to_locale_string)),
+        ("valueOf".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::Number(get_this(interpreter).to_number())))),
+    ])
+}
+
+fn get_boolean_prototype() -> JsObject {
+    JsObject::new(ObjectKind::Ordinary, [
+        ("toString".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::String(get_this(interpreter).to_js_string().into())))),
+        ("valueOf".to_string(), JsValue::native_function(|interpreter, _| Ok(JsValue::Boolean(get_this(interpreter).to_bool())))),
+    ])
+}
+
+/// Turns `apply` argument list into separate arguments, `null` & `undefined` mean no arguments
+pub fn list_from_array_like(value: &JsValue) -> Result<Vec<JsValue>, String> {
+    match value {
+        JsValue::Undefined | JsValue::Null => Ok(vec![]),
+        JsValue::Object(object) if matches!(object.borrow().kind, ObjectKind::Array) => Ok(object.borrow().array_items()),
+        _ => Err("TypeError: CreateListFromArrayLike called on non-object".to_string()),
+    }
+}
+
+/// Object argument of `Object.keys` & similar functions
+fn object_argument(arguments: &Vec<JsValue>) -> Result<JsObjectRef, String> {
+    match arguments.get(0) {
+        Some(JsValue::Object(object)) => Ok(JsObjectRef::clone(object)),
+        argument => Err(format!(
+            "TypeError: Cannot convert {} to object",
+            argument.map_or("undefined".to_string(), |x| x.get_type_as_str())
+        )),
+    }
+}
+
+fn object_keys(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let keys = object_argument(arguments)?.borrow().enumerable_keys();
+    Ok(JsObject::array(keys.into_iter().map(|x| JsValue::String(x.into())).collect()).to_js_value())
+}
+
+fn object_values(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let entries = object_argument(arguments)?.borrow().enumerable_entries();
+    Ok(JsObject::array(entries.into_iter().map(|(_, value)| value).collect()).to_js_value())
+}
+
+fn object_entries(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let entries = object_argument(arguments)?.borrow().enumerable_entries();
+    let entries = entries.into_iter()
+        .map(|(key, value)| JsObject::array(vec![JsValue::String(key.into()), value]).to_js_value())
+        .collect();
+
+    Ok(JsObject::array(entries).to_js_value())
+}
+
+/// Copies own properties of sources into target, inherited ones are skipped
+fn object_assign(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let target = object_argument(arguments)?;
+
+    for source in arguments.iter().skip(1) {
+        if let JsValue::Object(source) = source {
+            let keys = source.borrow().enumerable_keys();
+
+            for key in keys {
+                let value = interpreter.get_object_property(source, &key)?;
+                interpreter.set_object_property(&target, &key, value)?;
+            }
+        }
+    }
+
+    Ok(JsValue::Object(target))
+}
+
+fn object_is(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let left = arguments.get(0).unwrap_or(&JsValue::Undefined);
+    let right = arguments.get(1).unwrap_or(&JsValue::Undefined);
+    Ok(JsValue::Boolean(left.is_same_value(right)))
+}
+
+fn object_get_prototype_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    Ok(interpreter.get_object_proto(&object_argument(arguments)?))
+}
+
+fn object_set_prototype_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let target = object_argument(arguments)?;
+    interpreter.set_object_proto(&target, arguments.get(1).unwrap_or(&JsValue::Undefined))?;
+    Ok(JsValue::Object(target))
+}
+
+/// `Array(1, 2)` creates array of its arguments, a single number argument is the length of the array.
+/// Arrays don't have holes at the end, so such array is filled with undefined
+fn array_constructor(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let items = match arguments.as_slice() {
+        [JsValue::Number(length)] => {
+            if *length < 0.0 || length.fract() != 0.0 || *length > u32::MAX as f64 {
+                return Err("RangeError: Invalid array length".to_string());
+            }
+
+            vec![JsValue::Undefined; *length as usize]
+        }
+        items => items.to_vec(),
+    };
+
+    Ok(JsObject::array(items).to_js_value())
+}
+
+fn array_is_array(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let is_array = matches!(arguments.get(0), Some(JsValue::Object(object)) if matches!(object.borrow().kind, ObjectKind::Array));
+    Ok(JsValue::Boolean(is_array))
+}
+
+/// Creates array from an iterable or an array-like object with `length`, e.g. `{ length: 2, 0: 'a' }`,
+/// optional second argument maps every item & its index
+fn array_from(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let source = arguments.get(0).unwrap_or(&JsValue::Undefined);
+    let map_function = arguments.get(1).filter(|x| **x != JsValue::Undefined);
+
+    if let Some(map_function) = map_function {
+        if !map_function.is_function() {
+            return Err(format!("TypeError: {} is not a function", map_function.display(DisplayMode::Plain)));
+        }
+    }
+
+    let items = match source {
+        JsValue::Object(object) if !matches!(object.borrow().kind, ObjectKind::Array) => {
+            let length = object.borrow().get_property_value("length").to_number();
+            let length = if length.is_nan() || length < 0.0 { 0 } else { length.min(u32::MAX as f64) as usize };
+            (0..length).map(|i| object.borrow().get_property_value(&i.to_string())).collect()
+        }
+        source => interpreter.iterate_value(source)?,
+    };
+
+    let Some(map_function) = map_function else {
+        return Ok(JsObject::array(items).to_js_value());
+    };
+
+    let mut mapped = Vec::with_capacity(items.len());
+
+    for (index, item) in items.into_iter().enumerate() {
+        let arguments = vec![item, JsValue::Number(index as f64)];
+        mapped.push(interpreter.call_function_value(map_function, JsValue::Undefined, arguments, false)?);
+    }
+
+    Ok(JsObject::array(mapped).to_js_value())
+}
+
+fn array_of(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    Ok(JsObject::array(arguments.clone()).to_js_value())
+}
+
+/// `new Proxy(target, handler)`, only `get`, `set`, `has` & `deleteProperty` traps are supported
+fn proxy_constructor(_: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    match (arguments.get(0), arguments.get(1)) {
+        (Some(JsValue::Object(target)), Some(JsValue::Object(handler))) => {
+            Ok(JsObject::proxy(Rc::clone(target), Rc::clone(handler)).to_js_value())
+        }
+        _ => Err("TypeError: Cannot create proxy with a non-object as target or handler".to_string()),
+    }
+}
+
+/// Target argument of `Reflect` functions, unlike `Object` functions primitives are not accepted
+fn reflect_target(arguments: &Vec<JsValue>, function_name: &str) -> Result<JsObjectRef, String> {
+    match arguments.get(0) {
+        Some(JsValue::Object(object)) => Ok(Rc::clone(object)),
+        _ => Err(format!("TypeError: Reflect.{function_name} called on non-object")),
+    }
+}
+
+fn get_reflect_object() -> JsValue {
+    fn get(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        interpreter.get_object_property(&reflect_target(arguments, "get")?, &key_argument(interpreter, arguments, 1)?)
+    }
+
+    /// Returns false instead of throwing when the property is read only
+    fn set(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let target = reflect_target(arguments, "set")?;
+        let key = key_argument(interpreter, arguments, 1)?;
+
+        if interpreter.check_writable(&target, &key).is_err() {
+            return Ok(JsValue::Boolean(false));
+        }
+
+        interpreter.set_object_property(&target, &key, arguments.get(2).cloned().unwrap_or(JsValue::Undefined))?;
+        Ok(JsValue::Boolean(true))
+    }
+
+    fn has(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        Ok(JsValue::Boolean(interpreter.has_property(&reflect_target(arguments, "has")?, &key_argument(interpreter, arguments, 1)?)?))
+    }
+
+    fn delete_property(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        Ok(JsValue::Boolean(interpreter.delete_property(&reflect_target(arguments, "deleteProperty")?, &key_argument(interpreter, arguments, 1)?)?))
+    }
+
+    fn get_prototype_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        Ok(interpreter.get_object_proto(&reflect_target(arguments, "getPrototypeOf")?))
+    }
+
+    fn set_prototype_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let target = reflect_target(arguments, "setPrototypeOf")?;
+        interpreter.set_object_proto(&target, arguments.get(1).unwrap_or(&JsValue::Undefined))?;
+        Ok(JsValue::Boolean(true))
+    }
+
+    /// `Reflect.apply(f, thisArgument, argumentsList)`
+    fn apply(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let function = arguments.get(0).unwrap_or(&JsValue::Undefined);
+        let this = arguments.get(1).cloned().unwrap_or(JsValue::Undefined);
+        let function_arguments = list_from_array_like(arguments.get(2).unwrap_or(&JsValue::Undefined))?;
+        interpreter.call_function_value(function, this, function_arguments, false)
+    }
+
+    /// `Reflect.construct(constructor, argumentsList)`, like `new constructor(...argumentsList)`
+    fn construct(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let constructor = arguments.get(0).unwrap_or(&JsValue::Undefined);
+        let constructor_arguments = list_from_array_like(arguments.get(1).unwrap_or(&JsValue::Undefined))?;
+        interpreter.call_function_value(constructor, JsValue::Undefined, constructor_arguments, true)
+    }
+
+    JsValue::object([
+        ("get".to_string(), JsValue::native_function(get)),
+        ("set".to_string(), JsValue::native_function(set)),
+        ("has".to_string(), JsValue::native_function(has)),
+        ("deleteProperty".to_string(), JsValue::native_function(delete_property)),
+        ("getPrototypeOf".to_string(), JsValue::native_function(get_prototype_of)),
+        ("setPrototypeOf".to_string(), JsValue::native_function(set_prototype_of)),
+        ("apply".to_string(), JsValue::native_function(apply)),
+        ("construct".to_string(), JsValue::native_function(construct)),
+    ])
+}
+
+fn get_object_prototype() -> JsObject {
+    fn has_own_property(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let key = key_argument(interpreter, arguments, 0)?;
+
+        match get_this(interpreter) {
+            JsValue::Object(object) => Ok(JsValue::Boolean(object.borrow().has_own_property(&key))),
+            JsValue::Undefined | JsValue::Null => Err("TypeError: Cannot convert undefined or null to object".to_string()),
+            _ => Ok(JsValue::Boolean(false)),
+        }
+    }
+
+    /// `[object Tag]`, where tag is the kind of the value
+    fn to_string(interpreter: &Interpreter, _: &Vec<JsValue>) -> Result<JsValue, String> {
+        let tag = match get_this(interpreter) {
+            JsValue::Undefined => "Undefined",
+            JsValue::Null => "Null",
+            JsValue::String(_) => "String",
+            JsValue::Number(_) => "Number",
+            JsValue::Boolean(_) => "Boolean",
+            JsValue::Object(object) => match object.borrow().kind {
+                ObjectKind::Ordinary | ObjectKind::Native(_) | ObjectKind::Proxy(_) => "Object",
+                ObjectKind::Function(_) => "Function",
+                ObjectKind::Array => "Array",
+                ObjectKind::Error => "Error",
+                ObjectKind::WeakMap(_) => "WeakMap",
+                ObjectKind::WeakSet(_) => "WeakSet",
+            },
+        };
+
+        Ok(JsValue::String(format!("[object {tag}]").into()))
+    }
+
+    fn value_of(interpreter: &Interpreter, _: &Vec<JsValue>) -> Result<JsValue, String> {
+        match get_this(interpreter) {
+            JsValue::Undefined | JsValue::Null => Err("TypeError: Cannot convert undefined or null to object".to_string()),
+            this => Ok(this),
+        }
+    }
+
+    /// Walks prototype chain of the argument, including the implicit
+    /// `Function.prototype` & `Object.prototype` at its end
+    fn is_prototype_of(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let Some(JsValue::Object(object)) = arguments.get(0) else {
+            return Ok(JsValue::Boolean(false));
+        };
+
+        let prototype = match get_this(interpreter) {
+            JsValue::Object(prototype) => prototype,
+            JsValue::Undefined | JsValue::Null => return Err("TypeError: Cannot convert undefined or null to object".to_string()),
+            _ => return Ok(JsValue::Boolean(false)),
+        };
+
+        let mut current = object.borrow().get_proto();
+
+        while let Some(current_prototype) = current {
+            if Rc::ptr_eq(&current_prototype, &prototype) {
+                return Ok(JsValue::Boolean(true));
+            }
+
+            current = current_prototype.borrow().get_proto();
+        }
+
+        let intrinsics = interpreter.intrinsics();
+        let is_implicit_prototype = (object.borrow().is_function() && Rc::ptr_eq(&prototype, &intrinsics.function_prototype))
+            || (Rc::ptr_eq(&prototype, &intrinsics.object_prototype) && !Rc::ptr_eq(object, &intrinsics.object_prototype));
+
+        Ok(JsValue::Boolean(is_implicit_prototype))
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("hasOwnProperty".to_string(), JsValue::native_function(has_own_property)),
+        ("isPrototypeOf".to_string(), JsValue::native_function(is_prototype_of)),
+        ("toString".to_string(), JsValue::native_function(to_string)),
+        ("valueOf".to_string(), JsValue::native_function(value_of)),
+    ])
+}
+
+fn get_function_prototype() -> JsObject {
+    fn call(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let this_argument = arguments.get(0).cloned().unwrap_or(JsValue::Undefined);
+        let rest_arguments = arguments.iter().skip(1).cloned().collect();
+
+        interpreter.call_function_value(&get_this(interpreter), this_argument, rest_arguments, false)
+    }
+
+    fn apply(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let this_argument = arguments.get(0).cloned().unwrap_or(JsValue::Undefined);
+        let argument_list = list_from_array_like(arguments.get(1).unwrap_or(&JsValue::Undefined))?;
+
+        interpreter.call_function_value(&get_this(interpreter), this_argument, argument_list, false)
+    }
+
+    fn bind(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let target = get_this(interpreter);
+
+        if !target.is_function() {
+            return Err("TypeError: Bind must be called on a function".to_string());
+        }
+
+        let bound_function = BoundFunction {
+            target,
+            this: arguments.get(0).cloned().unwrap_or(JsValue::Undefined),
+            arguments: arguments.iter().skip(1).cloned().collect(),
+        };
+
+        Ok(JsFunction::Bound(bound_function).into())
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("call".to_string(), JsValue::native_function(call)),
+        ("apply".to_string(), JsValue::native_function(apply)),
+        ("bind".to_string(), JsValue::native_function(bind)),
+    ])
+}
+
+fn error_message_argument(arguments: &Vec<JsValue>) -> Option<String> {
+    match arguments.get(0) {
+        None | Some(JsValue::Undefined) => None,
+        Some(message) => Some(message.to_js_string()),
+    }
+}
+
+/// Error object inheriting from the given error prototype, `stack` holds `name: message`
+/// since the interpreter doesn't keep track of call frames
+pub fn create_error(prototype: &JsObjectRef, message: Option<String>) -> JsValue {
+    let mut error = JsObject::new(ObjectKind::Error, []);
+    error.set_proto(JsObjectRef::clone(prototype));
+
+    if let Some(message) = message {
+        error.add_property("message", JsValue::String(message.into()));
+    }
+
+    let stack = error.error_to_string();
+    error.add_property("stack", JsValue::String(stack.into()));
+
+    return error.to_js_value();
+}
+
+fn get_error_prototype() -> JsObject {
+    fn to_string(interpreter: &Interpreter, _: &Vec<JsValue>) -> Result<JsValue, String> {
+        match get_this(interpreter) {
+            JsValue::Object(object) => Ok(JsValue::String(object.borrow().error_to_string().into())),
+            _ => Err("TypeError: Error.prototype.toString called on non-object".to_string()),
+        }
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("name".to_string(), JsValue::String("Error".into())),
+        ("message".to_string(), JsValue::String(String::new().into())),
+        ("toString".to_string(), JsValue::native_function(to_string)),
+    ])
+}
+
+fn get_native_error_prototype(name: &str, error_prototype: &JsObjectRef) -> JsObject {
+    let mut prototype = JsObject::new(ObjectKind::Ordinary, [
+        ("name".to_string(), JsValue::String(name.into())),
+        ("message".to_string(), JsValue::String(String::new().into())),
+    ]);
+    prototype.set_proto(JsObjectRef::clone(error_prototype));
+    prototype
+}
+
+/// Object argument used as a key of a weak collection, primitives can't be held weakly
+fn weak_key_argument(arguments: &Vec<JsValue>) -> Option<JsObjectRef> {
+    match arguments.get(0) {
+        Some(JsValue::Object(object)) => Some(JsObjectRef::clone(object)),
+        _ => None,
+    }
+}
+
+/// Runs callback with storage of `this`, which has to be a `WeakMap` or a `WeakSet` depending on `is_map`
+fn with_weak_collection<T>(interpreter: &Interpreter, is_map: bool, method: &str, callback: impl FnOnce(&mut WeakCollection) -> T) -> Result<T, String> {
+    let this = get_this(interpreter);
+
+    if let JsValue::Object(object) = &this {
+        match &mut object.borrow_mut().kind {
+            ObjectKind::WeakMap(entries) if is_map => return Ok(callback(entries)),
+            ObjectKind::WeakSet(entries) if !is_map => return Ok(callback(entries)),
+            _ => {}
+        }
+    }
+
+    let name = if is_map { "WeakMap" } else { "WeakSet" };
+    return Err(format!("TypeError: Method {name}.prototype.{method} called on incompatible receiver {}", this.get_type_as_str()));
+}
+
+fn create_weak_collection(prototype: &JsObjectRef, kind: ObjectKind) -> JsValue {
+    let mut collection = JsObject::new(kind, []);
+    collection.set_proto(JsObjectRef::clone(prototype));
+    return collection.to_js_value();
+}
+
+/// `new WeakMap(entries)`, entries are optional `[key, value]` pairs
+fn weak_map_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let mut entries = WeakCollection::default();
+
+    if let Some(iterable) = arguments.get(0).filter(|x| !matches!(x, JsValue::Undefined | JsValue::Null)) {
+        for entry in interpreter.iterate_value(iterable)? {
+            let JsValue::Object(entry) = entry else {
+                return Err(format!("TypeError: Iterator value {} is not an entry object", entry.to_js_string()));
+            };
+
+            let (key, value) = (entry.borrow().get_property_value("0"), entry.borrow().get_property_value("1"));
+
+            match key {
+                JsValue::Object(key) => entries.set(&key, value),
+                _ => return Err("TypeError: Invalid value used as weak map key".to_string()),
+            }
+        }
+    }
+
+    Ok(create_weak_collection(&interpreter.intrinsics().weak_map_prototype, ObjectKind::WeakMap(entries)))
+}
+
+/// `new WeakSet(values)`, values are optional
+fn weak_set_constructor(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    let mut entries = WeakCollection::default();
+
+    if let Some(iterable) = arguments.get(0).filter(|x| !matches!(x, JsValue::Undefined | JsValue::Null)) {
+        for value in interpreter.iterate_value(iterable)? {
+            match value {
+                JsValue::Object(value) => entries.set(&value, JsValue::Boolean(true)),
+                _ => return Err("TypeError: Invalid value used in weak set".to_string()),
+            }
+        }
+    }
+
+    Ok(create_weak_collection(&interpreter.intrinsics().weak_set_prototype, ObjectKind::WeakSet(entries)))
+}
+
+/// Weak collections have no size & can't be iterated, since their entries disappear together with keys
+fn get_weak_map_prototype() -> JsObject {
+    fn get(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let key = weak_key_argument(arguments);
+        with_weak_collection(interpreter, true, "get", |entries| key.map_or(JsValue::Undefined, |x| entries.get(&x)))
+    }
+
+    fn set(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let key = weak_key_argument(arguments).ok_or("TypeError: Invalid value used as weak map key".to_string())?;
+        let value = arguments.get(1).cloned().unwrap_or(JsValue::Undefined);
+        with_weak_collection(interpreter, true, "set", |entries| entries.set(&key, value))?;
+        Ok(get_this(interpreter))
+    }
+
+    fn has(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let key = weak_key_argument(arguments);
+        with_weak_collection(interpreter, true, "has", |entries| JsValue::Boolean(key.is_some_and(|x| entries.has(&x))))
+    }
+
+    fn delete(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let key = weak_key_argument(arguments);
+        with_weak_collection(interpreter, true, "delete", |entries| JsValue::Boolean(key.is_some_and(|x| entries.delete(&x))))
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("get".to_string(), JsValue::native_function(get)),
+        ("set".to_string(), JsValue::native_function(set)),
+        ("has".to_string(), JsValue::native_function(has)),
+        ("delete".to_string(), JsValue::native_function(delete)),
+    ])
+}
+
+fn get_weak_set_prototype() -> JsObject {
+    fn add(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let value = weak_key_argument(arguments).ok_or("TypeError: Invalid value used in weak set".to_string())?;
+        with_weak_collection(interpreter, false, "add", |entries| entries.set(&value, JsValue::Boolean(true)))?;
+        Ok(get_this(interpreter))
+    }
+
+    fn has(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let value = weak_key_argument(arguments);
+        with_weak_collection(interpreter, false, "has", |entries| JsValue::Boolean(value.is_some_and(|x| entries.has(&x))))
+    }
+
+    fn delete(interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+        let value = weak_key_argument(arguments);
+        with_weak_collection(interpreter, false, "delete", |entries| JsValue::Boolean(value.is_some_and(|x| entries.delete(&x))))
+    }
+
+    JsObject::new(ObjectKind::Ordinary, [
+        ("add".to_string(), JsValue::native_function(add)),
+        ("has".to_string(), JsValue::native_function(has)),
+        ("delete".to_string(), JsValue::native_function(delete)),
+    ])
+}
@@ -0,0 +1,145 @@
+/// Separators used when numbers are formatted for people, e.g. by `toLocaleString`.
+/// Only separators differ between locales, there is no ICU data behind them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Locale {
+    pub group_separator: char,
+    pub decimal_separator: char,
+}
+
+impl Default for Locale {
+    /// `en-US`
+    fn default() -> Self {
+        Self {
+            group_separator: ',',
+            decimal_separator: '.',
+        }
+    }
+}
+
+impl Locale {
+    /// Locale from a language tag like `de-DE`, only the language & a few regions are distinguished
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let (group_separator, decimal_separator) = match tag {
+            "de-CH" => ('\u{2019}', '.'),
+            "en-IN" | "hi-IN" => (',', '.'),
+            _ => match tag.split(['-', '_']).next()? {
+                "en" | "ja" | "zh" | "ko" | "he" | "th" => (',', '.'),
+                "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" => ('.', ','),
+                "fr" | "ru" | "pl" | "cs" | "sk" | "uk" | "sv" | "fi" | "nb" | "hu" => ('\u{a0}', ','),
+                _ => return None,
+            },
+        };
+
+        return Some(Self { group_separator, decimal_separator });
+    }
+
+    pub fn parse(tag: &str) -> Result<Self, String> {
+        Self::from_tag(tag).ok_or(format!("RangeError: Unsupported locale '{tag}'"))
+    }
+
+    /// Number with grouped thousands, rounded half away from zero to at most `max_fraction_digits`
+    pub fn format_number(&self, value: f64, min_fraction_digits: usize, max_fraction_digits: usize) -> String {
+        if value.is_nan() {
+            return "NaN".to_string();
+        }
+
+        let sign = if value < 0.0 { "-" } else { "" };
+
+        if value.is_infinite() {
+            return format!("{sign}∞");
+        }
+
+        let scale = 10f64.powi(max_fraction_digits as i32);
+        let rounded = (value.abs() * scale).round() / scale;
+        let formatted = format!("{:.*}", max_fraction_digits, rounded);
+        let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+        let mut fraction = fraction.to_string();
+
+        while fraction.len() > min_fraction_digits && fraction.ends_with('0') {
+            fraction.pop();
+        }
+
+        let mut result = sign.to_string();
+
+        for (i, digit) in integer.chars().enumerate() {
+            if i != 0 && (integer.len() - i) % 3 == 0 {
+                result.push(self.group_separator);
+            }
+
+            result.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            result.push(self.decimal_separator);
+            result.push_str(&fraction);
+        }
+
+        return result;
+    }
+}
+
+/// Year, month & day of days since 1970-01-01 in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Formats milliseconds since the epoch in UTC, `YYYY`, `MM`, `DD`, `HH`, `mm`, `ss` & `SSS`
+/// are replaced with parts of the date, the rest of the pattern is copied as is
+pub fn format_date(timestamp: f64, pattern: &str) -> Result<String, String> {
+    // same range as `Date`, ±100,000,000 days around the epoch
+    if !timestamp.is_finite() || timestamp.abs() > 8.64e15 {
+        return Err("RangeError: Invalid time value".to_string());
+    }
+
+    let milliseconds = timestamp.trunc() as i64;
+    let (year, month, day) = civil_from_days(milliseconds.div_euclid(86_400_000));
+    let time = milliseconds.rem_euclid(86_400_000);
+
+    let parts = [
+        ("YYYY", format!("{year:04}")),
+        ("SSS", format!("{:03}", time % 1000)),
+        ("MM", format!("{month:02}")),
+        ("DD", format!("{day:02}")),
+        ("HH", format!("{:02}", time / 3_600_000)),
+        ("mm", format!("{:02}", time / 60_000 % 60)),
+        ("ss", format!("{:02}", time / 1000 % 60)),
+    ];
+
+    let mut result = String::new();
+    let mut rest = pattern;
+
+    'pattern: while let Some(char) = rest.chars().next() {
+        for (token, value) in &parts {
+            if let Some(after_token) = rest.strip_prefix(token) {
+                result.push_str(value);
+                rest = after_token;
+                continue 'pattern;
+            }
+        }
+
+        result.push(char);
+        rest = &rest[char.len_utf8()..];
+    }
+
+    return Ok(result);
+}
+
+#[test]
+fn dates_are_formatted_in_utc() {
+    assert_eq!(format_date(0.0, "YYYY-MM-DD HH:mm:ss.SSS").unwrap(), "1970-01-01 00:00:00.000");
+    assert_eq!(format_date(951_782_400_000.0 + 45_296_789.0, "DD.MM.YYYY at HH:mm").unwrap(), "29.02.2000 at 12:34");
+    assert_eq!(format_date(-1.0, "YYYY-MM-DD HH:mm:ss.SSS").unwrap(), "1969-12-31 23:59:59.999");
+    assert!(format_date(f64::NAN, "YYYY").is_err());
+}
@@ -1,2 +1,8 @@
 pub mod ast_interpreter;
-pub mod environment;
\ No newline at end of file
+pub mod environment;
+pub mod coverage;
+pub mod random;
+pub mod intrinsics;
+pub mod engine;
+pub mod locale;
+pub mod hooks;
\ No newline at end of file
@@ -26,9 +26,14 @@ pub const IMPORT_KEYWORD: &'static str = "import";
 pub const EXPORT_KEYWORD: &'static str = "export";
 pub const STATIC_KEYWORD: &'static str = "static";
 pub const SWITCH_KEYWORD: &'static str = "switch";
+pub const CASE_KEYWORD: &'static str = "case";
+pub const DEFAULT_KEYWORD: &'static str = "default";
 pub const RETURN_KEYWORD: &'static str = "return";
+pub const INSTANCEOF_KEYWORD: &'static str = "instanceof";
+pub const TYPEOF_KEYWORD: &'static str = "typeof";
+pub const VOID_KEYWORD: &'static str = "void";
 
-pub const KEYWORDS: [&'static str; 29] = [
+pub const KEYWORDS: [&'static str; 34] = [
     THIS_KEYWORD,
     UNDEFINED_KEYWORD,
     NULL_KEYWORD,
@@ -57,7 +62,12 @@ pub const KEYWORDS: [&'static str; 29] = [
     EXPORT_KEYWORD,
     STATIC_KEYWORD,
     SWITCH_KEYWORD,
+    CASE_KEYWORD,
+    DEFAULT_KEYWORD,
     RETURN_KEYWORD,
+    INSTANCEOF_KEYWORD,
+    TYPEOF_KEYWORD,
+    VOID_KEYWORD,
 ];
 
 
@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use crate::diagnostic::{Diagnostic, DiagnosticBag, print_parse_error};
+use crate::parser::{ParseError, Parser};
+use crate::symbol_checker::symbol_checker::SymbolChecker;
+
+/// Problems of a single source found without running it
+pub struct LintReport<'a> {
+    pub parse_errors: Vec<ParseError>,
+    pub errors: Vec<Diagnostic<'a>>,
+    pub warnings: Vec<Diagnostic<'a>>,
+}
+
+/// Problems of many sources, symbol problems are sorted by file & line
+pub struct SourcesLintReport<'a> {
+    /// Syntax errors with name of the file they were found in
+    pub parse_errors: Vec<(&'a str, ParseError)>,
+    pub diagnostics: DiagnosticBag<'a>,
+}
+
+impl<'a> SourcesLintReport<'a> {
+    pub fn error_count(&self) -> usize {
+        self.parse_errors.len() + self.diagnostics.errors.len()
+    }
+}
+
+/// Parses source in error tolerant mode & runs symbol checker over everything which could be parsed
+pub fn lint_source(source: &str) -> LintReport<'_> {
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    let (ast, parse_errors) = Parser::default().parse_tolerant(source);
+    SymbolChecker::new(source, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+    let diagnostic_bag = Rc::try_unwrap(diagnostic_bag).ok().unwrap().into_inner();
+
+    return LintReport {
+        parse_errors,
+        errors: diagnostic_bag.errors,
+        warnings: diagnostic_bag.warnings,
+    };
+}
+
+/// Lints pairs of file name & source into a single bag, so problems can be reported together
+pub fn lint_sources(sources: &[(String, String)]) -> SourcesLintReport<'_> {
+    let mut diagnostics = DiagnosticBag::new();
+    let mut parse_errors = vec![];
+
+    for (file_name, source) in sources {
+        let report = lint_source(source);
+
+        // pragmas are already applied by the bag of the source, reported diagnostics only get the file
+        diagnostics.begin_source(file_name);
+        report.errors.into_iter().for_each(|x| diagnostics.report_error(x));
+        report.warnings.into_iter().for_each(|x| diagnostics.report_warning(x));
+        parse_errors.extend(report.parse_errors.into_iter().map(|x| (file_name.as_str(), x)));
+    }
+
+    diagnostics.sort();
+
+    return SourcesLintReport {
+        parse_errors,
+        diagnostics,
+    };
+}
+
+/// Totals printed after linting all files
+#[derive(Debug, Default, PartialEq)]
+pub struct LintSummary {
+    pub files: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Prints problems of every file, returns their totals
+pub fn lint_files(file_paths: &[PathBuf], is_colored: bool, is_printing_warnings: bool) -> Result<LintSummary, String> {
+    let mut sources = vec![];
+
+    for file_path in file_paths {
+        let source = fs::read_to_string(file_path)
+            .map_err(|e| format!("Cannot read {}: {e}", file_path.display()))?;
+        sources.push((file_path.display().to_string(), source));
+    }
+
+    let report = lint_sources(&sources);
+
+    for (file_name, error) in &report.parse_errors {
+        let source = sources.iter().find(|(name, _)| name == file_name).map_or("", |(_, source)| source);
+        print_parse_error(error, file_name, source, is_colored);
+    }
+
+    for error in &report.diagnostics.errors {
+        error.print_diagnostic(error.file().unwrap_or_default());
+    }
+
+    if is_printing_warnings {
+        for warning in &report.diagnostics.warnings {
+            warning.print_diagnostic(warning.file().unwrap_or_default());
+        }
+    }
+
+    return Ok(LintSummary {
+        files: sources.len(),
+        errors: report.error_count(),
+        warnings: report.diagnostics.warnings.len(),
+    });
+}
+
+#[test]
+fn lint_reports_every_syntax_error_and_symbol_problem() {
+    let code = "
+        let a = ;
+        const b = 1;
+        b = 2;
+        if (a) { let c = * 2; }
+        let unused = a;
+        d = b;
+    ";
+    let report = lint_source(code);
+
+    let parse_errors: Vec<(&str, usize)> = report.parse_errors.iter()
+        .map(|x| (x.message.as_str(), x.span.start.line))
+        .collect();
+    let errors: Vec<(&str, usize)> = report.errors.iter()
+        .map(|x| (x.kind().code(), x.kind().span().start.line))
+        .collect();
+    let warnings: Vec<&str> = report.warnings.iter().map(|x| x.kind().code()).collect();
+
+    assert_eq!(parse_errors, vec![("Unexpected token \";\"", 1), ("Unexpected token \"*\"", 4)]);
+    assert_eq!(errors, vec![("constant-assigning", 3), ("variable-not-defined", 6)]);
+    assert_eq!(warnings, vec!["unused-variable"]);
+}
+
+#[test]
+fn lint_sources_sorts_and_deduplicates_diagnostics() {
+    let sources = vec![
+        ("b.js".to_string(), "let unused = 1;\nx = 2;".to_string()),
+        ("a.js".to_string(), "// rustjs-disable-next-line unused-variable\nlet skipped = 1;\ny = 1;\nlet = ;".to_string()),
+        ("b.js".to_string(), "let unused = 1;\nx = 2;".to_string()),
+    ];
+    let report = lint_sources(&sources);
+
+    let parse_errors: Vec<(&str, usize)> = report.parse_errors.iter().map(|(file, x)| (*file, x.span.start.line)).collect();
+    let errors: Vec<(Option<&str>, &str, usize)> = report.diagnostics.errors.iter()
+        .map(|x| (x.file(), x.kind().code(), x.kind().span().start.line))
+        .collect();
+    let warnings: Vec<(Option<&str>, &str)> = report.diagnostics.warnings.iter().map(|x| (x.file(), x.kind().code())).collect();
+
+    assert_eq!(parse_errors, vec![("a.js", 3)]);
+    assert_eq!(errors, vec![(Some("a.js"), "variable-not-defined", 2), (Some("b.js"), "variable-not-defined", 1)]);
+    assert_eq!(warnings, vec![(Some("b.js"), "unused-variable")]);
+    assert_eq!(report.error_count(), 3);
+}
@@ -8,68 +8,468 @@ mod visitor;
 mod symbol_checker;
 mod diagnostic;
 mod nodes;
+mod minifier;
+mod project;
+mod debugger;
+mod linter;
+mod repl;
+mod report;
+mod ast_graph;
+mod formatter;
 use nodes::*;
 use std::cell::RefCell;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use crate::parser::Parser;
-use diagnostic::DiagnosticBag;
+use std::time::Instant;
+use crate::parser::{ParsedSource, Parser, TextEdit};
+use diagnostic::{DiagnosticBag, print_runtime_error};
 use crate::symbol_checker::symbol_checker::SymbolChecker;
-use crate::interpreter::ast_interpreter::Interpreter;
+use crate::interpreter::engine::ExecutionEngine;
+use crate::interpreter::ast_interpreter::{ErrorLocation, FractionalIndexCheck, Interpreter, ScriptSource};
+use crate::interpreter::locale::Locale;
+use crate::debugger::Debugger;
+use crate::formatter::{format_source, FormatOptions, QuoteStyle};
+use crate::repl::Repl;
+use crate::report::{ReportFormat, RunReport, ScriptReport};
+use crate::project::{collect_lint_paths, collect_script_paths, ProjectConfig, PROJECT_CONFIG_FILE_NAME, WarningLevel};
+use crate::value::{DisplayLimits, DisplayMode, JsValue};
 
-fn eval(code: &str, is_debug: bool) {
-    if is_debug {
-        println!("-----DEBUG (printing tokens)-----");
-        let mut scanner = scanner::Scanner::new(code.to_string());
+#[derive(Default)]
+struct EvalOptions {
+    is_debug: bool,
+    dump_scopes: bool,
+    coverage: bool,
+    /// `--time`, prints how long interpreter startup & every script took
+    is_timed: bool,
+    seed: Option<u64>,
+    /// `--mutable-globals`, scripts may reassign builtin globals & change builtin objects
+    mutable_globals: bool,
+    /// `--fractional-index warn`, reports arrays indexed with fractional numbers or NaN
+    fractional_index: FractionalIndexCheck,
+    /// `--strict-arithmetic`, arithmetic on operands of different types throws instead of converting them
+    strict_arithmetic: bool,
+    warnings: WarningLevel,
+    display: DisplayMode,
+    display_limits: DisplayLimits,
+    /// `--locale de-DE`, separators of numbers formatted by `toLocaleString`
+    locale: Locale,
+    result_output: ResultOutput,
+    /// `--report json`, timings & results of scripts are written to stderr as a single record once the run ends
+    report: Option<ReportFormat>,
+    /// `--call main`, global function called without arguments after all scripts ran, its return value is the result of the run
+    call: Option<String>,
+}
 
-        while let Some(token) = scanner.next_token() {
-            println!("{:?}", token);
+/// Which results of scripts are printed, result of a script is the value of its last top level expression statement.
+/// Scripts without such statements, e.g. only declaring functions, have no result
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum ResultOutput {
+    /// Result of every script after `> ` prompt
+    #[default]
+    EveryScript,
+    /// `--expr-result`, value of every top level expression statement with its location, e.g. `main.js:3 > 7`
+    EveryExpression,
+    /// `--quiet`, only output of scripts themselves
+    Nothing,
+    /// `--print-result`, result of the last script without the prompt, e.g. to use it in shell
+    LastScript,
+}
+
+impl ResultOutput {
+    fn from_arguments(arguments: &[String]) -> Self {
+        if arguments.iter().any(|x| x == "--quiet") {
+            return ResultOutput::Nothing;
+        }
+
+        if arguments.iter().any(|x| x == "--print-result") {
+            return ResultOutput::LastScript;
+        }
+
+        if arguments.iter().any(|x| x == "--expr-result") {
+            return ResultOutput::EveryExpression;
+        }
+
+        return ResultOutput::default();
+    }
+}
+
+/// Flags which are followed by a value, e.g. `--seed 42`
+const VALUE_FLAGS: [&str; 10] = ["--seed", "--display", "--max-items", "--max-string-length", "--locale", "--replay", "--report", "--fractional-index", "--quotes", "--call"];
+
+/// Environment variable selecting display mode when `--display` is not given
+const DISPLAY_MODE_VARIABLE: &str = "RUSTJS_DISPLAY";
+
+/// Display mode from `--display` flag, `RUSTJS_DISPLAY` or `NO_COLOR` environment variables
+fn get_display_mode(arguments: &[String]) -> Result<DisplayMode, String> {
+    if let Some(mode) = get_flag_value(arguments, "--display") {
+        return DisplayMode::parse(mode);
+    }
+
+    if let Ok(mode) = std::env::var(DISPLAY_MODE_VARIABLE) {
+        return DisplayMode::parse(&mode);
+    }
+
+    // https://no-color.org
+    if std::env::var("NO_COLOR").is_ok_and(|x| !x.is_empty()) {
+        return Ok(DisplayMode::Plain);
+    }
+
+    return Ok(DisplayMode::default());
+}
+
+/// Display limits from `--max-items` & `--max-string-length` flags, `0` disables the limit
+fn get_display_limits(arguments: &[String]) -> Result<DisplayLimits, String> {
+    let mut limits = DisplayLimits::default();
+
+    for (flag, limit) in [("--max-items", &mut limits.max_items), ("--max-string-length", &mut limits.max_string_length)] {
+        if let Some(value) = get_flag_value(arguments, flag) {
+            *limit = match value.parse() {
+                Ok(0) => usize::MAX,
+                Ok(value) => value,
+                Err(_) => return Err(format!("{flag} should be a non-negative integer, but got '{value}'")),
+            };
+        }
+    }
+
+    return Ok(limits);
+}
+
+/// Prints error in red, unless colors are disabled by the display mode
+fn print_error(message: &str, display: DisplayMode) {
+    if display == DisplayMode::Colored {
+        eprintln!("\x1b[31m{message}\x1b[0m");
+    } else {
+        eprintln!("{message}");
+    }
+}
+
+fn get_flag_value<'a>(arguments: &'a [String], flag: &str) -> Option<&'a String> {
+    let position = arguments.iter().position(|x| x == flag)?;
+    arguments.get(position + 1)
+}
+
+/// Arguments which are neither flags nor values of flags
+fn get_positional_arguments(arguments: &[String]) -> Vec<&String> {
+    let mut positional = vec![];
+    let mut is_flag_value = false;
+
+    for argument in arguments {
+        if is_flag_value {
+            is_flag_value = false;
+        } else if argument.starts_with("--") {
+            is_flag_value = VALUE_FLAGS.contains(&argument.as_str());
+        } else {
+            positional.push(argument);
+        }
+    }
+
+    positional
+}
+
+const COVERAGE_REPORT_PATH: &str = "lcov.info";
+/// How often `check --watch` reads the file to find out whether it changed
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// State shared by all scripts executed in one run
+struct Session {
+    interpreter: Interpreter,
+    /// Top level declarations of already executed scripts, with their constness
+    declared_globals: Vec<(String, bool)>,
+    coverage_report: String,
+    last_result: JsValue,
+    report: RunReport,
+}
+
+impl Session {
+    fn new(options: &EvalOptions) -> Self {
+        let start = Instant::now();
+        let interpreter = Interpreter::default();
+        let startup = start.elapsed();
+
+        if options.is_timed {
+            eprintln!("startup: {:.3}ms", startup.as_secs_f64() * 1000.0);
+        }
+
+        if let Some(seed) = options.seed {
+            interpreter.set_random_seed(seed);
+        }
+
+        interpreter.set_mutable_globals(options.mutable_globals);
+        interpreter.set_fractional_index_check(options.fractional_index);
+        interpreter.set_strict_arithmetic(options.strict_arithmetic);
+        interpreter.set_display_mode(options.display);
+        interpreter.set_display_limits(options.display_limits);
+        interpreter.set_locale(options.locale);
+
+        Self {
+            interpreter,
+            declared_globals: vec![],
+            coverage_report: String::new(),
+            last_result: JsValue::Undefined,
+            report: RunReport { startup, scripts: vec![] },
         }
     }
+}
 
+fn get_top_level_declarations(ast: &AstStatement) -> Vec<(String, bool)> {
+    let AstStatement::ProgramStatement(program) = ast else {
+        return vec![];
+    };
+
+    program.statements.iter().filter_map(|statement| match statement {
+        AstStatement::VariableDeclaration(node) => {
+            Some((node.id.id.clone(), matches!(node.kind, VariableDeclarationKind::Const)))
+        }
+        AstStatement::FunctionDeclaration(node) => Some((node.function_signature.name.id.clone(), false)),
+        AstStatement::ExpressionStatement(AstExpression::ClassDeclaration(node)) => Some((node.name.id.clone(), false)),
+        _ => None,
+    }).collect()
+}
+
+/// Parses a script & runs symbol checker over it, printing found problems.
+/// Returns the AST if the script can be run, `declared_globals` are bindings of the scripts checked before it.
+/// With `has_following_scripts` top level declarations aren't reported as unused, the following scripts can use them
+fn check_script(code: &str, file_path: &str, declared_globals: &[(String, bool)], has_following_scripts: bool, options: &EvalOptions, report: &mut ScriptReport) -> Option<AstStatement> {
+    let start = Instant::now();
     let mut parser = Parser::default();
-    let ast = parser
-        .parse(code)
-        .expect(format!("Error occurred during parsing").as_str());
+    let parsed = parser.parse(code);
+    report.parse = start.elapsed();
+
+    let ast = match parsed {
+        Ok(ast) => ast,
+        Err(e) => {
+            print_error(&format!("Error occurred during parsing: {e}"), options.display);
+            report.error = Some(e);
+            return None;
+        }
+    };
 
-    if is_debug {
+    if options.is_debug {
         println!("{:#?}", ast);
     }
 
+    if !check_ast(code, &ast, file_path, declared_globals, has_following_scripts, options, report) {
+        return None;
+    }
+
+    return Some(ast);
+}
+
+/// Runs symbol checker over a parsed script & prints found problems, returns false if the script can't be run
+fn check_ast(code: &str, ast: &AstStatement, file_path: &str, declared_globals: &[(String, bool)], has_following_scripts: bool, options: &EvalOptions, report: &mut ScriptReport) -> bool {
     let diagnostic_bag_ref = Rc::new(RefCell::new(DiagnosticBag::new()));
     let mut symbol_checker = SymbolChecker::new(code, Rc::clone(&diagnostic_bag_ref));
-    symbol_checker.check_symbols(&ast);
 
-    for error in &diagnostic_bag_ref.borrow().warnings {
-        error.print_diagnostic();
+    for (name, is_const) in declared_globals {
+        symbol_checker.define_external_global(name, *is_const);
+    }
+
+    symbol_checker.set_top_level_unused_check(!has_following_scripts);
+    symbol_checker.set_mutable_globals(options.mutable_globals);
+
+    let start = Instant::now();
+    symbol_checker.check_symbols(ast);
+    report.check = start.elapsed();
+
+    if options.dump_scopes {
+        print!("{}", symbol_checker.dump_scopes());
+    }
+
+    if options.warnings != WarningLevel::Allow {
+        for error in &diagnostic_bag_ref.borrow().warnings {
+            error.print_diagnostic(file_path);
+        }
     }
 
     for error in &diagnostic_bag_ref.borrow().errors {
-        error.print_diagnostic();
+        error.print_diagnostic(file_path);
+    }
+
+    let diagnostic_bag = diagnostic_bag_ref.borrow();
+    report.warnings = diagnostic_bag.warnings.len();
+    report.errors = diagnostic_bag.errors.len();
+    let is_denied_by_warnings = options.warnings == WarningLevel::Deny && diagnostic_bag.warnings.len() != 0;
+
+    return diagnostic_bag.errors.len() == 0 && !is_denied_by_warnings;
+}
+
+/// Checks & runs a script in the session, returns false if it was not run because of errors
+fn eval(session: &mut Session, code: &str, file_path: &str, has_following_scripts: bool, options: &EvalOptions) -> bool {
+    let mut report = ScriptReport::new(file_path);
+    let is_evaluated = eval_script(session, code, file_path, has_following_scripts, options, &mut report);
+    session.report.scripts.push(report);
+    return is_evaluated;
+}
+
+fn eval_script(session: &mut Session, code: &str, file_path: &str, has_following_scripts: bool, options: &EvalOptions, report: &mut ScriptReport) -> bool {
+    if options.is_debug {
+        println!("-----DEBUG (printing tokens)-----");
+        for token in scanner::tokenize(code) {
+            println!("{:?}", token);
+        }
+    }
+
+    let Some(ast) = check_script(code, file_path, &session.declared_globals, has_following_scripts, options, report) else {
+        return false;
+    };
+
+    let interpreter = &session.interpreter;
+
+    if options.coverage {
+        interpreter.enable_coverage(&ast);
+    }
+
+    let mut print_expression_result = |statement: &AstStatement, value: &JsValue| {
+        if options.result_output == ResultOutput::EveryExpression {
+            let value = value.display(options.display).with_limits(options.display_limits);
+            println!("{file_path}:{} > {value}", statement.get_span().start.line + 1);
+        }
+    };
+
+    interpreter.set_source(Some(Rc::new(ScriptSource { path: file_path.to_string(), code: code.to_string() })));
+
+    let start = Instant::now();
+    let result = interpreter.run_with_results(&ast, &mut print_expression_result);
+    report.eval = start.elapsed();
+
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            report_runtime_error(interpreter, &e, file_path, code, options.display);
+            report.error = Some(e);
+            return false;
+        }
+    };
+
+    report.result = result.as_ref().map(|x| x.display(DisplayMode::Json).to_string());
+
+    if let (ResultOutput::EveryScript, Some(result)) = (options.result_output, &result) {
+        print_result(result, options);
     }
 
-    if diagnostic_bag_ref.borrow().errors.len() == 0 {
-        let mut interpreter = Interpreter::default();
-        let result = interpreter
-            .interpret(&ast)
-            .expect("Error during evaluating node");
+    session.last_result = result.unwrap_or(JsValue::Undefined);
 
-        println!("> {}", result);
-        // match result {
-        //     None => println!("No Value"),
-        //     Some(value) => println!("> {}", value),
-        // }
+    if let Some(coverage) = interpreter.take_coverage() {
+        session.coverage_report.push_str(&coverage.to_lcov(file_path));
+        eprintln!("{file_path}: {}", coverage.summary());
+    }
+
+    session.declared_globals.extend(get_top_level_declarations(&ast));
+    return true;
+}
+
+/// Prints uncaught error, pointing at the failed part of the source when its location is known.
+/// Error inside a function declared by another script is shown in that script, `file_path` & `code` are used
+/// when the interpreter doesn't know the source
+fn report_runtime_error(interpreter: &Interpreter, message: &str, file_path: &str, code: &str, display: DisplayMode) {
+    let is_colored = display == DisplayMode::Colored;
+
+    match interpreter.take_error_location() {
+        Some(ErrorLocation { span, source: Some(source) }) => print_runtime_error(message, &span, &source.path, &source.code, is_colored),
+        Some(ErrorLocation { span, source: None }) => print_runtime_error(message, &span, file_path, code, is_colored),
+        None => print_error(&format!("Uncaught {message}"), display),
+    }
+}
+
+/// Prints completion value of a script, in JSON mode without the prompt so the line stays valid JSON
+fn print_result(result: &JsValue, options: &EvalOptions) {
+    let result = result.display(options.display).with_limits(options.display_limits);
+
+    match options.display {
+        DisplayMode::Json => println!("{result}"),
+        _ => println!("> {result}"),
     }
 }
 
 fn main() {
-    let path = std::env::args().nth(1);
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
 
-    if path.is_some() {
-        eval_file(&path.unwrap());
+    let mut options = EvalOptions {
+        is_debug: arguments.iter().any(|x| x == "--debug"),
+        dump_scopes: arguments.iter().any(|x| x == "--dump-scopes"),
+        coverage: arguments.iter().any(|x| x == "--coverage"),
+        is_timed: arguments.iter().any(|x| x == "--time"),
+        mutable_globals: arguments.iter().any(|x| x == "--mutable-globals"),
+        seed: get_flag_value(&arguments, "--seed")
+            .map(|x| x.parse().map_err(|_| format!("--seed should be a non-negative integer, but got '{x}'")))
+            .transpose()
+            .unwrap_or_else(|e| {
+                print_error(&e, DisplayMode::Plain);
+                std::process::exit(1);
+            }),
+        fractional_index: get_flag_value(&arguments, "--fractional-index").map_or(Ok(FractionalIndexCheck::default()), |x| FractionalIndexCheck::parse(x)).unwrap_or_else(|e| {
+            print_error(&e, DisplayMode::Plain);
+            std::process::exit(1);
+        }),
+        strict_arithmetic: arguments.iter().any(|x| x == "--strict-arithmetic"),
+        warnings: WarningLevel::default(),
+        display: get_display_mode(&arguments).unwrap_or_else(|e| {
+            print_error(&e, DisplayMode::Plain);
+            std::process::exit(1);
+        }),
+        display_limits: get_display_limits(&arguments).unwrap_or_else(|e| {
+            print_error(&e, DisplayMode::Plain);
+            std::process::exit(1);
+        }),
+        locale: get_flag_value(&arguments, "--locale").map_or(Ok(Locale::default()), |x| Locale::parse(x)).unwrap_or_else(|e| {
+            print_error(&e, DisplayMode::Plain);
+            std::process::exit(1);
+        }),
+        result_output: ResultOutput::from_arguments(&arguments),
+        report: get_flag_value(&arguments, "--report").map(|x| ReportFormat::parse(x)).transpose().unwrap_or_else(|e| {
+            print_error(&e, DisplayMode::Plain);
+            std::process::exit(1);
+        }),
+        call: get_flag_value(&arguments, "--call").cloned(),
+    };
+
+    let positional = get_positional_arguments(&arguments);
+
+    if arguments.iter().any(|x| x == "--tokens") {
+        let path = positional.first().expect("Expected path of the file to tokenize");
+        print_tokens(path);
+    } else if positional.first().map(|x| x.as_str()) == Some("minify") {
+        let path = positional.get(1).expect("Expected path of the file to minify");
+        let remove_unused = arguments.iter().any(|x| x == "--remove-unused");
+        minify_file(path, remove_unused);
+    } else if positional.first().map(|x| x.as_str()) == Some("lint") {
+        lint(&positional[1..], &options);
+    } else if positional.first().map(|x| x.as_str()) == Some("check") && arguments.iter().any(|x| x == "--watch") {
+        let path = positional.get(1).expect("Expected path of the file to watch");
+        watch_file(path, &options);
+    } else if positional.first().map(|x| x.as_str()) == Some("check") {
+        check_files(&positional[1..], &mut options);
+    } else if positional.first().map(|x| x.as_str()) == Some("ast") {
+        let path = positional.get(1).expect("Expected path of the file to print");
+        print_ast(path, arguments.iter().any(|x| x == "--dot"), &options);
+    } else if positional.first().map(|x| x.as_str()) == Some("fmt") {
+        let path = positional.get(1).expect("Expected path of the file to format");
+        let quotes = get_flag_value(&arguments, "--quotes").map_or(Ok(QuoteStyle::default()), |x| QuoteStyle::parse(x)).unwrap_or_else(|e| {
+            print_error(&e, DisplayMode::Plain);
+            std::process::exit(1);
+        });
+        print_formatted(path, &FormatOptions { quotes, ..FormatOptions::default() }, &options);
+    } else if positional.first().map(|x| x.as_str()) == Some("debug-ast") {
+        let path = positional.get(1).expect("Expected path of the file to debug");
+        debug_file(path, &options);
+    } else if let Some(session_path) = get_flag_value(&arguments, "--replay") {
+        repl(&options, Some(session_path));
+    } else if !positional.is_empty() {
+        match collect_script_paths(&positional) {
+            Ok(paths) => eval_files(&paths, &options),
+            Err(e) => print_error(&e, options.display),
+        }
         // format_file(&path.unwrap());
     } else {
-        repl();
+        match ProjectConfig::load(Path::new(".")) {
+            Ok(Some(config)) => {
+                options.warnings = config.warnings;
+                eval_files(&config.entry, &options);
+            }
+            Ok(None) => repl(&options, None),
+            Err(e) => print_error(&e, options.display),
+        }
     }
 }
 
@@ -82,30 +482,239 @@ fn main() {
 //     fs::write(file_path, formatted_source).unwrap();
 // }
 
-fn eval_file(file_path: &str) {
+fn minify_file(file_path: &str, remove_unused: bool) {
     let source_code = fs::read_to_string(file_path)
         .expect("Should have been able to read the file");
-    eval(source_code.as_str(), false);
+
+    match minifier::minify(&source_code, remove_unused) {
+        Ok(minified) => println!("{minified}"),
+        Err(e) => eprintln!("\x1b[31mError occurred during parsing: {e}\x1b[0m"),
+    }
 }
 
-fn repl() {
-    let mut parser = Parser::default();
-    let interpreter = Interpreter::default();
+/// Reports syntax errors & symbol checker diagnostics of all given files without running them,
+/// exits with non-zero code if any of them has errors
+/// Warnings level is taken from `rustjs.toml` of the current directory when there is one
+fn lint(paths: &[&String], options: &EvalOptions) {
+    let current_directory = ".".to_string();
+    let paths = if paths.is_empty() { vec![&current_directory] } else { paths.to_vec() };
+
+    let result = ProjectConfig::load(Path::new(".")).and_then(|config| {
+        let warnings = config.map_or(WarningLevel::default(), |x| x.warnings);
+        let file_paths = collect_lint_paths(&paths)?;
+        let summary = linter::lint_files(&file_paths, options.display == DisplayMode::Colored, warnings != WarningLevel::Allow)?;
+        return Ok((summary, warnings));
+    });
+
+    let (summary, warnings) = result.unwrap_or_else(|e| {
+        print_error(&e, options.display);
+        std::process::exit(1);
+    });
+
+    println!("Linted {} file(s): {} error(s), {} warning(s)", summary.files, summary.errors, summary.warnings);
+
+    if summary.errors > 0 || (warnings == WarningLevel::Deny && summary.warnings > 0) {
+        std::process::exit(1);
+    }
+}
+
+/// Checks scripts the same way they are checked before running, but never runs them, so no interpreter is created.
+/// Scripts see top level declarations of the scripts before them, without paths entry of `rustjs.toml` is checked.
+/// Exits with non-zero code if any of the scripts couldn't be run
+fn check_files(paths: &[&String], options: &mut EvalOptions) {
+    let file_paths = if paths.is_empty() {
+        ProjectConfig::load(Path::new(".")).and_then(|config| match config {
+            Some(config) => {
+                options.warnings = config.warnings;
+                Ok(config.entry)
+            }
+            None => Err(format!("Expected paths of the files to check or {PROJECT_CONFIG_FILE_NAME} in the current directory")),
+        })
+    } else {
+        collect_script_paths(paths)
+    };
+
+    let file_paths = file_paths.unwrap_or_else(|e| {
+        print_error(&e, options.display);
+        std::process::exit(1);
+    });
+
+    let mut declared_globals = vec![];
+    let mut is_failed = false;
+
+    for (index, file_path) in file_paths.iter().enumerate() {
+        let source_code = fs::read_to_string(file_path)
+            .expect("Should have been able to read the file");
+
+        let has_following_scripts = index + 1 < file_paths.len();
+
+        match check_script(&source_code, &file_path.to_string_lossy(), &declared_globals, has_following_scripts, options, &mut ScriptReport::default()) {
+            Some(ast) => declared_globals.extend(get_top_level_declarations(&ast)),
+            None => is_failed = true,
+        }
+    }
+
+    if is_failed {
+        std::process::exit(1);
+    }
+}
 
-    let mut line = String::new();
+/// Checks the file like `check` does every time it changes, until the process is stopped.
+/// Statements before the changed part are not parsed again
+fn watch_file(file_path: &str, options: &EvalOptions) {
+    let mut parsed: Option<ParsedSource> = None;
+    let mut checked_source = None;
 
     loop {
-        print!("> ");
-        std::io::Write::flush(&mut std::io::stdout()).expect("flush failed!");
-        std::io::stdin().read_line(&mut line).unwrap();
-        let ast = parser
-            .parse(&line)
-            .expect(format!("Error occured during parsing").as_str());
-        line.clear();
+        let source = fs::read_to_string(file_path).unwrap_or_else(|e| {
+            print_error(&format!("Cannot read {file_path}: {e}"), options.display);
+            std::process::exit(1);
+        });
+
+        if checked_source.as_ref() != Some(&source) {
+            let result = match parsed.as_mut() {
+                Some(parsed) => parsed.apply_edit(&TextEdit::between(parsed.source(), &source)).map(|_| ()),
+                None => ParsedSource::parse(&source).map(|x| parsed = Some(x)),
+            };
 
-        match interpreter.interpret(&ast) {
-            Ok(result) => println!("{}", result),
-            Err(e) => println!("\x1b[31mError during evaluating node: {e}\x1b[0m"),
+            match (result, &parsed) {
+                (Ok(()), Some(parsed)) => {
+                    if check_ast(parsed.source(), parsed.ast(), file_path, &[], false, options, &mut ScriptReport::default()) {
+                        eprintln!("{file_path}: no problems found");
+                    }
+                }
+                (Err(e), _) => print_error(&format!("Error occurred during parsing: {e}"), options.display),
+                (Ok(()), None) => unreachable!("Source is parsed when there is no parse error"),
+            }
+
+            checked_source = Some(source);
         }
+
+        std::thread::sleep(WATCH_INTERVAL);
     }
 }
+
+/// Prints tokens of the file as JSON without running it, so other tools can reuse the scanner
+fn print_tokens(file_path: &str) {
+    let source_code = fs::read_to_string(file_path)
+        .expect("Should have been able to read the file");
+
+    println!("{}", scanner::tokens_to_json(&source_code, &scanner::tokenize(&source_code)));
+}
+
+/// Prints parse tree of the file, with `--dot` as a Graphviz graph
+fn print_ast(file_path: &str, is_dot: bool, options: &EvalOptions) {
+    let source_code = fs::read_to_string(file_path)
+        .expect("Should have been able to read the file");
+
+    match Parser::parse_code_to_ast(&source_code) {
+        Ok(ast) if is_dot => print!("{}", ast_graph::ast_to_dot(&ast)),
+        Ok(ast) => println!("{:#?}", ast),
+        Err(e) => {
+            print_error(&format!("Error occurred during parsing: {e}"), options.display);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints the file formatted with the given options, the file itself is not changed
+fn print_formatted(file_path: &str, format_options: &FormatOptions, options: &EvalOptions) {
+    let source_code = fs::read_to_string(file_path)
+        .expect("Should have been able to read the file");
+
+    match format_source(&source_code, format_options) {
+        Ok(formatted) => print!("{formatted}"),
+        Err(e) => {
+            print_error(&format!("Error occurred during formatting: {e}"), options.display);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs a script in the AST interpreter, pausing before its statements to read debugger commands from stdin
+fn debug_file(file_path: &str, options: &EvalOptions) {
+    let source_code = fs::read_to_string(file_path)
+        .expect("Should have been able to read the file");
+
+    let mut session = Session::new(options);
+    let input = Box::new(std::io::BufReader::new(std::io::stdin()));
+    let debugger = Debugger::new(file_path, &source_code, options.display, input, Box::new(std::io::stdout()));
+    session.interpreter.set_hooks(Box::new(debugger));
+
+    if !eval(&mut session, &source_code, file_path, false, options) {
+        std::process::exit(1);
+    }
+}
+
+/// Runs scripts one after another in a single global environment,
+/// declarations of a script are visible in the scripts which follow it
+fn eval_files(file_paths: &[PathBuf], options: &EvalOptions) {
+    let mut session = Session::new(options);
+
+    let mut is_failed = false;
+
+    for (index, file_path) in file_paths.iter().enumerate() {
+        let source_code = fs::read_to_string(file_path)
+            .expect("Should have been able to read the file");
+
+        let start = Instant::now();
+        // function called by `--call` uses top level declarations the same way a following script does
+        let has_following_scripts = index + 1 < file_paths.len() || options.call.is_some();
+        let is_evaluated = eval(&mut session, source_code.as_str(), &file_path.to_string_lossy(), has_following_scripts, options);
+
+        if options.is_timed {
+            eprintln!("{}: {:.3}ms", file_path.display(), start.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        if !is_evaluated {
+            is_failed = true;
+            break;
+        }
+    }
+
+    if let (false, Some(function_name)) = (is_failed, &options.call) {
+        let function = session.interpreter.get_global(function_name);
+
+        match session.interpreter.call(&function, JsValue::Undefined, vec![]) {
+            Ok(result) => session.last_result = result,
+            Err(e) => {
+                // function is declared by one of the scripts, so the interpreter knows the source of the error
+                report_runtime_error(&session.interpreter, &e, function_name, "", options.display);
+                is_failed = true;
+            }
+        }
+    }
+
+    if options.coverage {
+        fs::write(COVERAGE_REPORT_PATH, &session.coverage_report)
+            .expect("Should have been able to write coverage report");
+        eprintln!("Coverage report written to {COVERAGE_REPORT_PATH}");
+    }
+
+    if options.report == Some(ReportFormat::Json) {
+        eprintln!("{}", session.report.to_json());
+    }
+
+    if is_failed {
+        std::process::exit(1);
+    }
+
+    if options.result_output == ResultOutput::LastScript {
+        println!("{}", session.last_result.display(options.display).with_limits(options.display_limits));
+    }
+}
+
+/// Interactive session in the terminal, `--replay session.js` evaluates lines of the file before reading input
+fn repl(options: &EvalOptions, session_path: Option<&String>) {
+    let interpreter = Session::new(options).interpreter;
+    let mut repl = Repl::new(interpreter, options.display, options.display_limits).with_history();
+
+    if let Some(session_path) = session_path {
+        if let Err(e) = repl.replay(Path::new(session_path)) {
+            print_error(&e, options.display);
+            std::process::exit(1);
+        }
+    }
+
+    repl.run(&mut std::io::stdin().lock());
+}
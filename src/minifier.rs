@@ -0,0 +1,266 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use crate::diagnostic::{DiagnosticBag, DiagnosticKind};
+use crate::keywords::KEYWORDS;
+use crate::node::Printer;
+use crate::nodes::*;
+use crate::parser::Parser;
+use crate::symbol_checker::symbol_checker::SymbolChecker;
+use crate::visitor::Visitor;
+
+const NAME_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Parses source and prints it back without insignificant whitespace,
+/// local bindings are renamed to the shortest names which don't clash with anything visible.
+/// Top level bindings keep their names, since they can be referenced from outside.
+pub fn minify(source: &str, remove_unused: bool) -> Result<String, String> {
+    let ast = Parser::parse_code_to_ast(source)?;
+    let mut printer = Printer::compact().with_renamer(Renamer::new(&ast));
+
+    if remove_unused {
+        printer = printer.with_removed_declarations(find_unused_declarations(source, &ast));
+    }
+
+    return Ok(printer.print(&ast));
+}
+
+/// Returns spans of identifiers of declarations which are never used according to symbol checker.
+/// Symbol checker doesn't see references before a declaration, e.g. a call of a function declared below,
+/// so declarations whose name is referenced anywhere in the program are kept
+fn find_unused_declarations(source: &str, ast: &AstStatement) -> Vec<TextSpan> {
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    let mut symbol_checker = SymbolChecker::new(source, Rc::clone(&diagnostic_bag));
+    symbol_checker.check_symbols(ast);
+
+    let mut collector = ReferenceCollector { names: HashSet::new() };
+    collector.visit_statement(ast);
+
+    let unused_spans = diagnostic_bag.borrow().warnings.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::UnusedVariable(diagnostic) if !collector.names.contains(&diagnostic.variable_name) => {
+                Some(diagnostic.id_span.clone())
+            }
+            _ => None,
+        })
+        .collect();
+
+    return unused_spans;
+}
+
+/// Keeps track of renamed bindings for every scope while printing
+pub struct Renamer {
+    reserved_names: HashSet<String>,
+    scopes: Vec<RenamerScope>,
+}
+
+struct RenamerScope {
+    names: HashMap<String, String>,
+    next_name_index: usize,
+}
+
+impl Renamer {
+    /// All identifiers occurring in the program are reserved, so a generated name
+    /// can never shadow a global or a binding which is not renamed
+    pub fn new(ast: &AstStatement) -> Self {
+        let mut collector = IdentifierCollector { names: HashSet::new() };
+        collector.visit_statement(ast);
+
+        let mut reserved_names = collector.names;
+        reserved_names.extend(KEYWORDS.iter().map(|x| x.to_string()));
+
+        Self {
+            reserved_names,
+            scopes: vec![],
+        }
+    }
+
+    /// Names declared directly in the statement list, they are visible in the whole block
+    pub fn declared_names(statements: &[AstStatement]) -> Vec<String> {
+        statements.iter().filter_map(|statement| match statement {
+            AstStatement::VariableDeclaration(node) => Some(node.id.id.clone()),
+            AstStatement::FunctionDeclaration(node) => Some(node.function_signature.name.id.clone()),
+            AstStatement::ExpressionStatement(AstExpression::ClassDeclaration(node)) => Some(node.name.id.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn enter_scope(&mut self, declared_names: Vec<String>) {
+        let mut next_name_index = self.scopes.last().map_or(0, |x| x.next_name_index);
+        let mut names = HashMap::new();
+
+        for declared_name in declared_names {
+            if names.contains_key(&declared_name) {
+                continue;
+            }
+
+            let (generated_name, index) = self.generate_name(next_name_index);
+            next_name_index = index + 1;
+            names.insert(declared_name, generated_name);
+        }
+
+        self.scopes.push(RenamerScope { names, next_name_index });
+    }
+
+    pub fn exit_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        for scope in self.scopes.iter().rev() {
+            if let Some(renamed) = scope.names.get(name) {
+                return renamed;
+            }
+        }
+
+        return name;
+    }
+
+    fn generate_name(&self, mut index: usize) -> (String, usize) {
+        loop {
+            let name = name_from_index(index);
+
+            if !self.reserved_names.contains(&name) {
+                return (name, index);
+            }
+
+            index += 1;
+        }
+    }
+}
+
+fn name_from_index(index: usize) -> String {
+    let alphabet_len = NAME_ALPHABET.len();
+    let mut index = index;
+    let mut name = String::new();
+
+    loop {
+        name.push(NAME_ALPHABET[index % alphabet_len] as char);
+
+        if index < alphabet_len {
+            return name;
+        }
+
+        index = index / alphabet_len - 1;
+    }
+}
+
+struct IdentifierCollector {
+    names: HashSet<String>,
+}
+
+impl Visitor for IdentifierCollector {
+    fn visit_variable_declaration(&mut self, stmt: &VariableDeclarationNode) {
+        self.visit_identifier_node(&stmt.id);
+
+        if let Some(value) = &stmt.value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_identifier_node(&mut self, stmt: &IdentifierNode) {
+        self.names.insert(stmt.id.clone());
+    }
+}
+
+/// Names of all identifiers except of the declared ones, e.g. `a` of `let a = b;` is skipped, but `b` is collected
+struct ReferenceCollector {
+    names: HashSet<String>,
+}
+
+impl Visitor for ReferenceCollector {
+    fn visit_class_declaration(&mut self, stmt: &ClassDeclarationNode) {
+        if let Some(parent) = &stmt.parent {
+            self.visit_identifier_node(parent);
+        }
+
+        stmt.fields.iter().for_each(|x| self.visit_class_field(x));
+        stmt.methods.iter().for_each(|x| self.visit_class_method(x));
+    }
+
+    fn visit_function_signature(&mut self, stmt: &FunctionSignature) {
+        stmt.arguments.iter().for_each(|x| self.visit_function_argument(x));
+        self.visit_statement(&stmt.body);
+    }
+
+    fn visit_function_argument(&mut self, stmt: &FunctionArgument) {
+        if let Some(value) = &stmt.default_value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_variable_declaration(&mut self, stmt: &VariableDeclarationNode) {
+        if let Some(value) = &stmt.value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_identifier_node(&mut self, stmt: &IdentifierNode) {
+        self.names.insert(stmt.id.clone());
+    }
+}
+
+#[test]
+fn minify_renames_local_bindings() {
+    let code = "
+        let counter = 0;
+        function increment(step) {
+            const next = counter + step;
+            return next;
+        }
+        { let value = 5; counter = increment(value); }
+    ";
+
+    assert_eq!(
+        minify(code, false).unwrap(),
+        "let counter=0;function increment(a){const b=counter+a;return b;}{let a=5;counter=increment(a);}"
+    );
+}
+
+#[test]
+fn minify_keeps_required_parentheses() {
+    let code = "let a = (1 + 2) * 3; let b = 1 - (2 - 3); let c = { key: a > b ? a : b };";
+
+    assert_eq!(
+        minify(code, false).unwrap(),
+        "let a=(1+2)*3;let b=1-(2-3);let c={key:a>b?a:b};"
+    );
+}
+
+#[test]
+fn minify_removes_unused_declarations() {
+    let code = "let used = 1; let unused = 2; function f() {} console.log(used);";
+    assert_eq!(minify(code, true).unwrap(), "let used=1;console.log(used);");
+}
+
+#[test]
+fn minify_keeps_functions_called_before_their_declaration() {
+    let code = "function f() { return a(); } function a() { return 1; } let unused = 2; f();";
+    let minified = minify(code, true).unwrap();
+    assert_eq!(minified, "function f(){return a();}function a(){return 1;}f();");
+
+    let ast = Parser::parse_code_to_ast(&minified).unwrap();
+    assert_eq!(Interpreter::default().interpret(&ast), Ok(JsValue::Number(1.0)));
+}
+
+#[test]
+fn minify_keeps_array_holes() {
+    assert_eq!(minify("let a = [1, , [2, 3], ,];", false).unwrap(), "let a=[1,,[2,3],,];");
+}
+
+#[test]
+fn minify_keeps_destructuring_assignment() {
+    let code = "let a = 1; let b = 2; [a, b] = [b, a]; ({ a, c: b } = { a: 3, c: 4 });";
+    assert_eq!(minify(code, false).unwrap(), "let a=1;let b=2;[a,b]=[b,a];({a:a,c:b}={a:3,c:4});");
+}
+
+#[test]
+fn minify_keeps_exponentiation_grouping() {
+    assert_eq!(minify("let a = (2 ** 3) ** 2 + 2 ** 3 ** 2 + (-2) ** 2;", false).unwrap(), "let a=(2**3)**2+2**3**2+(-2)**2;");
+}
+
+#[test]
+fn minify_prints_switch_statement() {
+    let code = "function f(x) { switch (x) { case 1: let y = 2; return y; default: break; } } f(1);";
+    assert_eq!(minify(code, false).unwrap(), "function f(a){switch(a){case 1:let b=2;return b;default:break;}}f(1);");
+}
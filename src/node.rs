@@ -1,17 +1,23 @@
 use crate::scanner::TextSpan;
-use std::fmt::Debug;
-use crate::keywords::{CONST_KEYWORD, FALSE_KEYWORD, LET_KEYWORD, TRUE_KEYWORD};
+use crate::keywords::{CONST_KEYWORD, FALSE_KEYWORD, LET_KEYWORD, TRUE_KEYWORD, TYPEOF_KEYWORD, VOID_KEYWORD};
+use crate::minifier::Renamer;
 use crate::nodes::*;
+use crate::value::number_to_string;
 use crate::visitor::Visitor;
 
 pub trait GetSpan {
     fn get_span(&self) -> TextSpan;
 }
 
+/// Generates source code from ast.
+/// In compact mode all insignificant whitespace is omitted, which is used by the minifier.
 pub struct Printer {
     ident: u32,
     level: u32,
     pub(crate) result: String,
+    compact: bool,
+    renamer: Option<Renamer>,
+    removed_declarations: Vec<TextSpan>,
 }
 
 impl Printer {
@@ -20,112 +26,667 @@ impl Printer {
             ident,
             level: 0,
             result: String::new(),
+            compact: false,
+            renamer: None,
+            removed_declarations: vec![],
         }
     }
 
-    // fn spaces(&self) -> &str {
-    //     " ".repeat((self.ident * self.level) as usize).as_str()
-    // }
+    /// Creates printer which emits code without insignificant whitespace & newlines
+    pub fn compact() -> Self {
+        Self {
+            compact: true,
+            ..Self::new(0)
+        }
+    }
+
+    pub fn with_renamer(mut self, renamer: Renamer) -> Self {
+        self.renamer = Some(renamer);
+        self
+    }
+
+    /// Declarations with identifiers located at given spans are omitted from the output,
+    /// if their initializers don't have side effects
+    pub fn with_removed_declarations(mut self, spans: Vec<TextSpan>) -> Self {
+        self.removed_declarations = spans;
+        self
+    }
+
+    pub fn print(mut self, ast: &AstStatement) -> String {
+        self.visit_statement(ast);
+        self.result
+    }
+
+    pub fn print_expression(mut self, expression: &AstExpression) -> String {
+        self.visit_expression(expression);
+        self.result
+    }
+
+    /// Appends text, in compact mode a space is inserted only where tokens would merge otherwise
+    fn write(&mut self, text: &str) {
+        let last_char = self.result.chars().last();
+        let first_char = text.chars().next();
+
+        if let (Some(last_char), Some(first_char)) = (last_char, first_char) {
+            let is_word_char = |x: char| x.is_alphanumeric() || x == '_' || x == '$';
+            let would_merge = (is_word_char(last_char) && is_word_char(first_char))
+                || (last_char == '+' && first_char == '+')
+                || (last_char == '-' && first_char == '-');
+
+            if would_merge {
+                self.result.push(' ');
+            }
+        }
+
+        self.result += text;
+    }
+
+    fn space(&mut self) {
+        if !self.compact {
+            self.result.push(' ');
+        }
+    }
+
+    fn newline(&mut self) {
+        if !self.compact {
+            self.result.push('\n');
+        }
+    }
+
+    fn write_indent(&mut self) {
+        if !self.compact {
+            self.result += " ".repeat((self.ident * self.level) as usize).as_str();
+        }
+    }
+
+    /// Writes binary-like operator, surrounded by spaces in non compact mode
+    fn write_operator(&mut self, operator: &str) {
+        self.space();
+        self.write(operator);
+        self.space();
+    }
+
+    fn write_comma_separated<T>(&mut self, items: &[T], print_item: impl Fn(&mut Self, &T)) {
+        for (i, item) in items.iter().enumerate() {
+            if i != 0 {
+                self.write(",");
+                self.space();
+            }
+
+            print_item(self, item);
+        }
+    }
+
+    fn print_statement_list(&mut self, statements: &[AstStatement]) {
+        for statement in statements {
+            if self.is_removed_declaration(statement) {
+                continue;
+            }
+
+            self.write_indent();
+            self.visit_statement(statement);
+            self.newline();
+        }
+    }
+
+    fn is_removed_declaration(&self, statement: &AstStatement) -> bool {
+        match statement {
+            AstStatement::VariableDeclaration(node) => {
+                self.removed_declarations.contains(&node.id.token.span)
+                    && node.value.as_ref().map_or(true, |x| is_side_effect_free(x))
+            }
+            AstStatement::FunctionDeclaration(node) => {
+                self.removed_declarations.contains(&node.function_signature.name.token.span)
+            }
+            _ => false,
+        }
+    }
+
+    fn print_expression_with_precedence(&mut self, expression: &AstExpression, min_precedence: u8) {
+        if expression_precedence(expression) < min_precedence {
+            self.write("(");
+            self.visit_expression(expression);
+            self.write(")");
+        } else {
+            self.visit_expression(expression);
+        }
+    }
+
+    fn print_function(&mut self, name: Option<&IdentifierNode>, arguments: &[FunctionArgument], body: &AstStatement) {
+        if let Some(name) = name {
+            self.visit_identifier_node(name);
+        }
+
+        self.enter_scope(arguments.iter().map(|x| x.name.id.clone()).collect());
+        self.write("(");
+        self.write_comma_separated(arguments, |printer, argument| printer.visit_function_argument(argument));
+        self.write(")");
+        self.space();
+        self.visit_statement(body);
+        self.exit_scope();
+    }
+
+    fn enter_scope(&mut self, declared_names: Vec<String>) {
+        if let Some(renamer) = self.renamer.as_mut() {
+            renamer.enter_scope(declared_names);
+        }
+    }
+
+    fn exit_scope(&mut self) {
+        if let Some(renamer) = self.renamer.as_mut() {
+            renamer.exit_scope();
+        }
+    }
+
+    /// Prints object key or non-computed member property, which are never renamed
+    fn print_property_key(&mut self, key: &AstExpression) {
+        match key {
+            AstExpression::Identifier(node) => self.write(&node.id.clone()),
+            _ => self.visit_expression(key),
+        }
+    }
+}
+
+/// Operator precedence of expression according to js grammar, higher binds tighter
+fn expression_precedence(expression: &AstExpression) -> u8 {
+    match expression {
+        AstExpression::AssignmentExpression(_) => 2,
+        AstExpression::ConditionalExpression(_) => 3,
+        AstExpression::BinaryExpression(node) => binary_operator_precedence(&node.operator),
+        AstExpression::UnaryExpression(_) => 15,
+        AstExpression::CallExpression(_)
+        | AstExpression::MemberExpression(_)
+        | AstExpression::NewExpression(_) => 18,
+        _ => 20,
+    }
+}
+
+fn binary_operator_precedence(operator: &BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::LogicalOr => 4,
+        BinaryOperator::LogicalAnd => 5,
+        BinaryOperator::Equality | BinaryOperator::Inequality => 9,
+        BinaryOperator::MoreThan
+        | BinaryOperator::MoreThanOrEqual
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqual
+        | BinaryOperator::InstanceOf
+        | BinaryOperator::In => 10,
+        BinaryOperator::Add | BinaryOperator::Sub => 12,
+        BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Rem => 13,
+        BinaryOperator::MulMul => 14,
+    }
+}
+
+pub fn binary_operator_to_str(operator: &BinaryOperator) -> &'static str {
+    match operator {
+        BinaryOperator::Add => "+",
+        BinaryOperator::Sub => "-",
+        BinaryOperator::Div => "/",
+        BinaryOperator::Mul => "*",
+        BinaryOperator::Rem => "%",
+        BinaryOperator::LogicalOr => "||",
+        BinaryOperator::LogicalAnd => "&&",
+        BinaryOperator::MoreThan => ">",
+        BinaryOperator::MoreThanOrEqual => ">=",
+        BinaryOperator::LessThan => "<",
+        BinaryOperator::LessThanOrEqual => "<=",
+        BinaryOperator::Equality => "==",
+        BinaryOperator::Inequality => "!=",
+        BinaryOperator::MulMul => "**",
+        BinaryOperator::InstanceOf => "instanceof",
+        BinaryOperator::In => "in",
+    }
+}
+
+pub fn unary_operator_to_str(operator: &UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Minus => "-",
+        UnaryOperator::Plus => "+",
+        UnaryOperator::LogicalNot => "!",
+        UnaryOperator::Typeof => TYPEOF_KEYWORD,
+        UnaryOperator::Void => VOID_KEYWORD,
+    }
+}
+
+pub fn assignment_operator_to_str(operator: &AssignmentOperator) -> &'static str {
+    match operator {
+        AssignmentOperator::AddEqual => "+=",
+        AssignmentOperator::SubEqual => "-=",
+        AssignmentOperator::DivEqual => "/=",
+        AssignmentOperator::MulEqual => "*=",
+        AssignmentOperator::RemEqual => "%=",
+        AssignmentOperator::ExponentiationEqual => "**=",
+        AssignmentOperator::Equal => "=",
+    }
+}
+
+/// Expression statements starting with `{` or `function` have to be wrapped in parentheses,
+/// otherwise they would be parsed as block or function declaration
+fn starts_with_ambiguous_token(expression: &AstExpression) -> bool {
+    match expression {
+        AstExpression::ObjectExpression(_) | AstExpression::FunctionExpression(_) => true,
+        AstExpression::BinaryExpression(node) => starts_with_ambiguous_token(&node.left),
+        AstExpression::AssignmentExpression(node) => starts_with_ambiguous_token(&node.left),
+        AstExpression::ConditionalExpression(node) => starts_with_ambiguous_token(&node.test),
+        AstExpression::CallExpression(node) => starts_with_ambiguous_token(&node.callee),
+        AstExpression::MemberExpression(node) => starts_with_ambiguous_token(&node.object),
+        _ => false,
+    }
+}
+
+/// Whether evaluation of expression can be skipped without observable difference
+pub fn is_side_effect_free(expression: &AstExpression) -> bool {
+    match expression {
+        AstExpression::StringLiteral(_)
+        | AstExpression::NumberLiteral(_)
+        | AstExpression::BooleanLiteral(_)
+        | AstExpression::NullLiteral(_)
+        | AstExpression::UndefinedLiteral(_)
+        | AstExpression::ThisExpression(_)
+        | AstExpression::Identifier(_)
+        | AstExpression::FunctionExpression(_) => true,
+        AstExpression::ArrayExpression(node) => node.items.iter().flatten().all(is_side_effect_free),
+        AstExpression::UnaryExpression(node) => is_side_effect_free(&node.argument),
+        AstExpression::ObjectExpression(node) => node.properties.iter().all(|x| {
+            is_side_effect_free(&x.key) && is_side_effect_free(&x.value)
+        }),
+        _ => false,
+    }
 }
 
 impl Visitor for Printer {
     fn visit_program_statement(&mut self, stmt: &ProgramNode) {
-        stmt.statements.iter().for_each(|stmt| {
-            let spaces = " ".repeat((self.ident * self.level) as usize);
-            self.result += spaces.as_str();
-            self.visit_statement(stmt)
-        });
+        self.print_statement_list(&stmt.statements);
     }
 
     fn visit_block_statement(&mut self, stmt: &BlockStatementNode) {
-        self.result += "{\n";
+        if stmt.statements.is_empty() {
+            self.write("{}");
+            return;
+        }
+
+        self.enter_scope(Renamer::declared_names(&stmt.statements));
+        self.write("{");
+        self.newline();
         self.level += 1;
-        stmt.statements.iter().for_each(|stmt| {
-            let spaces = " ".repeat((self.ident * self.level) as usize);
-            self.result += spaces.as_str();
-            self.visit_statement(stmt)
-        });
-        self.result += "}";
+        self.print_statement_list(&stmt.statements);
+        self.level -= 1;
+        self.write_indent();
+        self.write("}");
+        self.exit_scope();
     }
 
     fn visit_variable_declaration(&mut self, stmt: &VariableDeclarationNode) {
-        self.result += match stmt.kind {
+        self.write(match stmt.kind {
             VariableDeclarationKind::Let => LET_KEYWORD,
             VariableDeclarationKind::Const => CONST_KEYWORD
-        };
-
-        self.result += " ";
+        });
 
+        self.space();
         self.visit_identifier_node(&stmt.id);
 
-        self.result += " = ";
+        if let Some(value) = &stmt.value {
+            self.write_operator("=");
+            self.print_expression_with_precedence(value, 2);
+        }
+
+        self.write(";");
+    }
+
+    fn visit_while_statement(&mut self, node: &WhileStatementNode) {
+        self.write("while");
+        self.space();
+        self.write("(");
+        self.visit_expression(&node.condition);
+        self.write(")");
+        self.space();
+        self.visit_statement(&node.body);
+    }
+
+    fn visit_return_statement(&mut self, node: &ReturnStatementNode) {
+        self.write("return");
+        self.space();
+        self.visit_expression(&node.expression);
+        self.write(";");
+    }
+
+    fn visit_throw_statement(&mut self, node: &ThrowStatementNode) {
+        self.write("throw");
+        self.space();
+        self.visit_expression(&node.expression);
+        self.write(";");
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatementNode) {
+        let declared_names = stmt.init.as_ref()
+            .map_or(vec![], |init| Renamer::declared_names(std::slice::from_ref(init.as_ref())));
+        self.enter_scope(declared_names);
+
+        self.write("for");
+        self.space();
+        self.write("(");
+
+        match &stmt.init {
+            Some(init) => self.visit_statement(init),
+            None => self.write(";"),
+        }
+
+        if let Some(test) = &stmt.test {
+            self.space();
+            self.visit_expression(test);
+        }
+
+        self.write(";");
 
-        if stmt.value.is_some() {
-            self.visit_expression(stmt.value.as_ref().unwrap());
+        if let Some(update) = &stmt.update {
+            self.space();
+            self.visit_expression(update);
         }
 
-        self.result += ";\n";
+        self.write(")");
+        self.space();
+        self.visit_statement(&stmt.body);
+        self.exit_scope();
     }
 
-    fn visit_identifier_node(&mut self, stmt: &IdentifierNode) {
-        self.result += stmt.id.as_str();
-        // println!("visit_identifier_declaration {}", stmt.id);
+    fn visit_break_statement(&mut self, _: &Token) {
+        self.write("break;");
     }
 
-    fn visit_string_literal(&mut self, stmt: &StringLiteralNode) {
-        self.result += stmt.value.as_str();
-        // println!("visit_string_literal: {}", stmt.value);
+    fn visit_continue_statement(&mut self, _: &Token) {
+        self.write("continue;");
     }
 
-    fn visit_number_literal(&mut self, stmt: &NumberLiteralNode) {
-        self.result += stmt.value.to_string().as_str();
-        // println!("visit_number_literal: {}", stmt.value);
+    fn visit_switch_statement(&mut self, node: &SwitchStatementNode) {
+        // all cases share one scope
+        self.enter_scope(node.cases.iter().flat_map(|x| Renamer::declared_names(&x.consequent)).collect());
+
+        self.write("switch");
+        self.space();
+        self.write("(");
+        self.visit_expression(&node.discriminant);
+        self.write(")");
+        self.space();
+        self.write("{");
+        self.newline();
+        self.level += 1;
+
+        for case in &node.cases {
+            self.write_indent();
+
+            match &case.test {
+                Some(test) => {
+                    self.write("case ");
+                    self.visit_expression(test);
+                }
+                None => self.write("default"),
+            }
+
+            self.write(":");
+            self.newline();
+            self.level += 1;
+            self.print_statement_list(&case.consequent);
+            self.level -= 1;
+        }
+
+        self.level -= 1;
+        self.write_indent();
+        self.write("}");
+        self.exit_scope();
     }
 
-    fn visit_expression_statement(&mut self, stmt: &AstExpression) {
-        println!("visit_expression_statement {stmt:?}");
-        self.visit_expression(stmt);
-        self.result += ";\n";
+    fn visit_empty_statement(&mut self, _: &Token) {
+        self.write(";");
+    }
+
+    fn visit_class_declaration(&mut self, stmt: &ClassDeclarationNode) {
+        self.write("class");
+        self.space();
+        self.visit_identifier_node(&stmt.name);
+
+        if let Some(parent) = &stmt.parent {
+            self.space();
+            self.write("extends");
+            self.space();
+            self.visit_identifier_node(parent);
+        }
+
+        self.space();
+        self.write("{");
+        self.newline();
+        self.level += 1;
+
+        for field in stmt.fields.iter() {
+            self.write_indent();
+            self.visit_class_field(field);
+            self.newline();
+        }
+
+        for method in &stmt.methods {
+            self.write_indent();
+            self.visit_class_method(method);
+            self.newline();
+        }
+
+        self.level -= 1;
+        self.write_indent();
+        self.write("}");
+    }
+
+    fn visit_class_method(&mut self, stmt: &ClassMethodNode) {
+        let signature = &stmt.function_signature;
+        self.write(&signature.name.id.clone());
+        self.print_function(None, &signature.arguments, &signature.body);
+    }
+
+    fn visit_class_field(&mut self, stmt: &ClassFieldNode) {
+        self.write(&stmt.name.id);
+
+        if let Some(value) = &stmt.value {
+            self.write_operator("=");
+            self.print_expression_with_precedence(value, 2);
+        }
+
+        self.write(";");
+    }
+
+    fn visit_function_declaration(&mut self, stmt: &FunctionDeclarationNode) {
+        let signature = &stmt.function_signature;
+        self.write("function");
+        self.space();
+        self.print_function(Some(&signature.name), &signature.arguments, &signature.body);
+    }
+
+    fn visit_function_argument(&mut self, stmt: &FunctionArgument) {
+        if stmt.is_rest {
+            self.write("...");
+        }
+
+        self.visit_identifier_node(&stmt.name);
+
+        if let Some(value) = &stmt.default_value {
+            self.write_operator("=");
+            self.print_expression_with_precedence(value, 2);
+        }
     }
 
     fn visit_if_statement(&mut self, stmt: &IfStatementNode) {
-        self.result += "if (";
+        self.write("if");
+        self.space();
+        self.write("(");
         self.visit_expression(&stmt.condition);
-        self.result += ") ";
+        self.write(")");
+        self.space();
 
         self.visit_statement(&stmt.then_branch);
 
         if let Some(else_branch) = &stmt.else_branch {
-            self.result += " else ";
+            self.space();
+            self.write("else");
+            self.space();
             self.visit_statement(else_branch);
         }
     }
 
-    fn visit_boolean_literal(&mut self, stmt: &BooleanLiteralNode) {
-        self.result += if stmt.value { TRUE_KEYWORD } else { FALSE_KEYWORD };
+    fn visit_expression_statement(&mut self, stmt: &AstExpression) {
+        if starts_with_ambiguous_token(stmt) {
+            self.write("(");
+            self.visit_expression(stmt);
+            self.write(")");
+        } else {
+            self.visit_expression(stmt);
+        }
+
+        self.write(";");
+    }
+
+    fn visit_string_literal(&mut self, stmt: &StringLiteralNode) {
+        let quote = if stmt.value.contains('"') { "'" } else { "\"" };
+        self.write(&format!("{quote}{}{quote}", stmt.value));
+    }
+
+    fn visit_number_literal(&mut self, stmt: &NumberLiteralNode) {
+        self.write(&number_to_string(stmt.value));
+    }
+
+    fn visit_conditional_expression(&mut self, node: &ConditionalExpressionNode) {
+        self.print_expression_with_precedence(&node.test, 4);
+        self.write_operator("?");
+        self.print_expression_with_precedence(&node.consequent, 2);
+        self.write_operator(":");
+        self.print_expression_with_precedence(&node.alternative, 2);
+    }
+
+    fn visit_array_expression(&mut self, node: &ArrayExpressionNode) {
+        self.write("[");
+        self.write_comma_separated(&node.items, |printer, item| {
+            if let Some(item) = item {
+                printer.print_expression_with_precedence(item, 2);
+            }
+        });
+
+        // trailing hole needs an extra comma, `[1, ,]` has two items
+        if let Some(None) = node.items.last() {
+            self.write(",");
+        }
+
+        self.write("]");
+    }
+
+    fn visit_unary_expression(&mut self, node: &UnaryExpressionNode) {
+        self.write(unary_operator_to_str(&node.operator));
+        self.print_expression_with_precedence(&node.argument, 15);
+    }
+
+    fn visit_spread_element(&mut self, node: &SpreadElementNode) {
+        self.write("...");
+        self.print_expression_with_precedence(&node.argument, 2);
+    }
+
+    fn visit_function_expression(&mut self, node: &FunctionExpressionNode) {
+        self.write("function");
+        self.print_function(None, &node.arguments, &node.body);
+    }
+
+    fn visit_undefined_literal(&mut self) {
+        self.write("undefined");
+    }
+
+    fn visit_null_literal(&mut self) {
+        self.write("null");
+    }
+
+    fn visit_this_expression(&mut self, _: &ThisExpressionNode) {
+        self.write("this");
+    }
+
+    fn visit_object_expression(&mut self, node: &ObjectExpressionNode) {
+        if node.properties.is_empty() {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.space();
+        self.write_comma_separated(&node.properties, |printer, property| printer.visit_object_property(property));
+        self.space();
+        self.write("}");
+    }
+
+    fn visit_object_property(&mut self, node: &ObjectPropertyNode) {
+        if node.computed {
+            self.write("[");
+            self.visit_expression(&node.key);
+            self.write("]");
+        } else {
+            self.print_property_key(&node.key);
+        }
+
+        self.write(":");
+        self.space();
+        self.print_expression_with_precedence(&node.value, 2);
+    }
+
+    fn visit_member_expression(&mut self, stmt: &MemberExpressionNode) {
+        self.print_expression_with_precedence(&stmt.object, 18);
+
+        if stmt.computed {
+            self.write("[");
+            self.visit_expression(&stmt.property);
+            self.write("]");
+        } else {
+            self.write(".");
+            self.print_property_key(&stmt.property);
+        }
+    }
+
+    fn visit_new_expression(&mut self, stmt: &NewExpressionNode) {
+        self.write("new");
+        self.space();
+        self.print_expression_with_precedence(&stmt.callee, 18);
+        self.write("(");
+        self.write_comma_separated(&stmt.arguments, |printer, argument| printer.print_expression_with_precedence(argument, 2));
+        self.write(")");
+    }
+
+    fn visit_call_expression(&mut self, stmt: &CallExpressionNode) {
+        self.print_expression_with_precedence(&stmt.callee, 18);
+        self.write("(");
+        self.write_comma_separated(&stmt.params, |printer, param| printer.print_expression_with_precedence(param, 2));
+        self.write(")");
+    }
+
+    fn visit_assignment_expression(&mut self, stmt: &AssignmentExpressionNode) {
+        self.print_expression_with_precedence(&stmt.left, 18);
+        self.write_operator(assignment_operator_to_str(&stmt.operator));
+        self.print_expression_with_precedence(&stmt.right, 2);
     }
 
     fn visit_binary_expression(&mut self, stmt: &BinaryExpressionNode) {
-        println!("visit_binary_expression");
-        self.visit_expression(stmt.left.as_ref());
-        self.result += " ";
-        self.result += match stmt.operator {
-            BinaryOperator::Add => "+",
-            BinaryOperator::Sub => "-",
-            BinaryOperator::Div => "/",
-            BinaryOperator::Mul => "*",
-            BinaryOperator::LogicalOr => "||",
-            BinaryOperator::LogicalAnd => "&&",
-            BinaryOperator::MoreThan => ">",
-            BinaryOperator::MoreThanOrEqual => ">=",
-            BinaryOperator::LessThan => "<",
-            BinaryOperator::LessThanOrEqual => "<=",
-            BinaryOperator::Equality => "==",
-            BinaryOperator::Inequality => "!=",
-            BinaryOperator::MulMul => "**",
+        let precedence = binary_operator_precedence(&stmt.operator);
+
+        // `**` is right associative & its base can't be a unary expression, e.g. `(-2) ** 2`
+        let (left_precedence, right_precedence) = match stmt.operator {
+            BinaryOperator::MulMul => (16, precedence),
+            _ => (precedence, precedence + 1),
+        };
+
+        self.print_expression_with_precedence(stmt.left.as_ref(), left_precedence);
+        self.write_operator(binary_operator_to_str(&stmt.operator));
+        self.print_expression_with_precedence(stmt.right.as_ref(), right_precedence);
+    }
+
+    fn visit_boolean_literal(&mut self, stmt: &BooleanLiteralNode) {
+        self.write(if stmt.value { TRUE_KEYWORD } else { FALSE_KEYWORD });
+    }
+
+    fn visit_identifier_node(&mut self, stmt: &IdentifierNode) {
+        let name = match &self.renamer {
+            Some(renamer) => renamer.resolve(&stmt.id).to_string(),
+            None => stmt.id.clone(),
         };
-        self.result += " ";
 
-        self.visit_expression(stmt.right.as_ref());
+        self.write(&name);
     }
-}
\ No newline at end of file
+}
@@ -1,16 +1,39 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::AstExpression;
+use crate::nodes::{AstExpression, LiteralTemplate, TextSpan};
 use crate::value::JsValue;
 use crate::value::object::JsObject;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ArrayExpressionNode {
-    pub items: Vec<AstExpression>,
+    /// `None` is a hole, e.g. the second item of `[1, , 3]`
+    pub items: Vec<Option<AstExpression>>,
+    pub span: TextSpan,
+    pub template: LiteralTemplate,
+}
+
+impl ArrayExpressionNode {
+    pub fn new(items: Vec<Option<AstExpression>>, span: TextSpan) -> Self {
+        let template = LiteralTemplate::of_array(&items);
+        Self { items, span, template }
+    }
 }
 
 impl Execute for ArrayExpressionNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        let array_items: Vec<JsValue> = self.items.iter().map(|x| x.execute(interpreter).unwrap()).collect();
-        return Ok(JsObject::array(array_items).to_js_value());
+        if let Some(value) = self.template.instantiate() {
+            return Ok(value);
+        }
+
+        let mut array_items: Vec<Option<JsValue>> = vec![];
+
+        for item in &self.items {
+            match item {
+                Some(AstExpression::SpreadElement(node)) => array_items.extend(interpreter.iterate_value(&node.argument.execute(interpreter)?)?.into_iter().map(Some)),
+                Some(item) => array_items.push(Some(item.execute(interpreter)?)),
+                None => array_items.push(None),
+            }
+        }
+
+        return Ok(JsObject::sparse_array(array_items).to_js_value());
     }
 }
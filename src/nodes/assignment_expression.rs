@@ -1,8 +1,10 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::node::GetSpan;
+use crate::node::{assignment_operator_to_str, GetSpan};
 use crate::nodes::AstExpression;
+use crate::nodes::member_expression::property_access_error;
 use crate::scanner::{Span, TextSpan, TokenKind};
 use crate::value::JsValue;
+use crate::value::object::{JsObject, PrimitiveHint};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct AssignmentExpressionNode {
@@ -15,49 +17,112 @@ impl Execute for AssignmentExpressionNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
         let right_hand_value = self.right.execute(interpreter)?;
 
-        match &self.left.as_ref() {
-            AstExpression::Identifier(id_node) => {
-                let original_value = interpreter
-                    .environment
-                    .borrow()
-                    .borrow()
-                    .get_variable_value(&id_node.id);
-
-                let new_variable_value = match self.operator {
-                    AssignmentOperator::AddEqual => &original_value + &right_hand_value,
-                    AssignmentOperator::SubEqual => &original_value - &right_hand_value,
-                    AssignmentOperator::DivEqual => &original_value / &right_hand_value,
-                    AssignmentOperator::MulEqual => &original_value * &right_hand_value,
-                    AssignmentOperator::ExponentiationEqual => original_value.exponentiation(&right_hand_value),
-                    AssignmentOperator::Equal => Ok(right_hand_value),
-                }.unwrap();
-
-                interpreter.environment.borrow()
-                    .borrow_mut()
-                    .assign_variable(id_node.id.clone(), new_variable_value.clone())?;
-                return Ok(new_variable_value);
+        // destructuring assignment evaluates to the whole right hand side, e.g. `[a, b] = [b, a]`
+        if matches!(self.left.as_ref(), AstExpression::ArrayExpression(_) | AstExpression::ObjectExpression(_)) {
+            assign_pattern(interpreter, &self.left, right_hand_value.clone())?;
+            return Ok(right_hand_value);
+        }
+
+        return assign_target(interpreter, &self.left, &self.operator, right_hand_value);
+    }
+}
+
+/// Stores value into identifier or property, compound operators combine it with the current value first
+fn assign_target(interpreter: &Interpreter, target: &AstExpression, operator: &AssignmentOperator, right_hand_value: JsValue) -> Result<JsValue, String> {
+    match target {
+        AstExpression::Identifier(id_node) => {
+            let original_value = interpreter
+                .environment
+                .borrow()
+                .borrow()
+                .get_variable_value(&id_node.id);
+
+            let new_variable_value = operator.apply(interpreter, &original_value, right_hand_value)?;
+            interpreter.check_variable_writable(&id_node.id)?;
+
+            interpreter.environment.borrow()
+                .borrow_mut()
+                .assign_variable(id_node.id.clone(), new_variable_value.clone())?;
+            return Ok(new_variable_value);
+        }
+        AstExpression::MemberExpression(node) => {
+            let object = node.object.execute(interpreter)?;
+            let key = interpreter.eval_member_expression_key(&node.property, node.computed)?;
+
+            if node.computed {
+                interpreter.check_array_index(&object, &key)?;
             }
-            AstExpression::MemberExpression(node) => {
-                let object = node.object.execute(interpreter)?;
-                let key = interpreter.eval_member_expression_key(&node.property, node.computed)?;
-
-                match object {
-                    JsValue::Object(object_value) => {
-                        let object = object_value;
-
-                        object
-                            .borrow_mut()
-                            .add_property(key.as_str(), right_hand_value);
-
-                        Ok(JsValue::Object(object))
-                    },
-                    JsValue::Undefined => Err(format!("Uncaught TypeError: Cannot read properties of undefined (reading '{}')", key).to_string()),
-                    _ => Err("Cannot assign: left hand side expression is not an object".to_string())
+
+            match object {
+                JsValue::Object(object) => {
+                    // plain assignment doesn't read the property, e.g. `get` trap of a proxy is not called
+                    let new_value = match operator {
+                        AssignmentOperator::Equal => right_hand_value,
+                        operator => {
+                            let original_value = interpreter.get_object_property(&object, &key)?;
+                            operator.apply(interpreter, &original_value, right_hand_value)?
+                        }
+                    };
+
+                    interpreter.set_object_property(&object, &key, new_value.clone())?;
+                    Ok(new_value)
+                },
+                JsValue::Undefined | JsValue::Null => Err(property_access_error(&object, &key, true)),
+                // assigning to a property of a primitive has no effect
+                _ => Ok(right_hand_value),
+            }
+        }
+        _ => Err("SyntaxError: Invalid left-hand side in assignment".to_string()),
+    }
+}
+
+/// Destructures value into the targets of array or object pattern, nested patterns are handled recursively.
+/// Target with a default value, e.g. `[a = 1] = []`, gets the default when value is undefined
+fn assign_pattern(interpreter: &Interpreter, target: &AstExpression, value: JsValue) -> Result<(), String> {
+    match target {
+        AstExpression::ArrayExpression(node) => {
+            let items = interpreter.iterate_value(&value)?;
+
+            for (index, item) in node.items.iter().enumerate() {
+                match item {
+                    Some(AstExpression::SpreadElement(rest)) => {
+                        let rest_items = items.iter().skip(index).cloned().collect();
+                        assign_pattern(interpreter, &rest.argument, JsObject::array(rest_items).to_js_value())?;
+                    }
+                    Some(item) => assign_pattern(interpreter, item, items.get(index).cloned().unwrap_or(JsValue::Undefined))?,
+                    None => {}
                 }
             }
-            _ => todo!(),
+        }
+        AstExpression::ObjectExpression(node) => {
+            if matches!(value, JsValue::Undefined | JsValue::Null) {
+                return Err(format!("TypeError: Cannot destructure '{0}' as it is {0}.", value.get_type_as_str()));
+            }
+
+            for property in &node.properties {
+                let key = interpreter.eval_member_expression_key(&property.key, property.computed)?;
+                let property_value = match &value {
+                    JsValue::Object(object) => interpreter.get_object_property(object, &key)?,
+                    _ => interpreter.get_primitive_property(&value, &key),
+                };
+
+                assign_pattern(interpreter, &property.value, property_value)?;
+            }
+        }
+        AstExpression::AssignmentExpression(node) => {
+            let value = match value {
+                JsValue::Undefined => node.right.execute(interpreter)?,
+                value => value,
+            };
+
+            assign_pattern(interpreter, &node.left, value)?;
+        }
+        _ => {
+            assign_target(interpreter, target, &AssignmentOperator::Equal, value)?;
         }
     }
+
+    return Ok(());
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -66,10 +131,35 @@ pub enum AssignmentOperator {
     SubEqual,
     DivEqual,
     MulEqual,
+    RemEqual,
     ExponentiationEqual,
     Equal,
 }
 
+impl AssignmentOperator {
+    /// Value stored by the assignment, compound operators combine it with the current value
+    fn apply(&self, interpreter: &Interpreter, original_value: &JsValue, right_hand_value: JsValue) -> Result<JsValue, String> {
+        if *self == AssignmentOperator::Equal {
+            return Ok(right_hand_value);
+        }
+
+        let hint = if *self == AssignmentOperator::AddEqual { PrimitiveHint::Default } else { PrimitiveHint::Number };
+        let original_value = &interpreter.to_primitive(original_value, hint)?;
+        let right_hand_value = interpreter.to_primitive(&right_hand_value, hint)?;
+        interpreter.check_arithmetic_operands(assignment_operator_to_str(self), original_value, &right_hand_value)?;
+
+        match self {
+            AssignmentOperator::AddEqual => original_value + &right_hand_value,
+            AssignmentOperator::SubEqual => original_value - &right_hand_value,
+            AssignmentOperator::DivEqual => original_value / &right_hand_value,
+            AssignmentOperator::MulEqual => original_value * &right_hand_value,
+            AssignmentOperator::RemEqual => original_value % &right_hand_value,
+            AssignmentOperator::ExponentiationEqual => original_value.exponentiation(&right_hand_value),
+            AssignmentOperator::Equal => Ok(right_hand_value),
+        }
+    }
+}
+
 impl GetSpan for AssignmentExpressionNode {
     fn get_span(&self) -> TextSpan {
         let begin_span = self.left.get_span();
@@ -96,6 +186,7 @@ impl TryFrom<&TokenKind> for AssignmentOperator {
             TokenKind::PlusEqual => Ok(Self::AddEqual),
             TokenKind::MinusEqual => Ok(Self::SubEqual),
             TokenKind::MulEqual => Ok(Self::MulEqual),
+            TokenKind::PercentEqual => Ok(Self::RemEqual),
             TokenKind::MulMulEqual => Ok(Self::ExponentiationEqual),
             TokenKind::DivEqual => Ok(Self::DivEqual),
             TokenKind::Equal => Ok(Self::Equal),
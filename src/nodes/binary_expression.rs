@@ -1,8 +1,11 @@
+use std::cmp::Ordering;
 use std::rc::Rc;
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+use crate::node::binary_operator_to_str;
 use crate::nodes::AstExpression;
 use crate::scanner::TokenKind;
 use crate::value::JsValue;
+use crate::value::object::PrimitiveHint;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BinaryExpressionNode {
@@ -17,6 +20,7 @@ pub enum BinaryOperator {
     Sub,
     Div,
     Mul,
+    Rem,
     MulMul,
     LogicalOr,
     LogicalAnd,
@@ -26,6 +30,8 @@ pub enum BinaryOperator {
     LessThanOrEqual,
     Equality,
     Inequality,
+    InstanceOf,
+    In,
 }
 
 impl Execute for BinaryExpressionNode {
@@ -33,11 +39,36 @@ impl Execute for BinaryExpressionNode {
         let evaluated_left_node = self.left.execute(interpreter)?;
         let evaluated_right_node = self.right.execute(interpreter)?;
 
+        // objects take part in arithmetic & comparison through their primitive values
+        let hint = match self.operator {
+            BinaryOperator::Add => Some(PrimitiveHint::Default),
+            BinaryOperator::Sub
+            | BinaryOperator::Div
+            | BinaryOperator::Mul
+            | BinaryOperator::Rem
+            | BinaryOperator::MulMul
+            | BinaryOperator::MoreThan
+            | BinaryOperator::MoreThanOrEqual
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqual => Some(PrimitiveHint::Number),
+            _ => None,
+        };
+
+        let (evaluated_left_node, evaluated_right_node) = match hint {
+            Some(hint) => (interpreter.to_primitive(&evaluated_left_node, hint)?, interpreter.to_primitive(&evaluated_right_node, hint)?),
+            None => (evaluated_left_node, evaluated_right_node),
+        };
+
+        if matches!(self.operator, BinaryOperator::Add | BinaryOperator::Sub | BinaryOperator::Div | BinaryOperator::Mul | BinaryOperator::Rem | BinaryOperator::MulMul) {
+            interpreter.check_arithmetic_operands(binary_operator_to_str(&self.operator), &evaluated_left_node, &evaluated_right_node)?;
+        }
+
         match self.operator {
             BinaryOperator::Add => &evaluated_left_node + &evaluated_right_node,
             BinaryOperator::Sub => &evaluated_left_node - &evaluated_right_node,
             BinaryOperator::Div => &evaluated_left_node / &evaluated_right_node,
             BinaryOperator::Mul => &evaluated_left_node * &evaluated_right_node,
+            BinaryOperator::Rem => &evaluated_left_node % &evaluated_right_node,
             BinaryOperator::MulMul => evaluated_left_node.exponentiation(&evaluated_right_node),
             BinaryOperator::LogicalOr => {
                 interpreter.logical_or(&evaluated_left_node, &evaluated_right_node)
@@ -49,25 +80,29 @@ impl Execute for BinaryExpressionNode {
             | BinaryOperator::MoreThanOrEqual
             | BinaryOperator::LessThan
             | BinaryOperator::LessThanOrEqual => {
-                if let JsValue::Number(left_number) = evaluated_left_node {
-                    if let JsValue::Number(right_number) = evaluated_right_node {
-                        let value = match self.operator {
-                            BinaryOperator::MoreThan => left_number > right_number,
-                            BinaryOperator::MoreThanOrEqual => left_number >= right_number,
-                            BinaryOperator::LessThan => left_number < right_number,
-                            BinaryOperator::LessThanOrEqual => left_number <= right_number,
-                            _ => unreachable!(),
-                        };
+                let ordering = evaluated_left_node.relational_comparison(&evaluated_right_node);
 
-                        return Ok(JsValue::Boolean(value));
-                    }
-                }
+                let value = match self.operator {
+                    BinaryOperator::MoreThan => matches!(ordering, Some(Ordering::Greater)),
+                    BinaryOperator::MoreThanOrEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    BinaryOperator::LessThan => matches!(ordering, Some(Ordering::Less)),
+                    BinaryOperator::LessThanOrEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    _ => unreachable!(),
+                };
+
+                Ok(JsValue::Boolean(value))
+            }
+            BinaryOperator::InstanceOf => {
+                evaluated_left_node.instance_of(&evaluated_right_node).map(JsValue::Boolean)
+            }
+            BinaryOperator::In => {
+                let key = interpreter.to_property_key(&evaluated_left_node)?;
+
+                let JsValue::Object(object) = &evaluated_right_node else {
+                    return Err(format!("TypeError: Cannot use 'in' operator to search for '{key}' in {}", evaluated_right_node.to_js_string()));
+                };
 
-                Err(format!(
-                    "Cannot compare value with type \"{}\" and \"{}\"",
-                    evaluated_left_node.get_type_as_str(),
-                    evaluated_right_node.get_type_as_str()
-                ).to_string())
+                interpreter.has_property(object, &key).map(JsValue::Boolean)
             }
             BinaryOperator::Equality
             | BinaryOperator::Inequality => {
@@ -115,6 +150,7 @@ impl TryFrom<&TokenKind> for BinaryOperator {
             TokenKind::Plus => Ok(Self::Add),
             TokenKind::Minus => Ok(Self::Sub),
             TokenKind::Mul => Ok(Self::Mul),
+            TokenKind::Percent => Ok(Self::Rem),
             TokenKind::MulMul => Ok(Self::MulMul),
             TokenKind::Div => Ok(Self::Div),
             TokenKind::Or => Ok(Self::LogicalOr),
@@ -125,6 +161,8 @@ impl TryFrom<&TokenKind> for BinaryOperator {
             TokenKind::MoreThanOrEqual => Ok(Self::MoreThanOrEqual),
             TokenKind::Equality => Ok(Self::Equality),
             TokenKind::Inequality => Ok(Self::Inequality),
+            TokenKind::InstanceOfKeyword => Ok(Self::InstanceOf),
+            TokenKind::InKeyword => Ok(Self::In),
             _ => Err("Cannot convert token kind to binary operator".to_string()),
         }
     }
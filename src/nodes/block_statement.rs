@@ -1,18 +1,16 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::AstStatement;
+use crate::nodes::{AstStatement, TextSpan};
 use crate::value::JsValue;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockStatementNode {
     pub statements: Vec<AstStatement>,
+    pub span: TextSpan,
 }
 
 impl Execute for BlockStatementNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
         let env = interpreter.create_new_environment();
-        interpreter.set_environment(env);
-        let result = self.statements.execute(interpreter);
-        interpreter.pop_environment();
-        return result;
+        return interpreter.with_environment(env, |interpreter| self.statements.execute(interpreter));
     }
 }
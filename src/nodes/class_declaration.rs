@@ -1,10 +1,11 @@
+use std::rc::Rc;
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
 use crate::nodes::AstExpression;
 use crate::nodes::function_signature::FunctionSignature;
 use crate::nodes::identifier::IdentifierNode;
-use crate::value::function::JsFunction;
+use crate::value::function::{JsFunction, OrdinaryFunction};
 use crate::value::JsValue;
-use crate::value::object::JsObject;
+use crate::value::object::{JsObject, JsObjectRef, link_constructor_prototype};
 
 const CONSTRUCTOR_METHOD_NAME: &'static str = "constructor";
 
@@ -13,6 +14,15 @@ pub struct ClassDeclarationNode {
     pub name: Box<IdentifierNode>,
     pub parent: Option<Box<IdentifierNode>>,
     pub methods: Vec<Box<ClassMethodNode>>,
+    /// Shared with the constructor, which initializes them on every new instance
+    pub fields: Rc<Vec<ClassFieldNode>>,
+}
+
+/// Instance field, e.g. `count = 0;`, without a value it's initialized with undefined
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassFieldNode {
+    pub name: IdentifierNode,
+    pub value: Option<AstExpression>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -22,56 +32,76 @@ pub struct ClassMethodNode {
 
 impl Execute for ClassDeclarationNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        let prototype_object = self.build_prototype_object_from_class_declaration(interpreter);
-        let mut constructor_function = self.build_constructor_from_class_declaration(interpreter).to_object();
+        let mut prototype_object = self.build_prototype_object_from_class_declaration(interpreter)?;
+        let mut parent_constructor = None;
 
-        constructor_function.set_prototype(prototype_object.to_ref());
+        if let Some(parent) = &self.parent {
+            let parent_object = match interpreter.environment.borrow().borrow().get_variable_value(&parent.id) {
+                JsValue::Object(object) if object.borrow().is_function() => object,
+                _ => return Err(format!("TypeError: Class extends value {} is not a constructor", parent.id)),
+            };
+
+            if let JsValue::Object(parent_prototype) = parent_object.borrow().get_prototype() {
+                prototype_object.set_proto(parent_prototype);
+            }
+
+            parent_constructor = Some(parent_object);
+        }
 
-        let constructor_function = JsValue::Object(constructor_function.to_ref());
+        let constructor_function = self.build_constructor_from_class_declaration(interpreter, parent_constructor)?.to_object().to_ref();
+        link_constructor_prototype(&constructor_function, &prototype_object.to_ref());
+
+        let constructor_function = JsValue::Object(constructor_function);
 
         interpreter.environment.borrow().borrow_mut().define_variable(
             self.name.id.clone(),
             constructor_function.clone(),
             false
-        ).unwrap();
+        )?;
 
         Ok(constructor_function)
     }
 }
 
 impl ClassDeclarationNode {
-    fn build_prototype_object_from_class_declaration(&self, interpreter: &Interpreter) -> JsObject {
+    fn build_prototype_object_from_class_declaration(&self, interpreter: &Interpreter) -> Result<JsObject, String> {
         let mut prototype_object = JsObject::empty();
 
-        for class_method in &self.methods {
-            let method_value = interpreter.create_js_function(&class_method.function_signature.arguments, *class_method.function_signature.body.clone());
-
-            prototype_object.add_property(&class_method.function_signature.name.id, method_value.into());
-            // if let AstStatement::FunctionDeclaration(method_declaration) = &class_method {
-            // if method_declaration.name.id == CONSTRUCTOR_METHOD_NAME { continue; }
+        // `constructor` method becomes the class itself, prototype gets `constructor` pointing at the class
+        for class_method in self.methods.iter().filter(|x| x.function_signature.name.id != CONSTRUCTOR_METHOD_NAME) {
+            let method_value = interpreter.create_js_function(&class_method.function_signature.name.id, &class_method.function_signature.arguments, &class_method.function_signature.body)?;
 
-            // let function = self.eval_function_declaration(&method_declaration).unwrap();
-            //
-            // if let IdentifierNode { id, .. } = method_declaration.function_signature.name.as_ref() {
-            //     prototype_object.add_property(id.as_str(), function);
-            // }
-            // }
+            prototype_object.add_non_enumerable_property(&class_method.function_signature.name.id, method_value.into());
         }
 
-        prototype_object
+        return Ok(prototype_object);
     }
 
-    pub(crate) fn build_constructor_from_class_declaration(&self, interpreter: &Interpreter) -> JsFunction {
+    /// Constructor is tagged with the class name, so calling it without `new` is an error.
+    /// Without `constructor` method it's synthesized, for derived classes it passes its arguments to the parent constructor
+    pub(crate) fn build_constructor_from_class_declaration(&self, interpreter: &Interpreter, parent_constructor: Option<JsObjectRef>) -> Result<JsFunction, String> {
         let constructor_method = self.methods.iter().find(|x| {
             return x.function_signature.name.id == CONSTRUCTOR_METHOD_NAME;
         });
 
-        if constructor_method.is_some() {
+        let mut constructor = if constructor_method.is_some() {
             let function_signature = &constructor_method.unwrap().as_ref().function_signature;
-            interpreter.create_js_function(&function_signature.arguments, *function_signature.body.clone())
+            interpreter.create_js_function("", &function_signature.arguments, &function_signature.body)?
         } else {
-            JsFunction::empty().into()
+            let mut function = OrdinaryFunction::empty_function();
+            function.environment = interpreter.environment.borrow().clone();
+
+            function.parent_constructor = parent_constructor;
+            function.into()
+        };
+
+        if let JsFunction::Ordinary(function) = &mut constructor {
+            function.name = self.name.id.clone();
+            function.class_name = Some(self.name.id.clone());
+            function.fields = Rc::clone(&self.fields);
         }
+
+        return Ok(constructor);
     }
 }
 
@@ -1,5 +1,5 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::AstExpression;
+use crate::nodes::{AstExpression, GetSpan, TextSpan};
 use crate::value::JsValue;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -9,9 +9,16 @@ pub struct ConditionalExpressionNode {
     pub alternative: Box<AstExpression>,
 }
 
+impl GetSpan for ConditionalExpressionNode {
+    fn get_span(&self) -> TextSpan {
+        self.test.get_span().join(&self.alternative.get_span())
+    }
+}
+
 impl Execute for ConditionalExpressionNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
         let test = self.test.execute(interpreter)?;
+        interpreter.record_branch_coverage(self, test.to_bool());
 
         let branch = if test.to_bool() {
             &self.consequent
@@ -12,20 +12,25 @@ pub struct ForStatementNode {
 
 impl Execute for ForStatementNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        interpreter.set_environment(interpreter.create_new_environment());
+        interpreter.with_environment(interpreter.create_new_environment(), |interpreter| {
+            if let Some(init) = &self.init {
+                init.execute(interpreter)?;
+            }
 
-        if let Some(init) = &self.init {
-            init.execute(interpreter)?;
-        }
+            // missing condition loops until `break`
+            while self.test.as_ref().map_or(Ok(JsValue::Boolean(true)), |x| x.execute(interpreter))?.to_bool() {
+                self.body.execute(interpreter)?;
 
-        while self.test.as_ref().unwrap().execute(interpreter)?.to_bool()
-        {
-            self.body.execute(interpreter)?;
-            self.update.as_ref().unwrap().execute(interpreter)?;
-        }
+                if interpreter.should_exit_loop() {
+                    break;
+                }
 
-        interpreter.pop_environment();
+                if let Some(update) = &self.update {
+                    update.execute(interpreter)?;
+                }
+            }
 
-        Ok(JsValue::Undefined)
+            Ok(JsValue::Undefined)
+        })
     }
 }
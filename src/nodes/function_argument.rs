@@ -5,4 +5,6 @@ use crate::nodes::identifier::IdentifierNode;
 pub struct FunctionArgument {
     pub name: IdentifierNode,
     pub default_value: Option<Box<AstExpression>>,
+    /// `...name`, collects all remaining arguments into an array
+    pub is_rest: bool,
 }
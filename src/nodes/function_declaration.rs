@@ -1,7 +1,7 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
 use crate::nodes::function_signature::FunctionSignature;
 use crate::value::JsValue;
-use crate::value::object::JsObject;
+use crate::value::object::{JsObject, link_constructor_prototype};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FunctionDeclarationNode {
@@ -10,10 +10,10 @@ pub struct FunctionDeclarationNode {
 
 impl Execute for FunctionDeclarationNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        let js_function_value: JsValue = interpreter.create_js_function(&self.function_signature.arguments, *self.function_signature.body.clone()).into();
+        let js_function_value: JsValue = interpreter.create_js_function(&self.function_signature.name.id, &self.function_signature.arguments, &self.function_signature.body)?.into();
 
         if let JsValue::Object(function) = &js_function_value {
-            function.borrow_mut().set_prototype(JsObject::empty_ref());
+            link_constructor_prototype(function, &JsObject::empty_ref());
         }
 
         interpreter.environment.borrow()
@@ -1,19 +1,21 @@
+use std::rc::Rc;
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::{AstStatement, FunctionArgument};
+use crate::nodes::{AstStatement, FunctionArgument, TextSpan};
 use crate::value::JsValue;
+use crate::value::object::{JsObject, link_constructor_prototype};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FunctionExpressionNode {
     pub arguments: Vec<FunctionArgument>,
-    pub body: Box<AstStatement>,
+    /// Shared with every function value created from this expression
+    pub body: Rc<AstStatement>,
+    pub span: TextSpan,
 }
 
 impl Execute for FunctionExpressionNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        let function = interpreter.create_js_function(&self.arguments, *self.body.clone());
-        let mut object = function.to_object();
-        object.add_property("prototype", JsValue::object([]));
-        // object.set_prototype(JsObject::empty_ref());
-        return Ok(object.to_js_value());
+        let function = interpreter.create_js_function("", &self.arguments, &self.body)?.to_object().to_ref();
+        link_constructor_prototype(&function, &JsObject::empty_ref());
+        return Ok(JsValue::Object(function));
     }
 }
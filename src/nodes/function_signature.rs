@@ -1,3 +1,4 @@
+use std::rc::Rc;
 use crate::nodes::AstStatement;
 use crate::nodes::function_argument::FunctionArgument;
 use crate::nodes::identifier::IdentifierNode;
@@ -6,5 +7,6 @@ use crate::nodes::identifier::IdentifierNode;
 pub struct FunctionSignature {
     pub name: Box<IdentifierNode>,
     pub arguments: Vec<FunctionArgument>,
-    pub body: Box<AstStatement>,
+    /// Shared with every function value created from this signature
+    pub body: Rc<AstStatement>,
 }
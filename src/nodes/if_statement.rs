@@ -1,4 +1,4 @@
-use crate::nodes::{AstExpression, AstStatement, Execute, Interpreter, JsValue};
+use crate::nodes::{AstExpression, AstStatement, Execute, GetSpan, Interpreter, JsValue, TextSpan};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct IfStatementNode {
@@ -7,9 +7,17 @@ pub struct IfStatementNode {
     pub else_branch: Option<Box<AstStatement>>,
 }
 
+impl GetSpan for IfStatementNode {
+    fn get_span(&self) -> TextSpan {
+        let last_branch = self.else_branch.as_ref().unwrap_or(&self.then_branch);
+        self.condition.get_span().join(&last_branch.get_span())
+    }
+}
+
 impl Execute for IfStatementNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
         let condition_value = self.condition.execute(interpreter)?;
+        interpreter.record_branch_coverage(self, condition_value.to_bool());
 
         if condition_value.to_bool() {
             self.then_branch.execute(interpreter)?;
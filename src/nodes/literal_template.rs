@@ -0,0 +1,95 @@
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use crate::nodes::{static_property_key, AstExpression, ObjectPropertyNode, UnaryOperator};
+use crate::value::JsValue;
+use crate::value::object::{JsObject, PROTO_PROPERTY};
+
+/// Value of an array or object literal built only from constants, e.g. a lookup table.
+/// It's computed once the literal is parsed, evaluation copies it instead of evaluating every item
+#[derive(Clone, Default)]
+pub struct LiteralTemplate(Option<Rc<JsObject>>);
+
+impl LiteralTemplate {
+    pub fn of_array(items: &[Option<AstExpression>]) -> Self {
+        let values: Option<Vec<Option<JsValue>>> = items.iter()
+            .map(|item| match item {
+                Some(item) => constant_value(item).map(Some),
+                None => Some(None),
+            })
+            .collect();
+
+        Self(values.map(|x| Rc::new(JsObject::sparse_array(x))))
+    }
+
+    pub fn of_object(properties: &[ObjectPropertyNode]) -> Self {
+        let mut object = JsObject::empty();
+
+        for property in properties {
+            let key = match static_property_key(&property.key) {
+                Some(key) if !property.computed => key,
+                _ => return Self(None),
+            };
+
+            // `__proto__` key sets prototype, it's left to the evaluation
+            if key == PROTO_PROPERTY {
+                return Self(None);
+            }
+
+            match constant_value(&property.value) {
+                Some(value) => object.add_property(&key, value),
+                None => return Self(None),
+            }
+        }
+
+        Self(Some(Rc::new(object)))
+    }
+
+    /// Fresh copy of the template, nested arrays & objects are copied too, so scripts can mutate the result
+    pub fn instantiate(&self) -> Option<JsValue> {
+        self.0.as_ref().map(|template| JsValue::Object(copy_object(template).to_ref()))
+    }
+}
+
+fn copy_object(object: &JsObject) -> JsObject {
+    let mut copy = object.clone();
+
+    for value in copy.properties.values_mut() {
+        if let JsValue::Object(nested) = value {
+            let nested_copy = copy_object(&nested.borrow()).to_ref();
+            *value = JsValue::Object(nested_copy);
+        }
+    }
+
+    copy
+}
+
+/// Value of an expression which doesn't depend on anything & has no side effects
+fn constant_value(expression: &AstExpression) -> Option<JsValue> {
+    match expression {
+        AstExpression::NumberLiteral(node) => Some(JsValue::Number(node.value)),
+        AstExpression::StringLiteral(node) => Some(JsValue::String(Rc::clone(&node.value))),
+        AstExpression::BooleanLiteral(node) => Some(JsValue::Boolean(node.value)),
+        AstExpression::NullLiteral(_) => Some(JsValue::Null),
+        AstExpression::UndefinedLiteral(_) => Some(JsValue::Undefined),
+        AstExpression::UnaryExpression(node) if node.operator == UnaryOperator::Minus => match node.argument.as_ref() {
+            AstExpression::NumberLiteral(number) => Some(JsValue::Number(-number.value)),
+            _ => None,
+        },
+        AstExpression::ArrayExpression(node) => node.template.instantiate(),
+        AstExpression::ObjectExpression(node) => node.template.instantiate(),
+        _ => None,
+    }
+}
+
+impl Debug for LiteralTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(if self.0.is_some() { "constant" } else { "none" })
+    }
+}
+
+/// Template is derived from the literal itself, so it never makes literals different
+impl PartialEq for LiteralTemplate {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
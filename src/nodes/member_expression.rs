@@ -1,6 +1,7 @@
+use std::borrow::Cow;
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::AstExpression;
-use crate::value::JsValue;
+use crate::nodes::{AstExpression, GetSpan, TextSpan};
+use crate::value::{number_to_string, JsValue};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct MemberExpressionNode {
@@ -9,17 +10,53 @@ pub struct MemberExpressionNode {
     pub property: Box<AstExpression>, // TODO: maybe type
 }
 
-impl Execute for MemberExpressionNode {
-    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
+impl MemberExpressionNode {
+    /// Evaluates object & reads the property, object is returned too since it's `this` of a method call
+    pub fn evaluate_with_object(&self, interpreter: &Interpreter) -> Result<(JsValue, JsValue), String> {
         let property_key = interpreter.eval_member_expression_key(&self.property, self.computed)?;
         let resolved_object = self.object.execute(interpreter)?;
 
-        match resolved_object {
-            JsValue::Object(object) => {
-                Ok(object.borrow_mut().get_property_value(property_key.as_str()))
-            },
-            _ => Err("Is not an object".to_string())
+        if self.computed {
+            interpreter.check_array_index(&resolved_object, &property_key)?;
         }
 
+        let value = match &resolved_object {
+            JsValue::Object(object) => interpreter.get_object_property(object, &property_key)?,
+            JsValue::Undefined | JsValue::Null => {
+                return Err(property_access_error(&resolved_object, &property_key, false));
+            }
+            _ => interpreter.get_primitive_property(&resolved_object, &property_key),
+        };
+
+        return Ok((resolved_object, value));
+    }
+}
+
+impl Execute for MemberExpressionNode {
+    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
+        return self.evaluate_with_object(interpreter).map(|(_, value)| value);
+    }
+}
+
+impl GetSpan for MemberExpressionNode {
+    fn get_span(&self) -> TextSpan {
+        self.object.get_span().join(&self.property.get_span())
+    }
+}
+
+/// Key of a property named without computation, e.g. `a.b`, `{ "b": 1 }` or `{ 1.0: 1 }`.
+/// Numbers are keyed by their canonical string like computed keys, so `{ 1.0: x }` & `a["1"]` address the same property
+pub fn static_property_key(node: &AstExpression) -> Option<Cow<'_, str>> {
+    match node {
+        AstExpression::Identifier(node) => Some(Cow::Borrowed(node.id.as_str())),
+        AstExpression::StringLiteral(node) => Some(Cow::Borrowed(&node.value)),
+        AstExpression::NumberLiteral(node) => Some(Cow::Owned(number_to_string(node.value))),
+        _ => None,
     }
+}
+
+/// TypeError message for reading or setting a property of null or undefined, mirrors V8 wording
+pub fn property_access_error(value: &JsValue, key: &str, is_setting: bool) -> String {
+    let (action, participle) = if is_setting { ("set", "setting") } else { ("read", "reading") };
+    format!("TypeError: Cannot {action} properties of {} ({participle} '{key}')", value.get_type_as_str())
 }
\ No newline at end of file
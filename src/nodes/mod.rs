@@ -29,12 +29,17 @@ mod object_property;
 mod object_expression;
 mod new_expression;
 mod this_expression;
+mod spread_element;
+mod throw_statement;
+mod unary_expression;
+mod switch_statement;
+mod literal_template;
 
 pub use object_property::*;
 pub use function_signature::*;
 pub use class_declaration::*;
 pub use number_literal_node::NumberLiteralNode;
-pub use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+pub use crate::interpreter::ast_interpreter::{Completion, Execute, Interpreter};
 pub use crate::node::GetSpan;
 pub use crate::nodes::block_statement::BlockStatementNode;
 pub use crate::nodes::for_statement::ForStatementNode;
@@ -47,16 +52,21 @@ pub use crate::scanner::{TextSpan, Token};
 pub use crate::value::JsValue;
 pub use function_argument::FunctionArgument;
 pub use crate::nodes::array_expression::ArrayExpressionNode;
+pub use crate::nodes::literal_template::LiteralTemplate;
 pub use crate::nodes::assignment_expression::{AssignmentExpressionNode, AssignmentOperator};
 pub use crate::nodes::binary_expression::{BinaryExpressionNode, BinaryOperator};
 pub use crate::nodes::call_expression::CallExpressionNode;
 pub use crate::nodes::conditional_expression::ConditionalExpressionNode;
 pub use crate::nodes::function_declaration::FunctionDeclarationNode;
 pub use crate::nodes::function_expression::FunctionExpressionNode;
-pub use crate::nodes::member_expression::MemberExpressionNode;
+pub use crate::nodes::member_expression::{MemberExpressionNode, static_property_key};
 pub use crate::nodes::new_expression::NewExpressionNode;
 pub use crate::nodes::object_expression::ObjectExpressionNode;
 pub use crate::nodes::this_expression::ThisExpressionNode;
+pub use crate::nodes::spread_element::SpreadElementNode;
+pub use crate::nodes::throw_statement::ThrowStatementNode;
+pub use crate::nodes::unary_expression::{UnaryExpressionNode, UnaryOperator};
+pub use crate::nodes::switch_statement::{SwitchStatementNode, SwitchCaseNode};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AstStatement {
@@ -70,6 +80,11 @@ pub enum AstStatement {
     ExpressionStatement(AstExpression),
     IfStatement(IfStatementNode),
     BreakStatement(Token),
+    ContinueStatement(Token),
+    SwitchStatement(SwitchStatementNode),
+    ThrowStatement(ThrowStatementNode),
+    /// Stray `;`, it does nothing
+    EmptyStatement(Token),
 }
 
 impl Execute for Vec<AstStatement> {
@@ -77,7 +92,17 @@ impl Execute for Vec<AstStatement> {
         let mut result = JsValue::Undefined;
 
         for i in self {
+            // empty statement doesn't change completion value, e.g. `1;;` evaluates to 1
+            if let AstStatement::EmptyStatement(_) = i {
+                continue;
+            }
+
             result = i.execute(interpreter)?;
+
+            // `break`, `continue` & `return` skip the rest of the list
+            if interpreter.is_abrupt_completion() {
+                break;
+            }
         }
 
         Ok(result)
@@ -86,7 +111,11 @@ impl Execute for Vec<AstStatement> {
 
 impl Execute for AstStatement {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        match self {
+        interpreter.record_statement_coverage(self);
+        interpreter.count_statement()?;
+        interpreter.run_hooks(|hooks, interpreter| hooks.before_statement(interpreter, self));
+
+        let result = match self {
             AstStatement::ProgramStatement(node) => node.execute(interpreter),
             AstStatement::VariableDeclaration(node) => node.execute(interpreter),
             AstStatement::BlockStatement(node) => node.execute(interpreter),
@@ -96,8 +125,25 @@ impl Execute for AstStatement {
             AstStatement::ReturnStatement(node) => node.execute(interpreter),
             AstStatement::ExpressionStatement(node) => node.execute(interpreter),
             AstStatement::IfStatement(node) => node.execute(interpreter),
-            AstStatement::BreakStatement(_) => todo!(),
+            AstStatement::BreakStatement(_) => {
+                interpreter.set_completion(Completion::Break);
+                Ok(JsValue::Undefined)
+            }
+            AstStatement::ContinueStatement(_) => {
+                interpreter.set_completion(Completion::Continue);
+                Ok(JsValue::Undefined)
+            }
+            AstStatement::SwitchStatement(node) => node.execute(interpreter),
+            AstStatement::ThrowStatement(node) => node.execute(interpreter),
+            AstStatement::EmptyStatement(_) => Ok(JsValue::Undefined),
+        };
+
+        if result.is_err() && !matches!(self, AstStatement::ProgramStatement(_)) {
+            interpreter.record_error_span(self);
         }
+
+        interpreter.run_hooks(|hooks, interpreter| hooks.after_statement(interpreter, self));
+        return result;
     }
 }
 
@@ -126,11 +172,13 @@ pub enum AstExpression {
     ObjectExpression(ObjectExpressionNode),
     ClassDeclaration(ClassDeclarationNode),
     ArrayExpression(ArrayExpressionNode),
+    SpreadElement(SpreadElementNode),
+    UnaryExpression(UnaryExpressionNode),
 }
 
 impl Execute for AstExpression {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        match self {
+        let result = match self {
             AstExpression::StringLiteral(node) => node.execute(interpreter),
             AstExpression::NumberLiteral(node) => node.execute(interpreter),
             AstExpression::BooleanLiteral(node) => node.execute(interpreter),
@@ -148,7 +196,15 @@ impl Execute for AstExpression {
             AstExpression::ObjectExpression(node) => node.execute(interpreter),
             AstExpression::ClassDeclaration(node) => node.execute(interpreter),
             AstExpression::ArrayExpression(node) => node.execute(interpreter),
+            AstExpression::SpreadElement(node) => node.execute(interpreter),
+            AstExpression::UnaryExpression(node) => node.execute(interpreter),
+        };
+
+        if result.is_err() {
+            interpreter.record_error_span(self);
         }
+
+        return result;
     }
 }
 
@@ -161,18 +217,65 @@ impl GetSpan for AstExpression {
             AstExpression::NullLiteral(node) => node.span.clone(),
             AstExpression::UndefinedLiteral(node) => node.span.clone(),
             AstExpression::Identifier(node) => node.token.span.clone(),
-            _ => todo!()
-            // AstExpression::ThisExpression(_) => {}
-            // AstExpression::BinaryExpression(_) => {}
-            // AstExpression::AssignmentExpression(_) => {}
-            // AstExpression::FunctionExpression(_) => {}
-            // AstExpression::CallExpression(_) => {}
-            // AstExpression::ConditionalExpression(_) => {}
-            // AstExpression::MemberExpression(_) => {}
-            // AstExpression::NewExpression(_) => {}
-            // AstExpression::ObjectExpression(_) => {}
-            // AstExpression::ClassDeclaration(_) => {}
-            // AstExpression::ArrayExpression(_) => {}
+            AstExpression::ThisExpression(node) => node.token.span.clone(),
+            AstExpression::BinaryExpression(node) => node.left.get_span().join(&node.right.get_span()),
+            AstExpression::AssignmentExpression(node) => node.get_span(),
+            AstExpression::FunctionExpression(node) => node.span.clone(),
+            AstExpression::CallExpression(node) => {
+                let callee_span = node.callee.get_span();
+                node.params.last().map_or(callee_span.clone(), |x| callee_span.join(&x.get_span()))
+            }
+            AstExpression::ConditionalExpression(node) => node.get_span(),
+            AstExpression::MemberExpression(node) => node.get_span(),
+            AstExpression::NewExpression(node) => {
+                let callee_span = node.callee.get_span();
+                node.arguments.last().map_or(callee_span.clone(), |x| callee_span.join(&x.get_span()))
+            }
+            AstExpression::ObjectExpression(node) => node.span.clone(),
+            AstExpression::ClassDeclaration(node) => {
+                let name_span = node.name.get_span();
+                node.methods.last().map_or(name_span.clone(), |x| name_span.join(&x.function_signature.body.get_span()))
+            }
+            AstExpression::ArrayExpression(node) => node.span.clone(),
+            AstExpression::SpreadElement(node) => node.span.clone(),
+            AstExpression::UnaryExpression(node) => node.get_span(),
+        }
+    }
+}
+
+impl GetSpan for AstStatement {
+    fn get_span(&self) -> TextSpan {
+        match self {
+            AstStatement::ProgramStatement(node) => match (node.statements.first(), node.statements.last()) {
+                (Some(first), Some(last)) => first.get_span().join(&last.get_span()),
+                _ => TextSpan::default(),
+            },
+            AstStatement::VariableDeclaration(node) => {
+                let id_span = node.id.get_span();
+                node.value.as_ref().map_or(id_span.clone(), |x| id_span.join(&x.get_span()))
+            }
+            AstStatement::BlockStatement(node) => node.span.clone(),
+            AstStatement::WhileStatement(node) => node.condition.get_span().join(&node.body.get_span()),
+            AstStatement::ForStatement(node) => {
+                let body_span = node.body.get_span();
+
+                match (&node.init, &node.test) {
+                    (Some(init), _) => init.get_span().join(&body_span),
+                    (None, Some(test)) => test.get_span().join(&body_span),
+                    (None, None) => body_span,
+                }
+            }
+            AstStatement::FunctionDeclaration(node) => {
+                node.function_signature.name.get_span().join(&node.function_signature.body.get_span())
+            }
+            AstStatement::ReturnStatement(node) => node.expression.get_span(),
+            AstStatement::ExpressionStatement(node) => node.get_span(),
+            AstStatement::IfStatement(node) => node.get_span(),
+            AstStatement::BreakStatement(token) => token.span.clone(),
+            AstStatement::ContinueStatement(token) => token.span.clone(),
+            AstStatement::SwitchStatement(node) => node.span.clone(),
+            AstStatement::ThrowStatement(node) => node.get_span(),
+            AstStatement::EmptyStatement(token) => token.span.clone(),
         }
     }
 }
\ No newline at end of file
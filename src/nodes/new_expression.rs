@@ -10,12 +10,6 @@ pub struct NewExpressionNode {
 
 impl Execute for NewExpressionNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        let callee = self.callee.execute(interpreter)?;
-
-        if !callee.is_function() {
-            return Err("Cannot call non-function value".to_string());
-        }
-
         interpreter.call_function(self.callee.as_ref(), self.arguments.as_ref(), true)
     }
 }
\ No newline at end of file
@@ -1,20 +1,47 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+use crate::nodes::LiteralTemplate;
 use crate::nodes::object_property::ObjectPropertyNode;
+use crate::scanner::TextSpan;
 use crate::value::JsValue;
-use crate::value::object::JsObject;
+use crate::value::object::{JsObject, PROTO_PROPERTY};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ObjectExpressionNode {
     pub properties: Vec<ObjectPropertyNode>,
+    pub span: TextSpan,
+    pub template: LiteralTemplate,
+}
+
+impl ObjectExpressionNode {
+    pub fn new(properties: Vec<ObjectPropertyNode>, span: TextSpan) -> Self {
+        let template = LiteralTemplate::of_object(&properties);
+        Self { properties, span, template }
+    }
 }
 
 impl Execute for ObjectExpressionNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
+        if let Some(value) = self.template.instantiate() {
+            return Ok(value);
+        }
+
         let mut object_value = JsObject::empty();
 
         for property in &self.properties {
             let key = interpreter.eval_member_expression_key(&property.key, property.computed)?;
-            object_value.add_property(&key, property.value.execute(interpreter)?);
+            let value = property.value.execute(interpreter)?;
+
+            // `{ __proto__: parent }` sets prototype instead of creating a property,
+            // non object values are ignored, computed `["__proto__"]` is an ordinary property
+            if !property.computed && key == PROTO_PROPERTY {
+                if let JsValue::Object(prototype) = value {
+                    object_value.set_proto(prototype);
+                }
+
+                continue;
+            }
+
+            object_value.add_property(&key, value);
         }
 
         return Ok(object_value.into());
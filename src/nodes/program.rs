@@ -1,5 +1,5 @@
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::AstStatement;
+use crate::nodes::{AstExpression, AstStatement};
 use crate::value::JsValue;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -7,8 +7,49 @@ pub struct ProgramNode {
     pub statements: Vec<AstStatement>,
 }
 
+impl ProgramNode {
+    /// Completion value of a program is the value of its last executed top level expression statement,
+    /// declarations don't change it, e.g. `1; let a = 2;` results in 1.
+    /// It's `None` when no such statement was executed, e.g. the program only declares something.
+    /// `on_result` is called with every statement producing a value, in the order they are executed
+    pub fn completion_value(
+        &self,
+        interpreter: &Interpreter,
+        on_result: &mut dyn FnMut(&AstStatement, &JsValue),
+    ) -> Result<Option<JsValue>, String> {
+        let mut result = None;
+
+        for statement in &self.statements {
+            let value = statement.execute(interpreter)?;
+
+            if is_result_statement(statement) {
+                on_result(statement, &value);
+                result = Some(value);
+            }
+
+            // top level `return` ends the script
+            if interpreter.is_abrupt_completion() {
+                break;
+            }
+        }
+
+        return Ok(result);
+    }
+}
+
 impl Execute for ProgramNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        self.statements.execute(interpreter)
+        let result = self.completion_value(interpreter, &mut |_, _| {})?;
+        return Ok(result.unwrap_or(JsValue::Undefined));
     }
 }
+
+fn is_result_statement(statement: &AstStatement) -> bool {
+    !matches!(
+        statement,
+        AstStatement::VariableDeclaration(_)
+            | AstStatement::FunctionDeclaration(_)
+            | AstStatement::EmptyStatement(_)
+            | AstStatement::ExpressionStatement(AstExpression::ClassDeclaration(_))
+    )
+}
@@ -1,6 +1,8 @@
-use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+use crate::interpreter::ast_interpreter::{Completion, Execute, Interpreter};
 use crate::nodes::AstExpression;
+use crate::value::function::JsFunction;
 use crate::value::JsValue;
+use crate::value::object::ObjectKind;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReturnStatementNode {
@@ -9,6 +11,32 @@ pub struct ReturnStatementNode {
 
 impl Execute for ReturnStatementNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        self.expression.execute(interpreter)
+        if let AstExpression::CallExpression(call) = self.expression.as_ref() {
+            if interpreter.is_inside_function() {
+                let (function, this, arguments) = interpreter.evaluate_call(&call.callee, &call.params, false)?;
+
+                if !is_ordinary_function(&function) {
+                    let value = interpreter.call_function_value(&function, this, arguments, false)?;
+                    interpreter.set_completion(Completion::Return(value.clone()));
+                    return Ok(value);
+                }
+
+                interpreter.set_completion(Completion::TailCall { function, this, arguments });
+                return Ok(JsValue::Undefined);
+            }
+        }
+
+        let value = self.expression.execute(interpreter)?;
+        interpreter.set_completion(Completion::Return(value.clone()));
+        return Ok(value);
     }
 }
+
+/// Natives & class constructors are called in place, only calls of ordinary functions are deferred
+fn is_ordinary_function(value: &JsValue) -> bool {
+    let JsValue::Object(object) = value else {
+        return false;
+    };
+
+    return matches!(&object.borrow().kind, ObjectKind::Function(JsFunction::Ordinary(function)) if function.class_name.is_none());
+}
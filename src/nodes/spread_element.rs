@@ -0,0 +1,16 @@
+use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+use crate::nodes::{AstExpression, TextSpan};
+use crate::value::JsValue;
+
+/// `...argument` in a call argument list or an array literal, expanded by the call or the array itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadElementNode {
+    pub argument: Box<AstExpression>,
+    pub span: TextSpan,
+}
+
+impl Execute for SpreadElementNode {
+    fn execute(&self, _: &Interpreter) -> Result<JsValue, String> {
+        return Err("SyntaxError: Spread syntax is only allowed in call arguments and array literals".to_string());
+    }
+}
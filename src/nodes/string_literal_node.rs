@@ -1,17 +1,19 @@
 use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
 use crate::interpreter::ast_interpreter::{Execute, Interpreter};
 use crate::scanner::Token;
 use crate::value::JsValue;
 
 #[derive(Clone, PartialEq)]
 pub struct StringLiteralNode {
-    pub value: String,
+    /// Shared with every value the literal evaluates to, so evaluation doesn't copy the string
+    pub value: Rc<str>,
     pub token: Token,
 }
 
 impl Execute for StringLiteralNode {
     fn execute(&self, _: &Interpreter) -> Result<JsValue, String> {
-        Ok(JsValue::String(self.value.clone()))
+        Ok(JsValue::String(Rc::clone(&self.value)))
     }
 }
 
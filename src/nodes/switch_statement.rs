@@ -0,0 +1,57 @@
+use crate::interpreter::ast_interpreter::{Completion, Execute, Interpreter};
+use crate::nodes::{AstExpression, AstStatement, TextSpan};
+use crate::value::JsValue;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchStatementNode {
+    pub discriminant: Box<AstExpression>,
+    pub cases: Vec<SwitchCaseNode>,
+    pub span: TextSpan,
+}
+
+/// `case test: ...` or `default: ...` when `test` is `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchCaseNode {
+    pub test: Option<AstExpression>,
+    pub consequent: Vec<AstStatement>,
+}
+
+impl Execute for SwitchStatementNode {
+    /// Runs statements starting from the first case strictly equal to the discriminant, or from `default`,
+    /// execution falls through the following cases until `break`
+    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
+        let discriminant = self.discriminant.execute(interpreter)?;
+
+        interpreter.with_environment(interpreter.create_new_environment(), |interpreter| {
+            let mut start_index = None;
+
+            for (index, case) in self.cases.iter().enumerate() {
+                if let Some(test) = &case.test {
+                    if test.execute(interpreter)?.is_strictly_equal(&discriminant) {
+                        start_index = Some(index);
+                        break;
+                    }
+                }
+            }
+
+            let Some(start_index) = start_index.or_else(|| self.cases.iter().position(|x| x.test.is_none())) else {
+                return Ok(JsValue::Undefined);
+            };
+
+            for case in &self.cases[start_index..] {
+                case.consequent.execute(interpreter)?;
+
+                if interpreter.is_abrupt_completion() {
+                    break;
+                }
+            }
+
+            // `break` ends the switch, `continue` & `return` belong to the enclosing loop or function
+            if interpreter.completion() == Completion::Break {
+                interpreter.take_completion();
+            }
+
+            Ok(JsValue::Undefined)
+        })
+    }
+}
@@ -0,0 +1,24 @@
+use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+use crate::nodes::{AstExpression, GetSpan, TextSpan, Token};
+use crate::value::JsValue;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrowStatementNode {
+    pub token: Token,
+    pub expression: Box<AstExpression>,
+}
+
+impl Execute for ThrowStatementNode {
+    /// There is no `try`/`catch`, so thrown value always terminates the script,
+    /// it's reported the same way as runtime faults, e.g. `TypeError: message`
+    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
+        let value = self.expression.execute(interpreter)?;
+        return Err(value.to_js_string());
+    }
+}
+
+impl GetSpan for ThrowStatementNode {
+    fn get_span(&self) -> TextSpan {
+        self.token.span.join(&self.expression.get_span())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::interpreter::ast_interpreter::{Execute, Interpreter};
+use crate::nodes::{AstExpression, GetSpan, TextSpan, Token};
+use crate::scanner::TokenKind;
+use crate::value::JsValue;
+use crate::value::object::PrimitiveHint;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnaryExpressionNode {
+    pub operator: UnaryOperator,
+    pub argument: Box<AstExpression>,
+    /// Operator token, start of the expression
+    pub token: Token,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnaryOperator {
+    Minus,
+    Plus,
+    LogicalNot,
+    Typeof,
+    Void,
+}
+
+impl Execute for UnaryExpressionNode {
+    fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
+        let value = self.argument.execute(interpreter)?;
+
+        let result = match self.operator {
+            UnaryOperator::Minus => JsValue::Number(-interpreter.to_primitive(&value, PrimitiveHint::Number)?.to_number()),
+            UnaryOperator::Plus => JsValue::Number(interpreter.to_primitive(&value, PrimitiveHint::Number)?.to_number()),
+            UnaryOperator::LogicalNot => JsValue::Boolean(!value.to_bool()),
+            UnaryOperator::Typeof => JsValue::String(value.type_of().into()),
+            UnaryOperator::Void => JsValue::Undefined,
+        };
+
+        return Ok(result);
+    }
+}
+
+impl GetSpan for UnaryExpressionNode {
+    fn get_span(&self) -> TextSpan {
+        self.token.span.join(&self.argument.get_span())
+    }
+}
+
+impl TryFrom<&TokenKind> for UnaryOperator {
+    type Error = String;
+
+    fn try_from(value: &TokenKind) -> Result<Self, Self::Error> {
+        match value {
+            TokenKind::Minus => Ok(Self::Minus),
+            TokenKind::Plus => Ok(Self::Plus),
+            TokenKind::Exclamatory => Ok(Self::LogicalNot),
+            TokenKind::TypeofKeyword => Ok(Self::Typeof),
+            TokenKind::VoidKeyword => Ok(Self::Void),
+            _ => Err("Cannot convert token kind to unary operator".to_string()),
+        }
+    }
+}
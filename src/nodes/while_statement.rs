@@ -10,8 +10,12 @@ pub struct WhileStatementNode {
 
 impl Execute for WhileStatementNode {
     fn execute(&self, interpreter: &Interpreter) -> Result<JsValue, String> {
-        while self.condition.execute(interpreter).unwrap().to_bool() {
-            self.body.execute(interpreter).unwrap();
+        while self.condition.execute(interpreter)?.to_bool() {
+            self.body.execute(interpreter)?;
+
+            if interpreter.should_exit_loop() {
+                break;
+            }
         }
 
         Ok(JsValue::Undefined)
@@ -1,4 +1,5 @@
-use crate::scanner::{Scanner, TokenKind, Token};
+use std::rc::Rc;
+use crate::scanner::{Scanner, TokenKind, Token, Span};
 use ariadne::{ColorGenerator, Label, Report, ReportKind, Source};
 use crate::nodes::*;
 
@@ -7,6 +8,17 @@ pub struct Parser {
     current_token: Option<Token>,
     scanner: Scanner,
     source: String,
+    /// Errors are collected by `parse_tolerant` instead of being printed as they occur
+    is_error_tolerant: bool,
+    /// Errors which parsing recovered from inside the current statement, e.g. a missing comma between arguments
+    recovered_errors: Vec<ParseError>,
+}
+
+/// Syntax error found by `Parser::parse_tolerant`, span points at the token where parsing failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: TextSpan,
 }
 
 impl Default for Parser {
@@ -16,6 +28,8 @@ impl Default for Parser {
             current_token: None,
             scanner: Scanner::new("".to_string()),
             source: String::new(),
+            is_error_tolerant: false,
+            recovered_errors: vec![],
         }
     }
 }
@@ -27,26 +41,94 @@ impl Parser {
     }
 
     pub fn parse(&mut self, source: &str) -> Result<AstStatement, String> {
+        let (statements, _) = self.parse_statements_from(source, Scanner::new(source.to_string()))?;
+
+        return Ok(
+            AstStatement::ProgramStatement(ProgramNode { statements }),
+        );
+    }
+
+    /// Parses the whole source even if it has syntax errors, so all of them can be reported at once.
+    /// Top level statement which fails to parse is skipped up to the next statement boundary
+    pub fn parse_tolerant(&mut self, source: &str) -> (AstStatement, Vec<ParseError>) {
         self.source = source.to_string();
         self.scanner = Scanner::new(source.to_string());
+        self.is_error_tolerant = true;
 
         let mut statements: Vec<AstStatement> = vec![];
+        let mut errors: Vec<ParseError> = vec![];
 
-        self.current_token = self.scanner.next_token();
+        self.prev_token = None;
+        self.current_token = None;
+        self.next_token();
 
-        while let Some(token) = &self.current_token {
-            if let TokenKind::Comment(_) = token.token {
-                self.next_token();
-                continue;
+        while self.current_token.is_some() {
+            let statement = self.parse_statement();
+            errors.append(&mut self.recovered_errors);
+
+            match statement {
+                Ok(statement) => statements.push(statement),
+                Err(message) => {
+                    let span = match &self.current_token {
+                        Some(token) => token.span.clone(),
+                        None => self.create_span_from(self.prev_token.as_ref().map_or(Span::default(), |x| x.span.end)),
+                    };
+
+                    errors.push(ParseError { message, span });
+                    self.skip_to_statement_boundary();
+                }
+            }
+        }
+
+        self.is_error_tolerant = false;
+        return (AstStatement::ProgramStatement(ProgramNode { statements }), errors);
+    }
+
+    /// Skips tokens until the previous one ends a statement or the current one starts a statement,
+    /// at least one token is skipped, so parsing always moves forward
+    fn skip_to_statement_boundary(&mut self) {
+        self.next_token();
+
+        while let Some(token) = self.get_current_token() {
+            let is_statement_start = matches!(
+                token,
+                TokenKind::LetKeyword | TokenKind::ConstKeyword | TokenKind::FunctionKeyword | TokenKind::ClassKeyword
+                | TokenKind::IfKeyword | TokenKind::WhileKeyword | TokenKind::DoKeyword | TokenKind::ForKeyword
+                | TokenKind::SwitchKeyword | TokenKind::TryKeyword | TokenKind::ReturnKeyword | TokenKind::ThrowKeyword
+            );
+            let is_after_statement_end = matches!(
+                self.prev_token.as_ref().map(|x| &x.token),
+                Some(TokenKind::Semicolon) | Some(TokenKind::CloseBrace)
+            );
+
+            if is_statement_start || (is_after_statement_end && token != &TokenKind::CloseBrace) {
+                return;
             }
 
-            let statement = self.parse_statement().unwrap();
+            self.next_token();
+        }
+    }
+
+    /// Parses top level statements which the scanner yields,
+    /// returns them together with positions of their first tokens
+    fn parse_statements_from(&mut self, source: &str, scanner: Scanner) -> Result<(Vec<AstStatement>, Vec<Span>), String> {
+        self.source = source.to_string();
+        self.scanner = scanner;
+
+        let mut statements: Vec<AstStatement> = vec![];
+        let mut starts: Vec<Span> = vec![];
+
+        self.prev_token = None;
+        self.current_token = None;
+        self.next_token();
+
+        while self.current_token.is_some() {
+            starts.push(self.get_current_span_start());
+            let statement = self.parse_statement()?;
             statements.push(statement);
         }
 
-        return Ok(
-            AstStatement::ProgramStatement(ProgramNode { statements }),
-        );
+        return Ok((statements, starts));
     }
 
     fn parse_statement(&mut self) -> Result<AstStatement, String> {
@@ -61,75 +143,155 @@ impl Parser {
             Some(TokenKind::ReturnKeyword) => self.parse_return_statement(),
             Some(TokenKind::ForKeyword) => self.parse_for_statement(),
             Some(TokenKind::BreakKeyword) => self.parse_break_statement(),
+            Some(TokenKind::ContinueKeyword) => self.parse_continue_statement(),
+            Some(TokenKind::SwitchKeyword) => self.parse_switch_statement(),
+            Some(TokenKind::ThrowKeyword) => self.parse_throw_statement(),
+            Some(TokenKind::Semicolon) => self.parse_empty_statement(),
             // Some(TokenKind::ClassKeyword) => self.parse_class_expression(),
             _ => self.parse_expression_statement(),
         }
     }
 
+    fn parse_empty_statement(&mut self) -> Result<AstStatement, String> {
+        let token = self.get_copy_current_token();
+        self.eat(&TokenKind::Semicolon)?;
+        return Ok(AstStatement::EmptyStatement(token));
+    }
+
     fn parse_break_statement(&mut self) -> Result<AstStatement, String> {
         let token = self.get_copy_current_token();
-        self.eat(&TokenKind::BreakKeyword);
+        self.eat(&TokenKind::BreakKeyword)?;
         self.eat_if_present(&TokenKind::Semicolon);
         return Ok(AstStatement::BreakStatement(token));
     }
 
+    fn parse_continue_statement(&mut self) -> Result<AstStatement, String> {
+        let token = self.get_copy_current_token();
+        self.eat(&TokenKind::ContinueKeyword)?;
+        self.eat_if_present(&TokenKind::Semicolon);
+        return Ok(AstStatement::ContinueStatement(token));
+    }
+
+    fn parse_switch_statement(&mut self) -> Result<AstStatement, String> {
+        let start = self.get_current_span_start();
+        self.eat(&TokenKind::SwitchKeyword)?;
+        self.eat(&TokenKind::OpenParen)?;
+        let discriminant = self.parse_expression()?;
+        self.eat(&TokenKind::CloseParen)?;
+        self.eat(&TokenKind::OpenBrace)?;
+
+        let mut cases: Vec<SwitchCaseNode> = vec![];
+
+        while !self.is_current_token_matches(&TokenKind::CloseBrace) {
+            let test = match self.get_current_token() {
+                Some(TokenKind::CaseKeyword) => {
+                    self.eat(&TokenKind::CaseKeyword)?;
+                    Some(self.parse_expression()?)
+                }
+                Some(TokenKind::DefaultKeyword) => {
+                    if cases.iter().any(|x| x.test.is_none()) {
+                        return Err("SyntaxError: More than one default clause in switch statement".to_string());
+                    }
+
+                    self.eat(&TokenKind::DefaultKeyword)?;
+                    None
+                }
+                _ => return Err(format!("Unexpected {}, expected \"case\" or \"default\"", self.describe_current_token())),
+            };
+
+            self.eat(&TokenKind::Colon)?;
+
+            let mut consequent: Vec<AstStatement> = vec![];
+
+            while !matches!(
+                self.get_current_token(),
+                None | Some(TokenKind::CaseKeyword | TokenKind::DefaultKeyword | TokenKind::CloseBrace)
+            ) {
+                consequent.push(self.parse_statement()?);
+            }
+
+            cases.push(SwitchCaseNode { test, consequent });
+        }
+
+        self.eat(&TokenKind::CloseBrace)?;
+
+        return Ok(AstStatement::SwitchStatement(SwitchStatementNode {
+            discriminant: Box::new(discriminant),
+            cases,
+            span: self.create_span_from(start),
+        }));
+    }
+
     fn parse_class_expression(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::ClassKeyword);
+        self.eat(&TokenKind::ClassKeyword)?;
 
         let class_name_identifier = self.parse_identifier()?;
         let mut extends_identifier: Option<Box<IdentifierNode>> = None;
 
         if let Some(TokenKind::ExtendsKeyword) = self.get_current_token() {
             self.next_token();
-            let extends_identifier_candidate = self.parse_identifier().unwrap();
+            let extends_identifier_candidate = self.parse_identifier()?;
             extends_identifier = Some(Box::new(extends_identifier_candidate));
         }
 
-        let class_methods: Vec<Box<ClassMethodNode>> = self
-            .parse_class_body()?
-            .iter_mut()
-            .map(|x| Box::new(x.clone()))
-            .collect();
+        let (class_methods, class_fields) = self.parse_class_body()?;
 
         return Ok(
             AstExpression::ClassDeclaration(ClassDeclarationNode {
                 name: Box::new(class_name_identifier),
                 parent: extends_identifier,
-                methods: class_methods,
+                methods: class_methods.into_iter().map(Box::new).collect(),
+                fields: Rc::new(class_fields),
             }),
         );
     }
 
-    fn parse_class_body(&mut self) -> Result<Vec<ClassMethodNode>, String> {
-        self.eat(&TokenKind::OpenBrace);
+    /// Member name followed by `(` starts a method, otherwise it's a field
+    fn parse_class_body(&mut self) -> Result<(Vec<ClassMethodNode>, Vec<ClassFieldNode>), String> {
+        self.eat(&TokenKind::OpenBrace)?;
 
         let mut class_methods: Vec<ClassMethodNode> = vec![];
+        let mut class_fields: Vec<ClassFieldNode> = vec![];
 
         while let Some(TokenKind::Identifier(_)) = self.get_current_token() {
-            class_methods.push(self.parse_class_method()?);
+            let name = self.parse_identifier()?;
+
+            if self.is_current_token_matches(&TokenKind::OpenParen) {
+                class_methods.push(ClassMethodNode { function_signature: self.parse_function_signature_after_name(name)? });
+            } else {
+                class_fields.push(self.parse_class_field(name)?);
+            }
         }
 
-        self.eat(&TokenKind::CloseBrace);
+        self.eat(&TokenKind::CloseBrace)?;
 
-        return Ok(class_methods);
+        return Ok((class_methods, class_fields));
     }
 
-    fn parse_class_method(&mut self) -> Result<ClassMethodNode, String> {
-        Ok(ClassMethodNode { function_signature: self.parse_function_signature()? })
+    fn parse_class_field(&mut self, name: IdentifierNode) -> Result<ClassFieldNode, String> {
+        let mut value = None;
+
+        if self.is_current_token_matches(&TokenKind::Equal) {
+            self.next_token();
+            value = Some(self.parse_expression()?);
+        }
+
+        self.eat_if_present(&TokenKind::Semicolon);
+        return Ok(ClassFieldNode { name, value });
     }
 
     fn parse_for_statement(&mut self) -> Result<AstStatement, String> {
-        self.eat(&TokenKind::ForKeyword);
-        self.eat(&TokenKind::OpenParen);
+        self.eat(&TokenKind::ForKeyword)?;
+        self.eat(&TokenKind::OpenParen)?;
 
-        let init = self.parse_statement().unwrap();
-        let test = self.parse_expression().unwrap();
+        let init = self.parse_statement()?;
+        let test = self.parse_expression()?;
 
-        self.eat(&TokenKind::Semicolon);
-        let update = self.parse_expression().unwrap();
+        self.eat(&TokenKind::Semicolon)?;
+        let update = self.parse_expression()?;
 
-        self.eat(&TokenKind::CloseParen);
-        let body = self.parse_statement().unwrap();
+        self.eat(&TokenKind::CloseParen)?;
+        let body = self.parse_statement()?;
 
         return Ok(
             AstStatement::ForStatement(ForStatementNode {
@@ -142,8 +304,8 @@ impl Parser {
     }
 
     fn parse_return_statement(&mut self) -> Result<AstStatement, String> {
-        self.eat(&TokenKind::ReturnKeyword);
-        let expression = self.parse_expression().unwrap();
+        self.eat(&TokenKind::ReturnKeyword)?;
+        let expression = self.parse_expression()?;
         self.eat_if_present(&TokenKind::Semicolon);
         return Ok(
             AstStatement::ReturnStatement(ReturnStatementNode {
@@ -152,53 +314,86 @@ impl Parser {
         );
     }
 
+    fn parse_throw_statement(&mut self) -> Result<AstStatement, String> {
+        let token = self.get_copy_current_token();
+        self.eat(&TokenKind::ThrowKeyword)?;
+        let expression = self.parse_expression()?;
+        self.eat_if_present(&TokenKind::Semicolon);
+        return Ok(AstStatement::ThrowStatement(ThrowStatementNode { token, expression: Box::new(expression) }));
+    }
+
     fn parse_function_declaration(&mut self) -> Result<AstStatement, String> {
-        self.eat(&TokenKind::FunctionKeyword);
+        self.eat(&TokenKind::FunctionKeyword)?;
         Ok(AstStatement::FunctionDeclaration(FunctionDeclarationNode { function_signature: self.parse_function_signature()? }))
     }
 
     fn parse_function_signature(&mut self) -> Result<FunctionSignature, String> {
-        let function_name = self.parse_identifier().expect("Expected a function name");
+        let function_name = self.parse_identifier()?;
+        return self.parse_function_signature_after_name(function_name);
+    }
 
-        self.eat(&TokenKind::OpenParen);
-        let arguments =
-            self.parse_comma_sequence(&TokenKind::CloseParen, &Self::parse_function_argument)?;
-        self.eat(&TokenKind::CloseParen);
+    fn parse_function_signature_after_name(&mut self, function_name: IdentifierNode) -> Result<FunctionSignature, String> {
+        self.eat(&TokenKind::OpenParen)?;
+        let arguments = self.parse_function_arguments()?;
 
-        let body = self.parse_statement().unwrap();
+        let body = self.parse_statement()?;
 
         return Ok(FunctionSignature {
             name: Box::new(function_name),
             arguments: arguments,
-            body: Box::new(body),
+            body: Rc::new(body),
         });
     }
 
+    fn parse_function_arguments(&mut self) -> Result<Vec<FunctionArgument>, String> {
+        let arguments =
+            self.parse_comma_sequence(&TokenKind::CloseParen, "parameters", &Self::parse_function_argument)?;
+        self.eat(&TokenKind::CloseParen)?;
+
+        if arguments.iter().rev().skip(1).any(|x| x.is_rest) {
+            return Err("Rest parameter must be last formal parameter".to_string());
+        }
+
+        return Ok(arguments);
+    }
+
     fn parse_function_argument(&mut self) -> Result<FunctionArgument, String> {
-        let name = self.parse_identifier().unwrap();
+        if self.is_current_token_matches(&TokenKind::DotDotDot) {
+            self.eat(&TokenKind::DotDotDot)?;
+
+            return Ok(FunctionArgument {
+                name: self.parse_identifier()?,
+                default_value: None,
+                is_rest: true,
+            });
+        }
+
+        let name = self.parse_identifier()?;
 
         if self.is_current_token_matches(&TokenKind::Equal) {
-            self.eat(&TokenKind::Equal);
-            let default_value = self.parse_expression().unwrap();
+            self.eat(&TokenKind::Equal)?;
+            let default_value = self.parse_expression()?;
 
             return Ok(FunctionArgument {
                 name,
                 default_value: Some(Box::new(default_value)),
+                is_rest: false,
             });
         }
 
         return Ok(FunctionArgument {
             name,
             default_value: None,
+            is_rest: false,
         });
     }
 
     fn parse_while_statement(&mut self) -> Result<AstStatement, String> {
-        self.eat(&TokenKind::WhileKeyword);
-        self.eat(&TokenKind::OpenParen);
-        let condition = self.parse_expression().unwrap();
-        self.eat(&TokenKind::CloseParen);
-        let body = self.parse_statement().unwrap();
+        self.eat(&TokenKind::WhileKeyword)?;
+        self.eat(&TokenKind::OpenParen)?;
+        let condition = self.parse_expression()?;
+        self.eat(&TokenKind::CloseParen)?;
+        let body = self.parse_statement()?;
         return Ok(
             AstStatement::WhileStatement(WhileStatementNode {
                 condition: Box::new(condition),
@@ -219,20 +414,22 @@ impl Parser {
 
     fn parse_block_statement(&mut self) -> Result<AstStatement, String> {
         let mut statements: Vec<AstStatement> = vec![];
+        let start = self.get_current_span_start();
 
-        self.eat(&TokenKind::OpenBrace);
+        self.eat(&TokenKind::OpenBrace)?;
 
-        while let Some(token) = &self.current_token {
-            if &token.token == &TokenKind::CloseBrace {
-                self.eat(&TokenKind::CloseBrace);
-                break;
+        while !self.is_current_token_matches(&TokenKind::CloseBrace) {
+            if self.current_token.is_none() {
+                return Err("Unexpected end of input, expected \"}\"".to_string());
             }
 
-            statements.push(self.parse_statement().unwrap());
+            statements.push(self.parse_statement()?);
         }
 
+        self.eat(&TokenKind::CloseBrace)?;
+
         return Ok(
-            AstStatement::BlockStatement(BlockStatementNode { statements }),
+            AstStatement::BlockStatement(BlockStatementNode { statements, span: self.create_span_from(start) }),
         );
     }
 
@@ -246,7 +443,7 @@ impl Parser {
 
         return Err(format!(
             "Expected identifier, but got {}",
-            self.get_current_token().unwrap()
+            self.describe_current_token()
         ));
     }
 
@@ -268,11 +465,7 @@ impl Parser {
 
             let value = if self.is_current_token_matches(&TokenKind::Equal) {
                 self.next_token();
-                let expression = self
-                    .parse_expression()
-                    .expect("Expect variable initialization expression");
-
-                Some(Box::new(expression))
+                Some(Box::new(self.parse_expression()?))
             } else {
                 None
             };
@@ -295,7 +488,7 @@ impl Parser {
         let expression = self.parse_expression()?;
 
         if self.get_current_token().is_some() && self.is_current_token_matches(&TokenKind::Semicolon) {
-            self.eat(&TokenKind::Semicolon);
+            self.eat(&TokenKind::Semicolon)?;
         }
 
         return Ok(expression.into());
@@ -312,6 +505,7 @@ impl Parser {
             &TokenKind::MinusEqual,
             &TokenKind::DivEqual,
             &TokenKind::MulEqual,
+            &TokenKind::PercentEqual,
             &TokenKind::MulMulEqual,
             &TokenKind::Equal,
         ];
@@ -321,8 +515,9 @@ impl Parser {
                 break;
             }
             let operator = AssignmentOperator::try_from(token).unwrap();
+            Self::validate_assignment_target(&result_expression, operator == AssignmentOperator::Equal)?;
             self.next_token();
-            let right = self.parse_expression().unwrap();
+            let right = self.parse_expression()?;
             result_expression =
                 AstExpression::AssignmentExpression(AssignmentExpressionNode {
                     left: Box::new(result_expression),
@@ -335,6 +530,45 @@ impl Parser {
         return Ok(result_expression);
     }
 
+    /// Array & object literals on the left of `=` are destructuring patterns, e.g. `[a, b] = [b, a]`,
+    /// their items must be assignment targets themselves, possibly with a default value or a rest element
+    fn validate_assignment_target(target: &AstExpression, is_pattern_allowed: bool) -> Result<(), String> {
+        match target {
+            AstExpression::Identifier(_) | AstExpression::MemberExpression(_) => Ok(()),
+            AstExpression::ArrayExpression(node) if is_pattern_allowed => {
+                for (index, item) in node.items.iter().enumerate() {
+                    match item {
+                        Some(AstExpression::SpreadElement(rest)) => {
+                            if index != node.items.len() - 1 {
+                                return Err("SyntaxError: Rest element must be last element".to_string());
+                            }
+
+                            Self::validate_assignment_target(&rest.argument, true)?;
+                        }
+                        Some(item) => Self::validate_pattern_item(item)?,
+                        None => {}
+                    }
+                }
+
+                Ok(())
+            }
+            AstExpression::ObjectExpression(node) if is_pattern_allowed => {
+                node.properties.iter().try_for_each(|x| Self::validate_pattern_item(&x.value))
+            }
+            _ => Err("SyntaxError: Invalid left-hand side in assignment".to_string()),
+        }
+    }
+
+    /// Target inside a destructuring pattern, `target = default` is allowed there
+    fn validate_pattern_item(item: &AstExpression) -> Result<(), String> {
+        match item {
+            AstExpression::AssignmentExpression(node) if node.operator == AssignmentOperator::Equal => {
+                Self::validate_assignment_target(&node.left, true)
+            }
+            _ => Self::validate_assignment_target(item, true),
+        }
+    }
+
     fn parse_expression(&mut self) -> Result<AstExpression, String> {
         let expression = self.parse_logical_or_expression()?;
         let expression = self.parse_assignment_expression(expression)?;
@@ -363,8 +597,44 @@ impl Parser {
         );
     }
 
+    /// `**` is right associative, `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    /// Its base can't be a unary expression without parentheses, since `-2 ** 2` is ambiguous
     fn parse_exponentiation_expression(&mut self) -> Result<AstExpression, String> {
-        return self.parse_binary_expression(&Self::parse_primary_expression, &[TokenKind::MulMul]);
+        let is_unary_base = self.get_current_token().is_some_and(|x| UnaryOperator::try_from(x).is_ok());
+        let base = self.parse_unary_expression()?;
+
+        if !self.is_current_token_matches(&TokenKind::MulMul) {
+            return Ok(base);
+        }
+
+        if is_unary_base {
+            return Err("SyntaxError: Unary operator used immediately before exponentiation expression, parentheses must be used to disambiguate operator precedence".to_string());
+        }
+
+        self.eat(&TokenKind::MulMul)?;
+        let exponent = self.parse_exponentiation_expression()?;
+
+        return Ok(AstExpression::BinaryExpression(BinaryExpressionNode {
+            left: Box::new(base),
+            operator: BinaryOperator::MulMul,
+            right: Box::new(exponent),
+        }));
+    }
+
+    fn parse_unary_expression(&mut self) -> Result<AstExpression, String> {
+        let Some(Ok(operator)) = self.get_current_token().map(UnaryOperator::try_from) else {
+            return self.parse_primary_expression();
+        };
+
+        let token = self.get_copy_current_token();
+        self.next_token();
+        let argument = self.parse_unary_expression()?;
+
+        return Ok(AstExpression::UnaryExpression(UnaryExpressionNode {
+            operator,
+            argument: Box::new(argument),
+            token,
+        }));
     }
 
     //    fn function_call_new_computed_member_access(&mut self) -> Result<Node, String> {
@@ -383,6 +653,8 @@ impl Parser {
                 TokenKind::LessThanOrEqual,
                 TokenKind::MoreThan,
                 TokenKind::MoreThanOrEqual,
+                TokenKind::InstanceOfKeyword,
+                TokenKind::InKeyword,
             ],
         );
     }
@@ -397,9 +669,11 @@ impl Parser {
         );
     }
 
+    /// Parses items separated by commas up to `stop_token`, `items` names them in errors, e.g. "arguments"
     fn parse_comma_sequence<T>(
         &mut self,
         stop_token: &TokenKind,
+        items: &str,
         cb: &impl Fn(&mut Self) -> Result<T, String>,
     ) -> Result<Vec<T>, String> {
         let mut sequence = vec![];
@@ -411,7 +685,12 @@ impl Parser {
             }
 
             if !is_first {
-                self.eat(&TokenKind::Comma);
+                self.eat_list_comma(stop_token, items)?;
+
+                // trailing comma, e.g. `f(a, b,)`
+                if self.is_current_token_matches(stop_token) {
+                    break;
+                }
             }
 
             let expr = cb(self)?;
@@ -427,7 +706,7 @@ impl Parser {
         side_expression_fn: &impl Fn(&mut Self) -> Result<AstExpression, String>,
         tokens: &[TokenKind],
     ) -> Result<AstExpression, String> {
-        let mut left = side_expression_fn(self);
+        let mut left = side_expression_fn(self)?;
 
         while let Some(token) = self.get_current_token() {
             if !tokens.contains(&token) {
@@ -435,17 +714,15 @@ impl Parser {
             }
             let operator = BinaryOperator::try_from(token).unwrap();
             self.next_token();
-            let right = side_expression_fn(self);
-            left = Ok(
-                AstExpression::BinaryExpression(BinaryExpressionNode {
-                    left: Box::new(left.unwrap()),
-                    operator: operator,
-                    right: Box::new(right.unwrap()),
-                }),
-            );
+            let right = side_expression_fn(self)?;
+            left = AstExpression::BinaryExpression(BinaryExpressionNode {
+                left: Box::new(left),
+                operator: operator,
+                right: Box::new(right),
+            });
         }
 
-        return left;
+        return Ok(left);
     }
 
     fn parse_conditional_expression(
@@ -453,9 +730,9 @@ impl Parser {
         expression: AstExpression,
     ) -> Result<AstExpression, String> {
         if self.is_current_token_matches(&TokenKind::Question) {
-            self.eat(&TokenKind::Question);
+            self.eat(&TokenKind::Question)?;
             let consequent = self.parse_expression()?;
-            self.eat(&TokenKind::Colon);
+            self.eat(&TokenKind::Colon)?;
             let alternative = self.parse_expression()?;
             return Ok(AstExpression::ConditionalExpression(
                 ConditionalExpressionNode {
@@ -472,74 +749,110 @@ impl Parser {
     fn parse_primary_expression(&mut self) -> Result<AstExpression, String> {
         match self.get_current_token() {
             Some(TokenKind::ClassKeyword) => return self.parse_class_expression(),
-            Some(TokenKind::OpenSquareBracket) => return self.parse_array_expression(),
             Some(TokenKind::FunctionKeyword) => return self.parse_function_expression(),
-            Some(TokenKind::Number(_)) => return self.parse_number_literal(),
-            Some(TokenKind::String(_)) => return self.parse_string_literal(),
-            Some(TokenKind::Boolean(_)) => return self.parse_bool_literal(),
             Some(TokenKind::Null) => return self.parse_null_literal(),
             Some(TokenKind::Undefined) => return self.parse_undefined_literal(),
-            Some(TokenKind::OpenParen) => return self.parse_call_expression(),
-            Some(TokenKind::Identifier(_)) | Some(TokenKind::ThisKeyword) => {
+            // literals can be followed by member access, e.g. `'abc'.length` or `[1, 2][0]`
+            Some(TokenKind::OpenParen)
+            | Some(TokenKind::Identifier(_))
+            | Some(TokenKind::ThisKeyword)
+            | Some(TokenKind::Number(_))
+            | Some(TokenKind::String(_))
+            | Some(TokenKind::Boolean(_))
+            | Some(TokenKind::OpenSquareBracket)
+            | Some(TokenKind::OpenBrace) => {
                 return self.parse_call_expression()
             }
             Some(TokenKind::NewKeyword) => return self.parse_new_expression(),
-            Some(TokenKind::OpenBrace) => return self.parse_object_literal(),
             _ => {
+                let Some(token) = self.current_token.as_ref() else {
+                    return Err("Unexpected end of input".to_string());
+                };
+
+                let (message, label, label_end) = match &token.token {
+                    // unterminated string spans the rest of the source, only its opening quote is pointed at
+                    TokenKind::UnterminatedString(_) => {
+                        ("Unterminated string literal".to_string(), "String starts here", token.span.start.row + 1)
+                    }
+                    kind => (format!("Unexpected token \"{}\"", kind.to_keyword()), "Unexpected token", token.span.end.row),
+                };
+
+                if self.is_error_tolerant {
+                    return Err(message);
+                }
+
                 let mut colors = ColorGenerator::new();
-                let token = self.current_token.as_ref().unwrap();
 
                 Report::build(ReportKind::Error, (), token.span.start.row)
                     .with_message("Unexpected token found")
                     .with_label(
-                        Label::new(token.span.start.row..token.span.end.row)
-                            .with_message("Unexpected token")
+                        Label::new(token.span.start.row..label_end)
+                            .with_message(label)
                             .with_color(colors.next()),
                     )
                     .finish()
                     .print(Source::from(&self.source))
                     .unwrap();
 
-                unimplemented!()
+                return Err(message);
             }
         }
     }
 
     fn parse_array_expression(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::OpenSquareBracket);
-        let items: Vec<AstExpression> = self.parse_comma_sequence(&TokenKind::CloseSquareBracket, &Self::parse_primary_expression)?.into_iter().collect();
-        self.eat(&TokenKind::CloseSquareBracket);
-        Ok(AstExpression::ArrayExpression(ArrayExpressionNode { items }))
+        let start = self.get_current_span_start();
+        self.eat(&TokenKind::OpenSquareBracket)?;
+
+        let mut items: Vec<Option<AstExpression>> = vec![];
+
+        // unlike other comma separated lists, commas can follow each other to leave holes, e.g. `[1, , 3]`
+        while !self.is_current_token_matches(&TokenKind::CloseSquareBracket) {
+            if self.is_current_token_matches(&TokenKind::Comma) {
+                self.eat(&TokenKind::Comma)?;
+                items.push(None);
+                continue;
+            }
+
+            items.push(Some(self.parse_call_argument()?));
+
+            if !self.is_current_token_matches(&TokenKind::CloseSquareBracket) {
+                self.eat(&TokenKind::Comma)?;
+            }
+        }
+
+        self.eat(&TokenKind::CloseSquareBracket)?;
+        Ok(AstExpression::ArrayExpression(ArrayExpressionNode::new(items, self.create_span_from(start))))
     }
 
     fn parse_function_expression(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::FunctionKeyword);
-        self.eat(&TokenKind::OpenParen);
+        let start = self.get_current_span_start();
+        self.eat(&TokenKind::FunctionKeyword)?;
+        self.eat(&TokenKind::OpenParen)?;
 
-        let arguments =
-            self.parse_comma_sequence(&TokenKind::CloseParen, &Self::parse_function_argument)?;
-        self.eat(&TokenKind::CloseParen);
+        let arguments = self.parse_function_arguments()?;
 
-        let body = self.parse_statement().unwrap();
+        let body = self.parse_statement()?;
 
         return Ok(AstExpression::FunctionExpression(
             FunctionExpressionNode {
                 arguments: arguments,
-                body: Box::new(body),
+                body: Rc::new(body),
+                span: self.create_span_from(start),
             }),
         );
     }
 
     fn parse_this_expression(&mut self) -> Result<AstExpression, String> {
         let token = self.get_copy_current_token();
-        self.eat(&TokenKind::ThisKeyword);
+        self.eat(&TokenKind::ThisKeyword)?;
         return Ok(AstExpression::ThisExpression(ThisExpressionNode { token }));
     }
 
     fn parse_object_literal(&mut self) -> Result<AstExpression, String> {
         let mut properties: Vec<ObjectPropertyNode> = vec![];
+        let start = self.get_current_span_start();
 
-        self.eat(&TokenKind::OpenBrace);
+        self.eat(&TokenKind::OpenBrace)?;
 
         loop {
             if self.is_current_token_matches(&TokenKind::CloseBrace) {
@@ -547,7 +860,7 @@ impl Parser {
             }
 
             if properties.len() != 0 {
-                self.eat(&TokenKind::Comma);
+                self.eat_list_comma(&TokenKind::CloseBrace, "properties")?;
             }
 
             if self.is_current_token_matches(&TokenKind::CloseBrace) {
@@ -557,15 +870,22 @@ impl Parser {
             properties.push(self.parse_object_property()?);
         }
 
-        self.eat(&TokenKind::CloseBrace);
+        self.eat(&TokenKind::CloseBrace)?;
 
-        return Ok(AstExpression::ObjectExpression(ObjectExpressionNode { properties }));
+        return Ok(AstExpression::ObjectExpression(ObjectExpressionNode::new(properties, self.create_span_from(start))));
     }
 
     fn parse_object_property(&mut self) -> Result<ObjectPropertyNode, String> {
         let (is_computed, key) = self.parse_object_property_key()?;
-        self.eat(&TokenKind::Colon);
-        let value = self.parse_expression()?;
+
+        // shorthand `{ x }` is the same as `{ x: x }`
+        let value = match &key {
+            AstExpression::Identifier(_) if !is_computed && !self.is_current_token_matches(&TokenKind::Colon) => key.clone(),
+            _ => {
+                self.eat(&TokenKind::Colon)?;
+                self.parse_expression()?
+            }
+        };
 
         return Ok(ObjectPropertyNode {
             computed: is_computed,
@@ -577,9 +897,9 @@ impl Parser {
     fn parse_object_property_key(&mut self) -> Result<(bool, AstExpression), String> {
         return match &self.get_current_token() {
             Some(TokenKind::OpenSquareBracket) => {
-                self.eat(&TokenKind::OpenSquareBracket);
+                self.eat(&TokenKind::OpenSquareBracket)?;
                 let expression = self.parse_expression()?;
-                self.eat(&TokenKind::CloseSquareBracket);
+                self.eat(&TokenKind::CloseSquareBracket)?;
                 return Ok((true, expression));
             }
             Some(TokenKind::Identifier(_)) => Ok((false, self.parse_identifier()?.into())),
@@ -587,25 +907,25 @@ impl Parser {
             Some(TokenKind::Number(_)) => Ok((false, self.parse_number_literal()?)),
             _ => Err(format!(
                 "{} cannot be used as an object key",
-                self.get_current_token().unwrap().to_keyword()
+                self.describe_current_token()
             )),
         };
     }
 
     fn parse_new_expression(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::NewKeyword);
-        let expression = self.parse_call_expression()?;
+        self.eat(&TokenKind::NewKeyword)?;
+        let callee = self.parse_member_expression()?;
 
-        if let AstExpression::CallExpression(expression) = expression {
-            return Ok(
-                AstExpression::NewExpression(NewExpressionNode {
-                    callee: expression.callee,
-                    arguments: expression.params,
-                }),
-            );
-        }
+        self.eat(&TokenKind::OpenParen)?;
+        let arguments = self.parse_comma_sequence(&TokenKind::CloseParen, "arguments", &Self::parse_call_argument)?;
+        self.eat(&TokenKind::CloseParen)?;
 
-        return Err("".to_string());
+        return Ok(
+            AstExpression::NewExpression(NewExpressionNode {
+                callee: Box::new(callee),
+                arguments,
+            }),
+        );
     }
 
     fn parse_call_expression(&mut self) -> Result<AstExpression, String> {
@@ -613,12 +933,19 @@ impl Parser {
     }
 
     fn parse_member_expression(&mut self) -> Result<AstExpression, String> {
-        let mut literal = self.parse_literal()?;
+        let literal = self.parse_literal()?;
+        return self.parse_member_access_chain(literal, false);
+    }
+
+    /// Parses sequence of property accesses following the expression,
+    /// with `allow_calls` calls are also part of the chain, e.g. `a.b().c[0]()`
+    fn parse_member_access_chain(&mut self, expression: AstExpression, allow_calls: bool) -> Result<AstExpression, String> {
+        let mut literal = expression;
 
         loop {
             match self.get_current_token() {
                 Some(&TokenKind::Dot) => {
-                    self.eat(&TokenKind::Dot);
+                    self.eat(&TokenKind::Dot)?;
                     let property = self.parse_literal()?;
 
                     literal = AstExpression::MemberExpression(MemberExpressionNode {
@@ -628,9 +955,9 @@ impl Parser {
                     });
                 }
                 Some(&TokenKind::OpenSquareBracket) => {
-                    self.eat(&TokenKind::OpenSquareBracket);
+                    self.eat(&TokenKind::OpenSquareBracket)?;
                     let expression = self.parse_expression()?;
-                    self.eat(&TokenKind::CloseSquareBracket);
+                    self.eat(&TokenKind::CloseSquareBracket)?;
 
                     literal = AstExpression::MemberExpression(MemberExpressionNode {
                         computed: true,
@@ -638,6 +965,16 @@ impl Parser {
                         property: Box::new(expression),
                     });
                 }
+                Some(&TokenKind::OpenParen) if allow_calls && self.is_callee(&literal) => {
+                    self.eat(&TokenKind::OpenParen)?;
+                    let params = self.parse_comma_sequence(&TokenKind::CloseParen, "arguments", &Self::parse_call_argument)?;
+                    self.eat(&TokenKind::CloseParen)?;
+
+                    literal = AstExpression::CallExpression(CallExpressionNode {
+                        callee: Box::new(literal),
+                        params,
+                    });
+                }
                 _ => break,
             }
             // if let Some(&Token::Dot) = self.get_current_token() {}
@@ -646,22 +983,25 @@ impl Parser {
         return Ok(literal);
     }
 
-    fn parse_call_signature(&mut self) -> Result<AstExpression, String> {
-        let literal = self.parse_member_expression()?;
-
-        if self.is_callee(&literal) && self.is_current_token_matches(&TokenKind::OpenParen) {
-            self.eat(&TokenKind::OpenParen);
-            let params = self.parse_comma_sequence(&TokenKind::CloseParen, &Self::parse_expression)?;
-            self.eat(&TokenKind::CloseParen);
-            return Ok(
-                AstExpression::CallExpression(CallExpressionNode {
-                    callee: Box::new(literal),
-                    params,
-                }),
-            );
+    /// Argument of a call or `new` or an array item, possibly spread, e.g. `...values`
+    fn parse_call_argument(&mut self) -> Result<AstExpression, String> {
+        if !self.is_current_token_matches(&TokenKind::DotDotDot) {
+            return self.parse_expression();
         }
 
-        return Ok(literal);
+        let start = self.get_current_span_start();
+        self.eat(&TokenKind::DotDotDot)?;
+        let argument = self.parse_expression()?;
+
+        return Ok(AstExpression::SpreadElement(SpreadElementNode {
+            argument: Box::new(argument),
+            span: self.create_span_from(start),
+        }));
+    }
+
+    fn parse_call_signature(&mut self) -> Result<AstExpression, String> {
+        let literal = self.parse_literal()?;
+        return self.parse_member_access_chain(literal, true);
     }
 
     fn is_callee(&self, node: &AstExpression) -> bool {
@@ -669,6 +1009,7 @@ impl Parser {
             AstExpression::Identifier(_)
             | AstExpression::MemberExpression(_)
             | AstExpression::ThisExpression(_)
+            | AstExpression::CallExpression(_)
             | AstExpression::FunctionExpression(_) => true,
             _ => false,
         }
@@ -685,14 +1026,16 @@ impl Parser {
             Some(TokenKind::Identifier(_)) => return Ok(self.parse_identifier()?.into()),
             Some(TokenKind::FunctionKeyword) => return self.parse_function_expression(),
             Some(TokenKind::OpenParen) => return self.parse_paranthesised_expression(),
-            _ => unimplemented!(),
+            Some(TokenKind::OpenSquareBracket) => return self.parse_array_expression(),
+            Some(TokenKind::OpenBrace) => return self.parse_object_literal(),
+            _ => return Err(format!("Unexpected {}", self.describe_current_token())),
         }
     }
 
     fn parse_paranthesised_expression(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::OpenParen);
+        self.eat(&TokenKind::OpenParen)?;
         let expression = self.parse_expression();
-        self.eat(&TokenKind::CloseParen);
+        self.eat(&TokenKind::CloseParen)?;
         return expression;
     }
 
@@ -704,7 +1047,7 @@ impl Parser {
             return Ok(AstExpression::BooleanLiteral(BooleanLiteralNode { value, token, }));
         }
 
-        Err(format!("Expected boolean literal, but found \"{}\"", self.get_current_token().unwrap().to_keyword()).to_string())
+        Err(format!("Expected boolean literal, but found {}", self.describe_current_token()))
     }
 
     fn get_copy_current_token(&self) -> Token {
@@ -712,18 +1055,18 @@ impl Parser {
     }
 
     fn parse_null_literal(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::Null);
+        self.eat(&TokenKind::Null)?;
         return Ok(AstExpression::NullLiteral(self.get_copy_current_token()));
     }
 
     fn parse_undefined_literal(&mut self) -> Result<AstExpression, String> {
-        self.eat(&TokenKind::Undefined);
+        self.eat(&TokenKind::Undefined)?;
         return Ok(AstExpression::UndefinedLiteral(self.get_copy_current_token()));
     }
 
     fn parse_string_literal(&mut self) -> Result<AstExpression, String> {
         if let Some(TokenKind::String(str)) = self.get_current_token() {
-            let value = str.clone();
+            let value = str.as_str().into();
             let token = self.current_token.clone().unwrap();
             self.next_token();
             return Ok(AstExpression::StringLiteral(StringLiteralNode { value, token }));
@@ -731,7 +1074,7 @@ impl Parser {
 
         return Err(format!(
             "Expected string, but got: {}",
-            self.get_current_token().unwrap().to_keyword()
+            self.describe_current_token()
         ));
     }
 
@@ -745,24 +1088,41 @@ impl Parser {
 
         return Err(format!(
             "Expected number, but got: {}",
-            self.get_current_token().unwrap().to_keyword()
+            self.describe_current_token()
         ));
     }
 
+    fn get_current_span_start(&self) -> Span {
+        self.current_token.as_ref().map_or(Span::default(), |x| x.span.start)
+    }
+
+    /// Span from given start to the end of the last consumed token
+    fn create_span_from(&self, start: Span) -> TextSpan {
+        TextSpan {
+            start,
+            end: self.prev_token.as_ref().map_or(start, |x| x.span.end),
+        }
+    }
+
+    /// Moves to the next significant token, comments can appear between any tokens and are skipped
     fn next_token(&mut self) {
         self.prev_token = self.current_token.clone();
         self.current_token = self.scanner.next_token();
+
+        while let Some(Token { token: TokenKind::Comment(_), .. }) = &self.current_token {
+            self.current_token = self.scanner.next_token();
+        }
     }
 
     fn parse_if_statement(&mut self) -> Result<AstStatement, String> {
-        self.eat(&TokenKind::IfKeyword);
-        self.eat(&TokenKind::OpenParen);
+        self.eat(&TokenKind::IfKeyword)?;
+        self.eat(&TokenKind::OpenParen)?;
 
-        let condition = Box::new(self.parse_expression().expect("Error parsing if condition"));
+        let condition = Box::new(self.parse_expression()?);
 
-        self.eat(&TokenKind::CloseParen);
+        self.eat(&TokenKind::CloseParen)?;
 
-        let then_branch = Box::new(self.parse_statement().expect("Error parsing then branch"));
+        let then_branch = Box::new(self.parse_statement()?);
 
         let mut else_branch: Option<Box<AstStatement>> = None;
 
@@ -770,7 +1130,7 @@ impl Parser {
             self.next_token();
 
             else_branch = Some(Box::new(
-                self.parse_statement().expect("Error parsing else branch"),
+                self.parse_statement()?,
             ));
         }
 
@@ -783,31 +1143,94 @@ impl Parser {
         );
     }
 
-    fn eat(&mut self, token_kind: &TokenKind) {
-        if self.get_current_token().unwrap() == token_kind {
-            self.prev_token = self.current_token.clone();
+    fn eat(&mut self, token_kind: &TokenKind) -> Result<(), String> {
+        let Some(current_token) = self.current_token.as_ref() else {
+            return Err(format!("Unexpected end of input, expected \"{}\"", token_kind.to_keyword()));
+        };
+
+        if &current_token.token == token_kind {
             self.next_token();
-        } else {
-            let current_token = self.current_token.as_ref().unwrap();
+            return Ok(());
+        }
 
-            let error_message = format!(
-                "Expected token \"{}\", but got: {:?}",
-                token_kind.to_keyword(),
-                current_token.token.to_keyword()
-            );
+        let error_message = format!(
+            "Expected token \"{}\", but got: {:?}",
+            token_kind.to_keyword(),
+            current_token.token.to_keyword()
+        );
+
+        if !self.is_error_tolerant {
+            self.print_unexpected_token(current_token, &error_message);
+        }
+
+        return Err(error_message);
+    }
+
+    fn print_unexpected_token(&self, token: &Token, message: &str) {
+        Report::build(ReportKind::Error, (), token.span.start.row)
+            .with_message("Unexpected token found")
+            .with_label(
+                Label::new(token.span.start.row..token.span.end.row)
+                    .with_message(message),
+            )
+            .finish()
+            .print(Source::from(self.source.clone()))
+            .unwrap();
+    }
+
+    /// Comma between items of a list ending with `stop_token`, e.g. `f(a b)` misses one between arguments.
+    /// Error tolerant parser records the missing comma & skips to the next item or the end of the list,
+    /// so errors after it are still reported
+    fn eat_list_comma(&mut self, stop_token: &TokenKind, items: &str) -> Result<(), String> {
+        if self.is_current_token_matches(&TokenKind::Comma) {
+            self.next_token();
+            return Ok(());
+        }
+
+        let Some(current_token) = self.current_token.clone() else {
+            return Err(format!("Unexpected end of input, expected \",\" or \"{}\"", stop_token.to_keyword()));
+        };
 
-            Report::build(ReportKind::Error, (), current_token.span.start.row)
-                .with_message("Unexpected token found")
-                .with_label(
-                    Label::new(current_token.span.start.row..current_token.span.end.row)
-                        .with_message(&error_message),
-                )
-                .finish()
-                .print(Source::from(self.source.clone()))
-                .unwrap();
+        let message = format!("Expected ',' between {items}");
 
-            panic!("{error_message}");
+        if !self.is_error_tolerant {
+            self.print_unexpected_token(&current_token, &message);
+            return Err(message);
         }
+
+        if !self.skip_list_item(stop_token) {
+            return Err(message);
+        }
+
+        self.recovered_errors.push(ParseError { message, span: current_token.span });
+        self.eat_if_present(&TokenKind::Comma);
+        return Ok(());
+    }
+
+    /// Skips tokens up to the next `,` or `stop_token` outside of nested brackets,
+    /// gives up at the end of the statement, so the whole statement is reported instead
+    fn skip_list_item(&mut self, stop_token: &TokenKind) -> bool {
+        let mut depth = 0;
+
+        while let Some(token) = self.get_current_token() {
+            match token {
+                TokenKind::Comma if depth == 0 => return true,
+                token if depth == 0 && token == stop_token => return true,
+                TokenKind::OpenParen | TokenKind::OpenSquareBracket | TokenKind::OpenBrace => depth += 1,
+                TokenKind::CloseParen | TokenKind::CloseSquareBracket | TokenKind::CloseBrace if depth > 0 => depth -= 1,
+                TokenKind::CloseParen | TokenKind::CloseSquareBracket | TokenKind::CloseBrace | TokenKind::Semicolon if depth == 0 => return false,
+                _ => {}
+            }
+
+            self.next_token();
+        }
+
+        return false;
+    }
+
+    /// Current token for error messages, parser can run out of tokens in the middle of a statement
+    fn describe_current_token(&self) -> String {
+        self.get_current_token().map_or("end of input".to_string(), |x| format!("\"{}\"", x.to_keyword()))
     }
 
     fn is_current_token_matches(&self, token_kind: &TokenKind) -> bool {
@@ -821,3 +1244,155 @@ impl Parser {
         }
     }
 }
+
+/// Replacement of the `start..end` character range of a source with `text`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Single edit turning `old` into `new`, it replaces everything between their common prefix & suffix
+    pub fn between(old: &str, new: &str) -> Self {
+        let old: Vec<char> = old.chars().collect();
+        let new: Vec<char> = new.chars().collect();
+
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+
+        return Self {
+            start: prefix,
+            end: old.len() - suffix,
+            text: new[prefix..new.len() - suffix].iter().collect(),
+        };
+    }
+
+    pub fn apply(&self, source: &str) -> Result<String, String> {
+        let byte_offset = |position: usize| {
+            source.char_indices()
+                .map(|(offset, _)| offset)
+                .chain(std::iter::once(source.len()))
+                .nth(position)
+                .ok_or(format!("Edit position {position} is outside of the source"))
+        };
+
+        if self.start > self.end {
+            return Err(format!("Edit start {} is after its end {}", self.start, self.end));
+        }
+
+        let (start, end) = (byte_offset(self.start)?, byte_offset(self.end)?);
+        return Ok(format!("{}{}{}", &source[..start], self.text, &source[end..]));
+    }
+}
+
+/// Source together with its AST, which is kept up to date with edits of the source, e.g. in an editor.
+/// Top level statements before an edit are reused and only the rest of the source is parsed again.
+pub struct ParsedSource {
+    source: String,
+    ast: AstStatement,
+    /// Position of the first token of every top level statement
+    statement_starts: Vec<Span>,
+}
+
+impl ParsedSource {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser::default();
+        let (statements, statement_starts) = parser.parse_statements_from(source, Scanner::new(source.to_string()))?;
+
+        return Ok(Self {
+            source: source.to_string(),
+            ast: AstStatement::ProgramStatement(ProgramNode { statements }),
+            statement_starts,
+        });
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn ast(&self) -> &AstStatement {
+        &self.ast
+    }
+
+    /// Applies edit & parses the affected statements, if the new source has a syntax error nothing is changed.
+    /// Returns how many top level statements were reused.
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> Result<usize, String> {
+        let source = edit.apply(&self.source)?;
+
+        let AstStatement::ProgramStatement(program) = &mut self.ast else {
+            unreachable!("ParsedSource always holds a program");
+        };
+
+        // Parsing of a statement depends on its tokens & the first token of the next statement,
+        // so a statement is reused only if the statement after the next one starts before the edit
+        let reused_count = (0..program.statements.len())
+            .take_while(|i| self.statement_starts.get(i + 2).is_some_and(|x| x.row < edit.start))
+            .count();
+
+        let resume_at = self.statement_starts.get(reused_count).copied().unwrap_or_default();
+        let mut parser = Parser::default();
+        let (statements, starts) = parser.parse_statements_from(&source, Scanner::starting_at(&source, resume_at))?;
+
+        self.source = source;
+        program.statements.truncate(reused_count);
+        program.statements.extend(statements);
+        self.statement_starts.truncate(reused_count);
+        self.statement_starts.extend(starts);
+
+        return Ok(reused_count);
+    }
+}
+
+#[test]
+fn edited_source_reuses_statements_before_the_edit() {
+    let mut parsed = ParsedSource::parse("let a = 1;\nlet b = 2;\nlet c = a + b;\nc;").unwrap();
+
+    let reused_count = parsed.apply_edit(&TextEdit { start: 35, end: 35, text: " * 2".to_string() }).unwrap();
+    assert_eq!(reused_count, 1);
+    assert_eq!(parsed.source(), "let a = 1;\nlet b = 2;\nlet c = a + b * 2;\nc;");
+    assert_eq!(parsed.ast(), &Parser::parse_code_to_ast(parsed.source()).unwrap());
+
+    let reused_count = parsed.apply_edit(&TextEdit { start: 8, end: 9, text: "10".to_string() }).unwrap();
+    assert_eq!(reused_count, 0);
+    assert_eq!(parsed.ast(), &Parser::parse_code_to_ast("let a = 10;\nlet b = 2;\nlet c = a + b * 2;\nc;").unwrap());
+
+    assert!(parsed.apply_edit(&TextEdit { start: 44, end: 44, text: "let".to_string() }).is_err());
+    assert_eq!(parsed.source(), "let a = 10;\nlet b = 2;\nlet c = a + b * 2;\nc;");
+}
+
+#[test]
+fn edit_between_sources_replaces_only_the_changed_part() {
+    assert_eq!(TextEdit::between("let a = 1;", "let a = 10;"), TextEdit { start: 9, end: 9, text: "0".to_string() });
+    assert_eq!(TextEdit::between("aaa", "aa"), TextEdit { start: 2, end: 3, text: "".to_string() });
+    assert_eq!(TextEdit::between("f(ä);", "f(ö);"), TextEdit { start: 2, end: 3, text: "ö".to_string() });
+
+    let (old, new) = ("let a = 1;\nlet b = a;", "let a = 1;\nlet b = a * 2;\nb;");
+    assert_eq!(TextEdit::between(old, new).apply(old), Ok(new.to_string()));
+}
+
+#[test]
+fn missing_commas_are_reported_and_recovered_from() {
+    let (ast, errors) = Parser::default().parse_tolerant("f(a b, g(c d));\nlet o = { x: 1 y: 2 };\nlet = 3;");
+    let errors: Vec<(&str, usize, usize)> = errors.iter().map(|x| (x.message.as_str(), x.span.start.line, x.span.start.row)).collect();
+
+    assert_eq!(errors, vec![
+        ("Expected ',' between arguments", 0, 4),
+        ("Expected ',' between arguments", 0, 11),
+        ("Expected ',' between properties", 1, 31),
+        ("Identifier is missing in variable declaration", 2, 43),
+    ]);
+    let print = |ast: &AstStatement| crate::node::Printer::compact().print(ast);
+    assert_eq!(print(&ast), print(&Parser::parse_code_to_ast("f(a, g(c));\nlet o = { x: 1 };").unwrap()));
+
+    assert_eq!(Parser::parse_code_to_ast("f(a b)"), Err("Expected ',' between arguments".to_string()));
+}
+
+#[test]
+fn unterminated_string_is_reported() {
+    let (_, errors) = Parser::default().parse_tolerant("let a = 1;\nlet s = 'abc;\nlet b = 2;");
+    let errors: Vec<(&str, usize)> = errors.iter().map(|x| (x.message.as_str(), x.span.start.line)).collect();
+
+    assert_eq!(errors, vec![("Unterminated string literal", 1)]);
+}
@@ -0,0 +1,320 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::interpreter::engine::BACKEND_NAME;
+
+pub const PROJECT_CONFIG_FILE_NAME: &str = "rustjs.toml";
+
+/// How symbol checker warnings are treated
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum WarningLevel {
+    /// Warnings are not printed
+    Allow,
+    #[default]
+    Warn,
+    /// Warnings are printed & prevent script from running
+    Deny,
+}
+
+/// Project settings from `rustjs.toml`, e.g.
+/// ```toml
+/// entry = ["src/utils.js", "src/main.js"]
+/// backend = "ast"
+/// warnings = "deny"
+/// ```
+/// Only flat `key = value` pairs with string or array of strings values are supported.
+#[derive(Debug, PartialEq)]
+pub struct ProjectConfig {
+    /// Files executed one after another, paths are relative to the config file
+    pub entry: Vec<PathBuf>,
+    pub warnings: WarningLevel,
+}
+
+impl ProjectConfig {
+    /// Reads config from the given directory, returns `None` if there is no config file
+    pub fn load(directory: &Path) -> Result<Option<Self>, String> {
+        let config_path = directory.join(PROJECT_CONFIG_FILE_NAME);
+
+        if !config_path.exists() {
+            return Ok(None);
+        }
+
+        let source = fs::read_to_string(&config_path)
+            .map_err(|e| format!("Cannot read {}: {e}", config_path.display()))?;
+
+        let mut config = Self::parse(&source)
+            .map_err(|e| format!("{}: {e}", config_path.display()))?;
+        config.entry = config.entry.iter().map(|x| directory.join(x)).collect();
+
+        return Ok(Some(config));
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut entry = None;
+        let mut warnings = WarningLevel::default();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=')
+                .ok_or(format!("line {}: expected `key = value`", line_index + 1))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "entry" => {
+                    let paths = if value.starts_with('[') {
+                        parse_string_array(value)
+                    } else {
+                        parse_string(value).map(|x| vec![x])
+                    };
+
+                    entry = Some(paths.map_err(|e| format!("line {}: {e}", line_index + 1))?);
+                }
+                "backend" => {
+                    let backend = parse_string(value).map_err(|e| format!("line {}: {e}", line_index + 1))?;
+
+                    if backend != BACKEND_NAME {
+                        return Err(format!("line {}: backend '{backend}' is not available, only '{BACKEND_NAME}' is supported", line_index + 1));
+                    }
+                }
+                "warnings" => {
+                    warnings = match parse_string(value).map_err(|e| format!("line {}: {e}", line_index + 1))?.as_str() {
+                        "allow" => WarningLevel::Allow,
+                        "warn" => WarningLevel::Warn,
+                        "deny" => WarningLevel::Deny,
+                        level => return Err(format!("line {}: unknown warnings level '{level}', expected allow, warn or deny", line_index + 1)),
+                    };
+                }
+                key => return Err(format!("line {}: unknown key '{key}'", line_index + 1)),
+            }
+        }
+
+        let entry: Vec<PathBuf> = entry
+            .ok_or("entry is not specified".to_string())?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+
+        if entry.is_empty() {
+            return Err("entry should contain at least one file".to_string());
+        }
+
+        return Ok(Self { entry, warnings });
+    }
+}
+
+/// Part of the line before a `#` comment, `#` inside of a quoted string is a part of the value
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+
+    for (index, char) in line.char_indices() {
+        match quote {
+            None if char == '#' => return &line[..index],
+            None if char == '"' || char == '\'' => quote = Some(char),
+            Some(open) if char == open => quote = None,
+            _ => {}
+        }
+    }
+
+    return line;
+}
+
+fn parse_string(value: &str) -> Result<String, String> {
+    let is_quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+
+    if !is_quoted {
+        return Err(format!("expected quoted string, but got {value}"));
+    }
+
+    return Ok(value[1..value.len() - 1].to_string());
+}
+
+fn parse_string_array(value: &str) -> Result<Vec<String>, String> {
+    let items = value.strip_prefix('[')
+        .and_then(|x| x.strip_suffix(']'))
+        .ok_or(format!("expected array of strings, but got {value}"))?;
+
+    items.split(',')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .map(parse_string)
+        .collect()
+}
+
+/// Expands directories into the `.js` files they contain, sorted by name
+pub fn collect_script_paths(paths: &[&String]) -> Result<Vec<PathBuf>, String> {
+    let mut result = vec![];
+
+    for path in paths {
+        let path = PathBuf::from(path);
+
+        if !path.is_dir() {
+            result.push(path);
+            continue;
+        }
+
+        let mut scripts: Vec<PathBuf> = fs::read_dir(&path)
+            .map_err(|e| format!("Cannot read directory {}: {e}", path.display()))?
+            .filter_map(|x| x.ok().map(|x| x.path()))
+            .filter(|x| x.is_file() && x.extension().is_some_and(|x| x == "js"))
+            .collect();
+
+        scripts.sort();
+        result.extend(scripts);
+    }
+
+    return Ok(result);
+}
+
+/// Expands paths of files to lint into `.js` files. Directories are walked recursively skipping hidden ones,
+/// paths with `*` or `?` are glob patterns where `**` matches any number of directories, e.g. `src/**/*.js`
+pub fn collect_lint_paths(paths: &[&String]) -> Result<Vec<PathBuf>, String> {
+    let mut result = vec![];
+
+    for path in paths {
+        if !path.contains(['*', '?']) {
+            let path = PathBuf::from(path);
+
+            if path.is_dir() {
+                walk_scripts(&path, &mut result)?;
+            } else {
+                result.push(path);
+            }
+
+            continue;
+        }
+
+        let pattern: Vec<&str> = path.split('/').filter(|x| !x.is_empty() && *x != ".").collect();
+        let base: Vec<&str> = pattern.iter().take_while(|x| !x.contains(['*', '?'])).copied().collect();
+
+        // root of an absolute pattern is not a component, but walking has to start from it
+        let base_directory: PathBuf = match (path.starts_with('/'), base.is_empty()) {
+            (true, _) => std::iter::once("/").chain(base).collect(),
+            (false, true) => PathBuf::from("."),
+            (false, false) => base.iter().collect(),
+        };
+
+        let mut scripts = vec![];
+
+        if base_directory.is_dir() {
+            walk_scripts(&base_directory, &mut scripts)?;
+        }
+
+        let matched: Vec<PathBuf> = scripts.into_iter()
+            .filter(|script| {
+                let script = script.to_string_lossy();
+                let components: Vec<&str> = script.split('/').filter(|x| !x.is_empty() && *x != ".").collect();
+                glob_matches(&pattern, &components)
+            })
+            .collect();
+
+        if matched.is_empty() {
+            return Err(format!("No files match pattern {path}"));
+        }
+
+        result.extend(matched);
+    }
+
+    return Ok(result);
+}
+
+fn walk_scripts(directory: &Path, result: &mut Vec<PathBuf>) -> Result<(), String> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(directory)
+        .map_err(|e| format!("Cannot read directory {}: {e}", directory.display()))?
+        .filter_map(|x| x.ok().map(|x| x.path()))
+        .collect();
+
+    entries.sort();
+
+    for entry in entries {
+        let is_hidden = entry.file_name().is_some_and(|x| x.to_string_lossy().starts_with('.'));
+
+        if entry.is_dir() && !is_hidden {
+            walk_scripts(&entry, result)?;
+        } else if entry.is_file() && entry.extension().is_some_and(|x| x == "js") {
+            result.push(entry);
+        }
+    }
+
+    return Ok(());
+}
+
+/// Matches path components against pattern components, `**` matches zero or more components
+fn glob_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => (0..=path.len()).any(|skipped| glob_matches(&pattern[1..], &path[skipped..])),
+        Some(component) => !path.is_empty() && wildcard_matches(component, path[0]) && glob_matches(&pattern[1..], &path[1..]),
+    }
+}
+
+/// `*` matches any characters, `?` matches exactly one character
+fn wildcard_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.first() {
+            None => name.is_empty(),
+            Some('*') => (0..=name.len()).any(|skipped| matches(&pattern[1..], &name[skipped..])),
+            Some('?') => !name.is_empty() && matches(&pattern[1..], &name[1..]),
+            Some(char) => name.first() == Some(char) && matches(&pattern[1..], &name[1..]),
+        }
+    }
+
+    return matches(&pattern, &name);
+}
+
+#[test]
+fn parse_project_config() {
+    let config = ProjectConfig::parse("
+        # project settings
+        entry = [\"lib.js\", 'main.js']
+        backend = \"ast\"
+        warnings = \"deny\"
+    ").unwrap();
+
+    assert_eq!(config, ProjectConfig {
+        entry: vec![PathBuf::from("lib.js"), PathBuf::from("main.js")],
+        warnings: WarningLevel::Deny,
+    });
+
+    assert_eq!(ProjectConfig::parse("entry = \"main.js\"").unwrap().warnings, WarningLevel::Warn);
+    assert_eq!(
+        ProjectConfig::parse("entry = [\"#lib.js\", 'main#2.js'] # scripts").unwrap().entry,
+        vec![PathBuf::from("#lib.js"), PathBuf::from("main#2.js")],
+    );
+    assert!(ProjectConfig::parse("entry = \"main.js\"\nbackend = \"vm\"").is_err());
+    assert!(ProjectConfig::parse("warnings = \"warn\"").is_err());
+}
+
+#[test]
+fn glob_patterns_match_path_components() {
+    assert!(glob_matches(&["src", "**", "*.js"], &["src", "main.js"]));
+    assert!(glob_matches(&["src", "**", "*.js"], &["src", "a", "b", "util.js"]));
+    assert!(glob_matches(&["**", "test_?.js"], &["tests", "test_1.js"]));
+    assert!(!glob_matches(&["src", "*.js"], &["src", "a", "util.js"]));
+    assert!(!glob_matches(&["**", "test_?.js"], &["test_10.js"]));
+}
+
+#[test]
+fn absolute_lint_patterns_keep_the_root() {
+    let directory = std::env::temp_dir().join(format!("rustjs-lint-{}", std::process::id()));
+    fs::create_dir_all(directory.join("nested")).unwrap();
+
+    for file in ["main.js", "notes.txt", "nested/util.js"] {
+        fs::write(directory.join(file), "").unwrap();
+    }
+
+    let pattern = format!("{}/*.js", directory.display());
+    assert_eq!(collect_lint_paths(&[&pattern]), Ok(vec![directory.join("main.js")]));
+
+    let pattern = format!("{}/**/*.js", directory.display());
+    assert_eq!(collect_lint_paths(&[&pattern]), Ok(vec![directory.join("main.js"), directory.join("nested/util.js")]));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
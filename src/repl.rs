@@ -0,0 +1,271 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use crate::interpreter::ast_interpreter::{Interpreter, InterpreterSnapshot, ScriptSource};
+use crate::interpreter::engine::ExecutionEngine;
+use crate::parser::Parser;
+use crate::value::{DisplayLimits, DisplayMode};
+use crate::{print_error, report_runtime_error};
+
+/// File in the home directory every line typed into the REPL is appended to
+pub const HISTORY_FILE_NAME: &str = ".rustjs_history";
+
+/// Name lines typed into the REPL are reported under, e.g. in errors thrown by functions declared on previous lines
+const REPL_FILE_NAME: &str = "<repl>";
+
+/// Global holding the result of the last line which produced one, like `_` of Node.js REPL
+const LAST_RESULT_NAME: &str = "_";
+
+const HELP: &str = "\
+_              result of the last line which produced one
+.undo          revert global bindings to the state before the previous line
+.record FILE   append every successfully evaluated line & undo of it to the file, without FILE stop recording
+.help          print this help";
+
+/// Interactive session, input handling is separate from evaluation, so lines can come from
+/// the terminal as well as from a replayed session file
+pub struct Repl {
+    parser: Parser,
+    interpreter: Interpreter,
+    /// State before every evaluated line & whether the line was recorded, for `.undo`
+    snapshots: Vec<(InterpreterSnapshot, bool)>,
+    display: DisplayMode,
+    display_limits: DisplayLimits,
+    history: Option<File>,
+    recording: Option<File>,
+}
+
+impl Repl {
+    pub fn new(interpreter: Interpreter, display: DisplayMode, display_limits: DisplayLimits) -> Self {
+        Self {
+            parser: Parser::default(),
+            interpreter,
+            snapshots: vec![],
+            display,
+            display_limits,
+            history: None,
+            recording: None,
+        }
+    }
+
+    /// Appends typed lines to `~/.rustjs_history`, history is silently skipped when the file can't be opened
+    pub fn with_history(mut self) -> Self {
+        self.history = std::env::var_os("HOME")
+            .map(|x| PathBuf::from(x).join(HISTORY_FILE_NAME))
+            .and_then(|x| OpenOptions::new().create(true).append(true).open(x).ok());
+        self
+    }
+
+    /// Reads & handles lines until the end of input, e.g. Ctrl+D or end of piped script
+    pub fn run(&mut self, input: &mut dyn BufRead) {
+        // engine bugs are reported as errors of the line, default hook would print a backtrace
+        std::panic::set_hook(Box::new(|_| {}));
+
+        let mut line = String::new();
+
+        loop {
+            print!("> ");
+            std::io::stdout().flush().expect("flush failed!");
+            line.clear();
+
+            if input.read_line(&mut line).unwrap_or(0) == 0 {
+                println!();
+                return;
+            }
+
+            if let Some(history) = &mut self.history {
+                if !line.trim().is_empty() {
+                    let _ = history.write_all(line.as_bytes());
+                }
+            }
+
+            self.handle_line(line.trim_end_matches(['\n', '\r']));
+        }
+    }
+
+    /// Evaluates lines of a session file one by one, as if they were typed in
+    pub fn replay(&mut self, path: &Path) -> Result<(), String> {
+        let session = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+
+        for line in session.lines().filter(|x| !x.trim().is_empty()) {
+            println!("> {line}");
+            self.handle_line(line);
+        }
+
+        return Ok(());
+    }
+
+    /// Runs a command starting with `.` or evaluates a snippet
+    pub fn handle_line(&mut self, line: &str) {
+        let trimmed = line.trim();
+
+        if let Some(command) = trimmed.strip_prefix('.') {
+            let (command, argument) = command.split_once(' ').map_or((command, ""), |(command, argument)| (command, argument.trim()));
+            self.run_command(command, argument);
+            return;
+        }
+
+        if self.eval(line) && self.record(trimmed) {
+            if let Some((_, is_recorded)) = self.snapshots.last_mut() {
+                *is_recorded = true;
+            }
+        }
+    }
+
+    /// Appends a line to the recorded session, returns false if nothing is recorded
+    fn record(&mut self, line: &str) -> bool {
+        let Some(recording) = &mut self.recording else {
+            return false;
+        };
+
+        if let Err(e) = writeln!(recording, "{line}") {
+            print_error(&format!("Cannot record the line: {e}"), self.display);
+            return false;
+        }
+
+        return true;
+    }
+
+    fn run_command(&mut self, command: &str, argument: &str) {
+        match command {
+            "undo" => match self.snapshots.pop() {
+                Some((snapshot, is_recorded)) => {
+                    self.interpreter.restore(&snapshot);
+
+                    // replay has to revert the recorded line too
+                    if is_recorded {
+                        self.record(".undo");
+                    }
+                }
+                None => print_error("Nothing to undo", self.display),
+            },
+            "record" if argument.is_empty() => self.recording = None,
+            "record" => match OpenOptions::new().create(true).append(true).open(argument) {
+                Ok(file) => self.recording = Some(file),
+                Err(e) => print_error(&format!("Cannot record to {argument}: {e}"), self.display),
+            },
+            "help" => println!("{HELP}"),
+            _ => print_error(&format!("Unknown command .{command}, see .help"), self.display),
+        }
+    }
+
+    /// Evaluates a snippet & prints its result, returns false if it failed
+    fn eval(&mut self, line: &str) -> bool {
+        let ast = match catch_internal_error(|| self.parser.parse(line)) {
+            Ok(Ok(ast)) => ast,
+            Ok(Err(e)) => {
+                print_error(&format!("Error occurred during parsing: {e}"), self.display);
+                return false;
+            }
+            Err(e) => {
+                print_error(&e, self.display);
+                self.parser = Parser::default();
+                return false;
+            }
+        };
+
+        let snapshot = self.interpreter.snapshot();
+        self.interpreter.set_source(Some(Rc::new(ScriptSource { path: REPL_FILE_NAME.to_string(), code: line.to_string() })));
+
+        let is_evaluated = match catch_internal_error(|| self.interpreter.run(&ast)) {
+            Ok(Ok(Some(result))) => {
+                println!("{}", result.display(self.display).with_limits(self.display_limits));
+                self.interpreter.set_global(LAST_RESULT_NAME, result);
+                true
+            }
+            Ok(Ok(None)) => true,
+            Ok(Err(e)) => {
+                report_runtime_error(&self.interpreter, &e, REPL_FILE_NAME, line, self.display);
+                false
+            }
+            // the line stopped somewhere in the middle, its changes are reverted
+            Err(e) => {
+                print_error(&e, self.display);
+                self.interpreter.restore(&snapshot);
+                return false;
+            }
+        };
+
+        self.snapshots.push((snapshot, false));
+        return is_evaluated;
+    }
+}
+
+/// Runs a step of the REPL, so a panic inside of the engine is reported instead of ending the session
+fn catch_internal_error<T>(step: impl FnOnce() -> T) -> Result<T, String> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(step)).map_err(|payload| {
+        let message = payload.downcast_ref::<&str>().map(|x| x.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or("unknown error".to_string());
+
+        format!("Internal error: {message}")
+    })
+}
+
+#[test]
+fn record_and_replay_session() {
+    let directory = std::env::temp_dir().join(format!("rustjs-repl-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let session_path = directory.join("session.js");
+    let _ = std::fs::remove_file(&session_path);
+
+    let mut repl = Repl::new(Interpreter::default(), DisplayMode::Plain, DisplayLimits::default());
+    repl.handle_line("let before = 1;");
+    repl.handle_line(&format!(".record {}", session_path.display()));
+    repl.handle_line("let a = 2;");
+    repl.handle_line("let = ;");
+    repl.handle_line("missing();");
+    repl.handle_line("let b = a * 10;");
+    repl.handle_line(".record");
+    repl.handle_line("let after = 3;");
+
+    assert_eq!(std::fs::read_to_string(&session_path).unwrap(), "let a = 2;\nlet b = a * 10;\n");
+
+    let mut replayed = Repl::new(Interpreter::default(), DisplayMode::Plain, DisplayLimits::default());
+    replayed.replay(&session_path).unwrap();
+    assert_eq!(replayed.interpreter.get_global("b"), crate::value::JsValue::Number(20.0));
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn undo_of_recorded_line_is_replayed() {
+    let directory = std::env::temp_dir().join(format!("rustjs-repl-undo-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let session_path = directory.join("session.js");
+    let _ = std::fs::remove_file(&session_path);
+
+    let mut repl = Repl::new(Interpreter::default(), DisplayMode::Plain, DisplayLimits::default());
+    repl.handle_line(&format!(".record {}", session_path.display()));
+    repl.handle_line("let a = 2;");
+    repl.handle_line("missing();");
+    repl.handle_line(".undo");
+    repl.handle_line("let b = a * 10;");
+    repl.handle_line(".undo");
+    repl.handle_line("let b = 5;");
+    repl.handle_line(".record");
+
+    // failed line isn't recorded, so its undo isn't either
+    assert_eq!(std::fs::read_to_string(&session_path).unwrap(), "let a = 2;\nlet b = a * 10;\n.undo\nlet b = 5;\n");
+
+    let mut replayed = Repl::new(Interpreter::default(), DisplayMode::Plain, DisplayLimits::default());
+    replayed.replay(&session_path).unwrap();
+    assert_eq!(replayed.interpreter.get_global("b"), repl.interpreter.get_global("b"));
+    assert_eq!(replayed.interpreter.get_global("b"), crate::value::JsValue::Number(5.0));
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn last_result_is_available_as_underscore() {
+    let mut repl = Repl::new(Interpreter::default(), DisplayMode::Plain, DisplayLimits::default());
+    repl.handle_line("2 * 21;");
+    repl.handle_line("let answer = _;");
+    repl.handle_line("_ + 1;");
+    repl.handle_line(".undo");
+
+    assert_eq!(repl.interpreter.get_global("answer"), crate::value::JsValue::Number(42.0));
+    assert_eq!(repl.interpreter.get_global("_"), crate::value::JsValue::Number(42.0));
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+use crate::value::{DisplayMode, JsValue};
+
+/// Format of the `--report` record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Json,
+}
+
+impl ReportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown report format '{format}', expected json")),
+        }
+    }
+}
+
+/// Timings & outcome of every script of a run, written to stderr as a single JSON line once the run ends, e.g.
+/// `{"startup_ms":0.1,"scripts":[{"file":"main.js","parse_ms":0.2,"check_ms":0.1,"eval_ms":1.5,"warnings":0,"errors":0,"result":42,"error":null}]}`
+#[derive(Debug, Default)]
+pub struct RunReport {
+    pub startup: Duration,
+    pub scripts: Vec<ScriptReport>,
+}
+
+#[derive(Debug, Default)]
+pub struct ScriptReport {
+    pub file: String,
+    pub parse: Duration,
+    /// Symbol checking
+    pub check: Duration,
+    pub eval: Duration,
+    pub warnings: usize,
+    pub errors: usize,
+    /// Completion value of the script as JSON, `None` if the script has none or didn't finish
+    pub result: Option<String>,
+    /// Parse or uncaught runtime error
+    pub error: Option<String>,
+}
+
+impl ScriptReport {
+    pub fn new(file: &str) -> Self {
+        Self {
+            file: file.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+impl RunReport {
+    pub fn to_json(&self) -> String {
+        let scripts: Vec<String> = self.scripts.iter().map(|script| format!(
+            "{{\"file\":{},\"parse_ms\":{},\"check_ms\":{},\"eval_ms\":{},\"warnings\":{},\"errors\":{},\"result\":{},\"error\":{}}}",
+            json_string(&script.file),
+            milliseconds(script.parse),
+            milliseconds(script.check),
+            milliseconds(script.eval),
+            script.warnings,
+            script.errors,
+            script.result.as_deref().unwrap_or("null"),
+            script.error.as_deref().map_or("null".to_string(), json_string),
+        )).collect();
+
+        return format!("{{\"startup_ms\":{},\"scripts\":[{}]}}", milliseconds(self.startup), scripts.join(","));
+    }
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+fn json_string(text: &str) -> String {
+    JsValue::String(text.into()).display(DisplayMode::Json).to_string()
+}
+
+#[test]
+fn report_is_serialized_to_json() {
+    let report = RunReport {
+        startup: Duration::from_micros(250),
+        scripts: vec![
+            ScriptReport {
+                file: "src/\"main\".js".to_string(),
+                parse: Duration::from_millis(2),
+                warnings: 1,
+                result: Some("[1,2]".to_string()),
+                ..ScriptReport::default()
+            },
+            ScriptReport {
+                error: Some("ReferenceError: a is not defined".to_string()),
+                ..ScriptReport::new("b.js")
+            },
+        ],
+    };
+
+    assert_eq!(
+        report.to_json(),
+        "{\"startup_ms\":0.250,\"scripts\":[\
+        {\"file\":\"src/\\\"main\\\".js\",\"parse_ms\":2.000,\"check_ms\":0.000,\"eval_ms\":0.000,\"warnings\":1,\"errors\":0,\"result\":[1,2],\"error\":null},\
+        {\"file\":\"b.js\",\"parse_ms\":0.000,\"check_ms\":0.000,\"eval_ms\":0.000,\"warnings\":0,\"errors\":0,\"result\":null,\"error\":\"ReferenceError: a is not defined\"}]}"
+    );
+}
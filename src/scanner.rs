@@ -1,11 +1,13 @@
-use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
-use crate::keywords::{BREAK_KEYWORD, CATCH_KEYWORD, CLASS_KEYWORD, CONST_KEYWORD, CONTINUE_KEYWORD, DO_KEYWORD, ELSE_KEYWORD, EXPORT_KEYWORD, EXTENDS_KEYWORD, FALSE_KEYWORD, FOR_KEYWORD, FUNCTION_KEYWORD, IF_KEYWORD, IMPORT_KEYWORD, IN_KEYWORD, LET_KEYWORD, NEW_KEYWORD, NULL_KEYWORD, RETURN_KEYWORD, STATIC_KEYWORD, SUPER_KEYWORD, SWITCH_KEYWORD, THIS_KEYWORD, THROW_KEYWORD, TRUE_KEYWORD, TRY_KEYWORD, UNDEFINED_KEYWORD, WHILE_KEYWORD, YIELD_KEYWORD};
+use crate::value::{number_to_string, DisplayMode, JsValue};
+use crate::keywords::{BREAK_KEYWORD, CATCH_KEYWORD, CLASS_KEYWORD, CONST_KEYWORD, CONTINUE_KEYWORD, DO_KEYWORD, ELSE_KEYWORD, EXPORT_KEYWORD, EXTENDS_KEYWORD, FALSE_KEYWORD, FOR_KEYWORD, FUNCTION_KEYWORD, IF_KEYWORD, IMPORT_KEYWORD, IN_KEYWORD, INSTANCEOF_KEYWORD, TYPEOF_KEYWORD, VOID_KEYWORD, LET_KEYWORD, NEW_KEYWORD, NULL_KEYWORD, RETURN_KEYWORD, STATIC_KEYWORD, SUPER_KEYWORD, SWITCH_KEYWORD, CASE_KEYWORD, DEFAULT_KEYWORD, THIS_KEYWORD, THROW_KEYWORD, TRUE_KEYWORD, TRY_KEYWORD, UNDEFINED_KEYWORD, WHILE_KEYWORD, YIELD_KEYWORD};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenKind {
     // Literal
     String(String),
+    /// String which reaches the end of source without the closing quote
+    UnterminatedString(String),
     Number(f64),
     Boolean(String),
     Null,
@@ -66,6 +68,7 @@ pub enum TokenKind {
     Semicolon,
     Colon,
     Dot,
+    DotDotDot, // ...
     Exclamatory, // !
     Question,    // ?
 
@@ -94,14 +97,20 @@ pub enum TokenKind {
     ImportKeyword,
     StaticKeyword,
     SwitchKeyword,
+    CaseKeyword,
+    DefaultKeyword,
     ReturnKeyword,
+    InstanceOfKeyword,
+    TypeofKeyword,
+    VoidKeyword,
 }
 
 impl TokenKind {
     pub fn to_keyword(&self) -> String {
         match self {
             TokenKind::String(value) => format!("{} (string)", value),
-            TokenKind::Number(value) => format!("{} (number)", value),
+            TokenKind::UnterminatedString(_) => "unterminated string".to_string(),
+            TokenKind::Number(value) => format!("{} (number)", number_to_string(*value)),
             TokenKind::Boolean(value) => format!("{} (boolean)", value),
             TokenKind::Null => NULL_KEYWORD.to_string(),
             TokenKind::Undefined => UNDEFINED_KEYWORD.to_string(),
@@ -113,7 +122,7 @@ impl TokenKind {
             TokenKind::BitwiseAnd => "&".to_string(),
             TokenKind::Plus => "+".to_string(),
             TokenKind::PlusPlus => "++".to_string(),
-            TokenKind::Minus => "--".to_string(),
+            TokenKind::Minus => "-".to_string(),
             TokenKind::MinusMinus => "--".to_string(),
             TokenKind::Mul => "*".to_string(),
             TokenKind::MulMul => "**".to_string(),
@@ -138,6 +147,7 @@ impl TokenKind {
             TokenKind::Comma => ",".to_string(),
             TokenKind::Semicolon => ";".to_string(),
             TokenKind::Dot => ".".to_string(),
+            TokenKind::DotDotDot => "...".to_string(),
             TokenKind::Exclamatory => "!".to_string(),
             TokenKind::FunctionKeyword => FUNCTION_KEYWORD.to_string(),
             TokenKind::IfKeyword => IF_KEYWORD.to_string(),
@@ -163,7 +173,12 @@ impl TokenKind {
             TokenKind::ImportKeyword => IMPORT_KEYWORD.to_string(),
             TokenKind::StaticKeyword => STATIC_KEYWORD.to_string(),
             TokenKind::SwitchKeyword => SWITCH_KEYWORD.to_string(),
+            TokenKind::CaseKeyword => CASE_KEYWORD.to_string(),
+            TokenKind::DefaultKeyword => DEFAULT_KEYWORD.to_string(),
             TokenKind::ReturnKeyword => RETURN_KEYWORD.to_string(),
+            TokenKind::InstanceOfKeyword => INSTANCEOF_KEYWORD.to_string(),
+            TokenKind::TypeofKeyword => TYPEOF_KEYWORD.to_string(),
+            TokenKind::VoidKeyword => VOID_KEYWORD.to_string(),
             TokenKind::LessThan => "<".to_string(),
             TokenKind::LessThanOrEqual => "<=".to_string(),
             TokenKind::MoreThan => ">".to_string(),
@@ -176,22 +191,93 @@ impl TokenKind {
     }
 }
 
+impl TokenKind {
+    /// Name of the variant without its payload, e.g. `Identifier`
+    pub fn name(&self) -> String {
+        let name = format!("{:?}", self);
+        return name.split('(').next().unwrap().to_string();
+    }
+
+    /// Coarse kind of the token for syntax highlighting, one of
+    /// `keyword`, `identifier`, `string`, `number`, `literal` (booleans, `null` & `undefined`),
+    /// `comment`, `operator` or `punctuation`
+    pub fn category(&self) -> &'static str {
+        match self {
+            TokenKind::String(_) | TokenKind::UnterminatedString(_) => "string",
+            TokenKind::Number(_) => "number",
+            TokenKind::Boolean(_) | TokenKind::Null | TokenKind::Undefined => "literal",
+            TokenKind::Identifier(_) => "identifier",
+            TokenKind::Comment(_) => "comment",
+            TokenKind::OpenParen | TokenKind::CloseParen
+            | TokenKind::OpenBrace | TokenKind::CloseBrace
+            | TokenKind::OpenSquareBracket | TokenKind::CloseSquareBracket
+            | TokenKind::Comma | TokenKind::Semicolon | TokenKind::Colon
+            | TokenKind::Dot | TokenKind::DotDotDot => "punctuation",
+            TokenKind::Or | TokenKind::And | TokenKind::BitwiseOr | TokenKind::BitwiseAnd
+            | TokenKind::LessThan | TokenKind::LessThanOrEqual | TokenKind::MoreThan | TokenKind::MoreThanOrEqual
+            | TokenKind::Plus | TokenKind::PlusPlus | TokenKind::Minus | TokenKind::MinusMinus
+            | TokenKind::Mul | TokenKind::MulMul | TokenKind::Div | TokenKind::Percent
+            | TokenKind::Equal | TokenKind::PlusEqual | TokenKind::MinusEqual | TokenKind::MulEqual
+            | TokenKind::DivEqual | TokenKind::PercentEqual | TokenKind::MulMulEqual
+            | TokenKind::LSLSEqual | TokenKind::RSRSEqual | TokenKind::RSRSRSEqual
+            | TokenKind::Equality | TokenKind::Inequality
+            | TokenKind::Exclamatory | TokenKind::Question => "operator",
+            _ => "keyword",
+        }
+    }
+}
+
 impl Display for TokenKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TextSpan {
     pub start: Span,
     pub end: Span,
 }
 
+impl TextSpan {
+    /// Span which starts at the beginning of this span and ends at the end of other
+    pub fn join(&self, other: &TextSpan) -> TextSpan {
+        TextSpan {
+            start: self.start,
+            end: other.end,
+        }
+    }
+}
+
+impl TokenKind {
+    /// Whether the token ends an operand, so `/` after it divides, e.g. `a / b` or `(x) / 2`.
+    /// After other tokens, e.g. `(` or `=`, `/` starts a regular expression literal
+    pub(crate) fn ends_operand(&self) -> bool {
+        matches!(
+            self,
+            TokenKind::String(_) | TokenKind::UnterminatedString(_) | TokenKind::Number(_) | TokenKind::Boolean(_)
+            | TokenKind::Null | TokenKind::Undefined | TokenKind::Identifier(_)
+            | TokenKind::CloseParen | TokenKind::CloseSquareBracket | TokenKind::CloseBrace
+            | TokenKind::PlusPlus | TokenKind::MinusMinus
+            | TokenKind::ThisKeyword | TokenKind::SuperKeyword
+        )
+    }
+}
+
+/// Context of a token which depends on the tokens before it, comments are not taken into account
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TokenFlags {
+    /// Line break separates the token from the previous one, automatic semicolon insertion depends on it
+    pub is_after_line_break: bool,
+    /// `/` at the position of the token would start a regular expression literal rather than divide
+    pub is_regex_allowed: bool,
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Token {
     pub token: TokenKind,
     pub span: TextSpan,
+    pub flags: TokenFlags,
 }
 
 impl Debug for Token {
@@ -200,33 +286,163 @@ impl Debug for Token {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Span {
     pub line: usize,
     pub row: usize,
 }
 
+/// All tokens of the source including comments, for tools which need the token stream, e.g. syntax highlighters
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut scanner = Scanner::new(source.to_string());
+    let mut tokens = vec![];
+
+    while let Some(token) = scanner.next_token() {
+        tokens.push(token);
+    }
+
+    return tokens;
+}
+
+/// JSON array of tokens, one token per line, e.g.
+/// `{"kind":"Identifier","category":"identifier","text":"a","line":1,"start":4,"end":5}`.
+/// `line` is counted from 1, `start` & `end` are character offsets in the source, end is exclusive
+pub fn tokens_to_json(source: &str, tokens: &[Token]) -> String {
+    let chars: Vec<char> = source.chars().collect();
+
+    let items: Vec<String> = tokens.iter().map(|token| {
+        let TextSpan { start, end } = &token.span;
+        let text: String = chars[start.row..end.row].iter().collect();
+
+        format!(
+            "{{\"kind\":\"{}\",\"category\":\"{}\",\"text\":{},\"line\":{},\"start\":{},\"end\":{}}}",
+            token.token.name(),
+            token.token.category(),
+            JsValue::String(text.into()).display(DisplayMode::Json),
+            start.line + 1,
+            start.row,
+            end.row,
+        )
+    }).collect();
+
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+
+    return format!("[\n  {}\n]", items.join(",\n  "));
+}
+
+/// Token of a keyword or of a literal spelled like an identifier, e.g. `true`
+fn keyword_token(identifier: &str) -> Option<TokenKind> {
+    let token = match identifier {
+        LET_KEYWORD => TokenKind::LetKeyword,
+        CONST_KEYWORD => TokenKind::ConstKeyword,
+        IF_KEYWORD => TokenKind::IfKeyword,
+        ELSE_KEYWORD => TokenKind::ElseKeyword,
+        CLASS_KEYWORD => TokenKind::ClassKeyword,
+        NEW_KEYWORD => TokenKind::NewKeyword,
+        EXTENDS_KEYWORD => TokenKind::ExtendsKeyword,
+        FOR_KEYWORD => TokenKind::ForKeyword,
+        IN_KEYWORD => TokenKind::InKeyword,
+        FUNCTION_KEYWORD => TokenKind::FunctionKeyword,
+        THIS_KEYWORD => TokenKind::ThisKeyword,
+        DO_KEYWORD => TokenKind::DoKeyword,
+        WHILE_KEYWORD => TokenKind::WhileKeyword,
+        TRY_KEYWORD => TokenKind::TryKeyword,
+        CATCH_KEYWORD => TokenKind::CatchKeyword,
+        BREAK_KEYWORD => TokenKind::BreakKeyword,
+        CONTINUE_KEYWORD => TokenKind::ContinueKeyword,
+        SUPER_KEYWORD => TokenKind::SuperKeyword,
+        THROW_KEYWORD => TokenKind::ThrowKeyword,
+        YIELD_KEYWORD => TokenKind::YieldKeyword,
+        EXPORT_KEYWORD => TokenKind::ExportKeyword,
+        IMPORT_KEYWORD => TokenKind::ImportKeyword,
+        RETURN_KEYWORD => TokenKind::ReturnKeyword,
+        STATIC_KEYWORD => TokenKind::StaticKeyword,
+        SWITCH_KEYWORD => TokenKind::SwitchKeyword,
+        CASE_KEYWORD => TokenKind::CaseKeyword,
+        DEFAULT_KEYWORD => TokenKind::DefaultKeyword,
+        INSTANCEOF_KEYWORD => TokenKind::InstanceOfKeyword,
+        TYPEOF_KEYWORD => TokenKind::TypeofKeyword,
+        VOID_KEYWORD => TokenKind::VoidKeyword,
+        TRUE_KEYWORD => TokenKind::Boolean(TRUE_KEYWORD.to_string()),
+        FALSE_KEYWORD => TokenKind::Boolean(FALSE_KEYWORD.to_string()),
+        NULL_KEYWORD => TokenKind::Null,
+        UNDEFINED_KEYWORD => TokenKind::Undefined,
+        _ => return None,
+    };
+
+    return Some(token);
+}
+
+/// Splits source into tokens. Source is kept as characters, so looking at the current character is O(1)
+/// and `row` of spans is a character offset, which is what diagnostics expect
 pub struct Scanner {
     current_pos: usize,
     current_line: usize,
     prev_pos: usize,
     prev_line: usize,
-    source_code: String,
+    chars: Vec<char>,
+    /// Last token which isn't a comment & the line it ends on
+    prev_token: Option<(TokenKind, usize)>,
 }
 
 impl Scanner {
+    /// Leading shebang line, e.g. `#!/usr/bin/env rustjs`, is skipped. Line break after it isn't,
+    /// so positions & lines of tokens still match the source
     pub fn new(source_code: String) -> Self {
+        let chars: Vec<char> = source_code.chars().collect();
+
+        let start_pos = if source_code.starts_with("#!") {
+            chars.iter().position(|x| *x == '\n').unwrap_or(chars.len())
+        } else {
+            0
+        };
+
         Self {
-            prev_pos: 0,
+            prev_pos: start_pos,
             prev_line: 0,
-            current_pos: 0,
+            current_pos: start_pos,
             current_line: 0,
-            source_code,
+            chars,
+            prev_token: None,
+        }
+    }
+
+    /// Scanner which continues from the given position, e.g. to scan only the changed part of a source
+    pub fn starting_at(source_code: &str, start: Span) -> Self {
+        Self {
+            prev_pos: start.row,
+            prev_line: start.line,
+            current_pos: start.row,
+            current_line: start.line,
+            chars: source_code.chars().collect(),
+            prev_token: None,
         }
     }
 
-    fn consume(&self, token: TokenKind) -> Token {
+    /// Last scanned token which isn't a comment
+    pub fn previous_token(&self) -> Option<&TokenKind> {
+        self.prev_token.as_ref().map(|(token, _)| token)
+    }
+
+    /// Whether `/` at the current position would start a regular expression literal, see [`TokenFlags`]
+    pub fn is_regex_allowed(&self) -> bool {
+        self.previous_token().is_none_or(|x| !x.ends_operand())
+    }
+
+    fn consume(&mut self, token: TokenKind) -> Token {
+        let flags = TokenFlags {
+            is_after_line_break: self.prev_token.as_ref().is_some_and(|(_, line)| *line < self.prev_line),
+            is_regex_allowed: self.is_regex_allowed(),
+        };
+
+        if !matches!(token, TokenKind::Comment(_)) {
+            self.prev_token = Some((token.clone(), self.current_line));
+        }
+
         Token {
+            flags,
             token,
             span: TextSpan {
                 start: Span {
@@ -241,337 +457,263 @@ impl Scanner {
         }
     }
 
-    pub fn next_token(&mut self) -> Option<Token> {
-        self.prev_line = self.current_line;
-        self.prev_pos = self.current_pos;
-        let mut cursor = self.current_pos;
-
-        if self.current_pos >= self.source_code.len() {
-            return None;
-        }
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.current_pos).copied()
+    }
 
-        let mut chars = self.source_code.chars();
-        let current_char = chars.nth(self.current_pos).unwrap();
+    fn advance(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.current_pos += 1;
 
-        if current_char == '\n' {
+        if char == '\n' {
             self.current_line += 1;
         }
 
-        if current_char.is_whitespace() {
-            self.current_pos += 1;
-            return self.next_token();
-        }
-
-        let found_token = match current_char {
-            ',' => Some(TokenKind::Comma),
-            ';' => Some(TokenKind::Semicolon),
-            ':' => Some(TokenKind::Colon),
-            '(' => Some(TokenKind::OpenParen),
-            ')' => Some(TokenKind::CloseParen),
-            '{' => Some(TokenKind::OpenBrace),
-            '}' => Some(TokenKind::CloseBrace),
-            '[' => Some(TokenKind::OpenSquareBracket),
-            ']' => Some(TokenKind::CloseSquareBracket),
-            '.' => Some(TokenKind::Dot),
-            '?' => Some(TokenKind::Question),
-            _ => None,
-        };
-
-        if current_char == '=' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::Equality));
-            }
-
-            return Some(self.consume(TokenKind::Equal));
-        }
-
-        if current_char == '!' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::Inequality));
-            }
-
-            return Some(self.consume(TokenKind::Exclamatory));
-        }
-
-        if current_char == '%' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::PercentEqual));
-            }
-
-            return Some(self.consume(TokenKind::Percent));
-        }
-
-        if current_char == '>' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::MoreThanOrEqual));
-            }
+        return Some(char);
+    }
 
-            return Some(self.consume(TokenKind::MoreThan));
+    /// Consumes the next character only if it's the expected one
+    fn advance_if(&mut self, expected: char) -> bool {
+        if self.peek() != Some(expected) {
+            return false;
         }
 
-        if current_char == '<' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::LessThanOrEqual));
-            }
+        self.advance();
+        return true;
+    }
 
-            return Some(self.consume(TokenKind::LessThan));
+    fn advance_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&predicate) {
+            self.advance();
         }
+    }
 
-        if current_char == '/' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::DivEqual));
-            }
+    /// Text from the given position up to the current one
+    fn text_from(&self, start: usize) -> String {
+        self.chars[start..self.current_pos].iter().collect()
+    }
 
-            if let Some('/') = next_char {
-                self.current_pos += 1;
+    pub fn next_token(&mut self) -> Option<Token> {
+        self.advance_while(char::is_whitespace);
 
-                while let Some(char) = chars.next() {
-                    cursor += 1;
+        self.prev_line = self.current_line;
+        self.prev_pos = self.current_pos;
 
-                    if char == '\n' {
-                        break;
-                    }
+        let current_char = self.advance()?;
+
+        let token = match current_char {
+            ',' => TokenKind::Comma,
+            ';' => TokenKind::Semicolon,
+            ':' => TokenKind::Colon,
+            '(' => TokenKind::OpenParen,
+            ')' => TokenKind::CloseParen,
+            '{' => TokenKind::OpenBrace,
+            '}' => TokenKind::CloseBrace,
+            '[' => TokenKind::OpenSquareBracket,
+            ']' => TokenKind::CloseSquareBracket,
+            '?' => TokenKind::Question,
+            '.' => {
+                if self.chars.get(self.current_pos..self.current_pos + 2) == Some(&['.', '.']) {
+                    self.current_pos += 2;
+                    TokenKind::DotDotDot
+                } else {
+                    TokenKind::Dot
                 }
-
-                let token = TokenKind::Comment(self.source_code[self.current_pos..=cursor + 1].to_string());
-                self.current_pos = cursor + 2;
-                return Some(self.consume(token));
-            } else {
-                return Some(self.consume(TokenKind::Div));
-            }
-        }
-
-        if current_char == '=' {
-            self.current_pos += 1;
-
-            if let Some('=') = chars.next() {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::Equality));
             }
-
-            return Some(self.consume(TokenKind::Equal));
-        }
-
-        if current_char == '+' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::PlusEqual));
+            '=' => if self.advance_if('=') { TokenKind::Equality } else { TokenKind::Equal },
+            '!' => if self.advance_if('=') { TokenKind::Inequality } else { TokenKind::Exclamatory },
+            '%' => if self.advance_if('=') { TokenKind::PercentEqual } else { TokenKind::Percent },
+            '>' => if self.advance_if('=') { TokenKind::MoreThanOrEqual } else { TokenKind::MoreThan },
+            '<' => if self.advance_if('=') { TokenKind::LessThanOrEqual } else { TokenKind::LessThan },
+            '|' => if self.advance_if('|') { TokenKind::Or } else { TokenKind::BitwiseOr },
+            '&' => if self.advance_if('&') { TokenKind::And } else { TokenKind::BitwiseAnd },
+            '/' => {
+                if self.advance_if('=') {
+                    TokenKind::DivEqual
+                } else if self.advance_if('/') {
+                    let start = self.current_pos;
+                    self.advance_while(|x| x != '\n');
+                    TokenKind::Comment(self.text_from(start))
+                } else {
+                    TokenKind::Div
+                }
             }
-
-            if let Some('+') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::PlusPlus));
+            '+' => {
+                if self.advance_if('=') {
+                    TokenKind::PlusEqual
+                } else if self.advance_if('+') {
+                    TokenKind::PlusPlus
+                } else {
+                    TokenKind::Plus
+                }
             }
-
-            return Some(self.consume(TokenKind::Plus));
-        }
-
-        if current_char == '*' {
-            self.current_pos += 1;
-
-            let next_char = chars.next();
-
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::MulEqual));
+            '-' => {
+                if self.advance_if('=') {
+                    TokenKind::MinusEqual
+                } else if self.advance_if('-') {
+                    TokenKind::MinusMinus
+                } else {
+                    TokenKind::Minus
+                }
             }
-
-            if let Some('*') = next_char {
-                self.current_pos += 1;
-
-                let next_char = chars.next();
-
-                if let Some('=') = next_char {
-                    self.current_pos += 1;
-                    return Some(self.consume(TokenKind::MulMulEqual));
+            '*' => {
+                if self.advance_if('=') {
+                    TokenKind::MulEqual
+                } else if self.advance_if('*') {
+                    if self.advance_if('=') { TokenKind::MulMulEqual } else { TokenKind::MulMul }
+                } else {
+                    TokenKind::Mul
                 }
-
-                return Some(self.consume(TokenKind::MulMul));
             }
+            '"' | '\'' => self.scan_string_literal(current_char),
+            char if char.is_ascii_digit() => {
+                self.advance_while(|x| x.is_ascii_digit());
 
-            return Some(self.consume(TokenKind::Mul));
-        }
-
-        if current_char == '-' {
-            self.current_pos += 1;
+                // a single fraction part, so `1.2.3` is a member access instead of a malformed number
+                if self.advance_if('.') {
+                    self.advance_while(|x| x.is_ascii_digit());
+                }
 
-            let next_char = chars.next();
+                let number = self.text_from(self.prev_pos)
+                    .parse::<f64>()
+                    .unwrap_or(f64::NAN);
 
-            if let Some('=') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::MinusEqual));
+                TokenKind::Number(number)
             }
+            _ => {
+                self.advance_while(|x| x.is_alphanumeric() || x == '_');
+                let identifier = self.text_from(self.prev_pos);
 
-            if let Some('-') = next_char {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::MinusMinus));
-            }
-
-            return Some(self.consume(TokenKind::Minus));
-        }
-
-        if current_char == '|' {
-            self.current_pos += 1;
-
-            if let Some('|') = chars.next() {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::Or));
+                match keyword_token(&identifier) {
+                    Some(keyword) => keyword,
+                    None => TokenKind::Identifier(identifier),
+                }
             }
+        };
 
-            return Some(self.consume(TokenKind::BitwiseOr));
-        }
-
-        if current_char == '&' {
-            self.current_pos += 1;
-
-            if let Some('&') = chars.next() {
-                self.current_pos += 1;
-                return Some(self.consume(TokenKind::And));
-            }
+        return Some(self.consume(token));
+    }
 
-            return Some(self.consume(TokenKind::BitwiseAnd));
-        }
+    /// String without escape sequences, unterminated string lasts until the end of the source
+    /// Line breaks inside of a string go through `advance`, so tokens after a multi-line string keep their lines
+    fn scan_string_literal(&mut self, quote_char: char) -> TokenKind {
+        let start = self.current_pos;
+        self.advance_while(|x| x != quote_char);
+        let text = self.text_from(start);
 
-        if let Some(_) = found_token {
-            self.current_pos += 1;
-            return found_token.map(|x| self.consume(x));
+        if !self.advance_if(quote_char) {
+            return TokenKind::UnterminatedString(text);
         }
 
-        if current_char.is_digit(10) {
-            while let Some(char) = chars.next() {
-                if char == '.' {
-                    cursor += 1;
-                } else if char.is_digit(10) {
-                    cursor += 1;
-                } else {
-                    break;
-                }
-            }
-
-            let number_str = &self.source_code[self.current_pos..=cursor];
-            let number = number_str
-                .parse::<f64>()
-                .expect("Error during number parsing");
-            let token = TokenKind::Number(number);
+        return TokenKind::String(text);
+    }
+}
 
-            self.current_pos = cursor + 1;
+#[test]
+fn shebang_line_is_skipped() {
+    let mut scanner = Scanner::new("#!/usr/bin/env rustjs\nlet a;".to_string());
+    let token = scanner.next_token().unwrap();
 
-            return Some(self.consume(token));
-        }
+    assert_eq!(token.token, TokenKind::LetKeyword);
+    assert_eq!(token.span.start, Span { line: 1, row: 22 });
+    assert_eq!(Scanner::new("#!/usr/bin/env rustjs".to_string()).next_token(), None);
+}
 
-        if current_char == '"' || current_char == '\'' {
-            return self
-                .parse_string_literal(current_char)
-                .map(|x| self.consume(x));
-        }
+#[test]
+fn tokens_have_character_spans_and_lines() {
+    let mut scanner = Scanner::new("let é = 'ü'; // note\nx **= 1;".to_string());
+    let mut tokens = vec![];
 
-        while let Some(char) = chars.next() {
-            if !char.is_alphanumeric() && char != '_' {
-                break;
-            }
+    while let Some(token) = scanner.next_token() {
+        tokens.push((token.token, token.span.start, token.span.end));
+    }
 
-            match char {
-                '(' | ')' | '.' | ';' | '\n' | ',' => break,
-                _ => {}
-            }
+    assert_eq!(tokens[1], (TokenKind::Identifier("é".to_string()), Span { line: 0, row: 4 }, Span { line: 0, row: 5 }));
+    assert_eq!(tokens[3], (TokenKind::String("ü".to_string()), Span { line: 0, row: 8 }, Span { line: 0, row: 11 }));
+    assert_eq!(tokens[5].0, TokenKind::Comment(" note".to_string()));
+    assert_eq!(tokens[7], (TokenKind::MulMulEqual, Span { line: 1, row: 23 }, Span { line: 1, row: 26 }));
+}
 
-            cursor += 1;
-        }
+#[test]
+fn large_source_is_tokenized_in_linear_time() {
+    let source = "let value = (counter + 1.5) * 2; // comment\n".repeat(100_000);
+    let start = std::time::Instant::now();
+    let mut scanner = Scanner::new(source);
+    let mut count = 0;
 
-        let keywords = HashMap::from([
-            (LET_KEYWORD, TokenKind::LetKeyword),
-            (CONST_KEYWORD, TokenKind::ConstKeyword),
-            (IF_KEYWORD, TokenKind::IfKeyword),
-            (ELSE_KEYWORD, TokenKind::ElseKeyword),
-            (CLASS_KEYWORD, TokenKind::ClassKeyword),
-            (NEW_KEYWORD, TokenKind::NewKeyword),
-            (EXTENDS_KEYWORD, TokenKind::ExtendsKeyword),
-            (FOR_KEYWORD, TokenKind::ForKeyword),
-            (IN_KEYWORD, TokenKind::InKeyword),
-            (FUNCTION_KEYWORD, TokenKind::FunctionKeyword),
-            (THIS_KEYWORD, TokenKind::ThisKeyword),
-            (DO_KEYWORD, TokenKind::DoKeyword),
-            (WHILE_KEYWORD, TokenKind::WhileKeyword),
-            (TRY_KEYWORD, TokenKind::TryKeyword),
-            (CATCH_KEYWORD, TokenKind::CatchKeyword),
-            (BREAK_KEYWORD, TokenKind::BreakKeyword),
-            (CONTINUE_KEYWORD, TokenKind::ContinueKeyword),
-            (SUPER_KEYWORD, TokenKind::SuperKeyword),
-            (THROW_KEYWORD, TokenKind::ThrowKeyword),
-            (YIELD_KEYWORD, TokenKind::YieldKeyword),
-            (EXPORT_KEYWORD, TokenKind::ExportKeyword),
-            (IMPORT_KEYWORD, TokenKind::ImportKeyword),
-            (RETURN_KEYWORD, TokenKind::ReturnKeyword),
-            (STATIC_KEYWORD, TokenKind::StaticKeyword),
-            (SWITCH_KEYWORD, TokenKind::SwitchKeyword),
-            (TRUE_KEYWORD, TokenKind::Boolean("true".to_string())),
-            (FALSE_KEYWORD, TokenKind::Boolean("false".to_string())),
-            (NULL_KEYWORD, TokenKind::Null),
-            (UNDEFINED_KEYWORD, TokenKind::Undefined),
-        ]);
-
-        let identifier = &self.source_code[self.current_pos..=cursor];
-
-        if keywords.contains_key(identifier) {
-            let token_kind = keywords.get(identifier).unwrap();
-            self.current_pos += identifier.len();
-            return Some(self.consume(token_kind.clone()));
-        } else {
-            self.current_pos += identifier.len();
-            return Some(self.consume(TokenKind::Identifier(identifier.to_string())));
-        }
+    while scanner.next_token().is_some() {
+        count += 1;
     }
 
-    fn parse_string_literal(&mut self, quote_char: char) -> Option<TokenKind> {
-        let mut cursor = self.current_pos;
-        let mut chars = self.source_code[cursor..].chars();
+    assert_eq!(count, 1_200_000);
+    assert!(start.elapsed().as_secs() < 10, "tokenization took {:?}", start.elapsed());
+}
 
-        chars.next();
+#[test]
+fn tokens_are_serialized_to_json() {
+    let source = "let s = 'a\"b'; // hi\ns += 1;";
+    let json = tokens_to_json(source, &tokenize(source));
+
+    assert_eq!(json, [
+        "[",
+        r#"  {"kind":"LetKeyword","category":"keyword","text":"let","line":1,"start":0,"end":3},"#,
+        r#"  {"kind":"Identifier","category":"identifier","text":"s","line":1,"start":4,"end":5},"#,
+        r#"  {"kind":"Equal","category":"operator","text":"=","line":1,"start":6,"end":7},"#,
+        r#"  {"kind":"String","category":"string","text":"'a\"b'","line":1,"start":8,"end":13},"#,
+        r#"  {"kind":"Semicolon","category":"punctuation","text":";","line":1,"start":13,"end":14},"#,
+        r#"  {"kind":"Comment","category":"comment","text":"// hi","line":1,"start":15,"end":20},"#,
+        r#"  {"kind":"Identifier","category":"identifier","text":"s","line":2,"start":21,"end":22},"#,
+        r#"  {"kind":"PlusEqual","category":"operator","text":"+=","line":2,"start":23,"end":25},"#,
+        r#"  {"kind":"Number","category":"number","text":"1","line":2,"start":26,"end":27},"#,
+        r#"  {"kind":"Semicolon","category":"punctuation","text":";","line":2,"start":27,"end":28}"#,
+        "]",
+    ].join("\n"));
+    assert_eq!(tokens_to_json("", &tokenize("")), "[]");
+}
 
-        while let Some(char) = chars.next() {
-            cursor += 1;
+#[test]
+fn number_has_at_most_one_fraction_part() {
+    let tokens: Vec<TokenKind> = tokenize("1.5.2 3.").into_iter().map(|x| x.token).collect();
+    assert_eq!(tokens, vec![TokenKind::Number(1.5), TokenKind::Dot, TokenKind::Number(2.0), TokenKind::Number(3.0)]);
+}
 
-            if char == quote_char {
-                break;
-            }
-        }
+#[test]
+fn multi_line_strings_keep_lines_of_following_tokens() {
+    let tokens = tokenize("let s = 'a\nb\nc';\nx;\n'open\nend");
 
-        let token = TokenKind::String(self.source_code[self.current_pos + 1..cursor].to_string());
-        self.current_pos = cursor + 1;
-        return Some(token);
-    }
+    assert_eq!(tokens[3].token, TokenKind::String("a\nb\nc".to_string()));
+    assert_eq!((tokens[3].span.start.line, tokens[3].span.end.line), (0, 2));
+    assert_eq!((tokens[5].token.clone(), tokens[5].span.start), (TokenKind::Identifier("x".to_string()), Span { line: 3, row: 17 }));
+    assert_eq!(tokens[7].token, TokenKind::UnterminatedString("open\nend".to_string()));
+    assert_eq!(tokens[7].span.start.line, 4);
+}
+
+#[test]
+fn tokens_know_previous_significant_token() {
+    let flags: Vec<(String, bool, bool)> = tokenize("a = b / c // note\n(x) / 2\nreturn")
+        .into_iter()
+        .map(|x| (x.token.to_string(), x.flags.is_regex_allowed, x.flags.is_after_line_break))
+        .collect();
+
+    let expected = [
+        ("Identifier(\"a\")", true, false),
+        ("Equal", false, false),
+        ("Identifier(\"b\")", true, false),
+        ("Div", false, false),
+        ("Identifier(\"c\")", true, false),
+        ("Comment(\" note\")", false, false),
+        ("OpenParen", false, true),
+        ("Identifier(\"x\")", true, false),
+        ("CloseParen", false, false),
+        ("Div", false, false),
+        ("Number(2.0)", true, false),
+        ("ReturnKeyword", false, true),
+    ];
+
+    assert_eq!(flags, expected.map(|(token, is_regex_allowed, is_after_line_break)| (token.to_string(), is_regex_allowed, is_after_line_break)));
+
+    let mut scanner = Scanner::new("x /".to_string());
+    scanner.next_token();
+    assert_eq!(scanner.previous_token(), Some(&TokenKind::Identifier("x".to_string())));
+    assert!(!scanner.is_regex_allowed());
 }
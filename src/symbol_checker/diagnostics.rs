@@ -1,6 +1,6 @@
 use ariadne::{Color, Label, Report, ReportKind, Source};
 use crate::diagnostic::PrintDiagnostic;
-use crate::keywords::{BREAK_KEYWORD, THIS_KEYWORD};
+use crate::keywords::{BREAK_KEYWORD, CONTINUE_KEYWORD, THIS_KEYWORD};
 use crate::scanner::TextSpan;
 
 #[derive(Debug)]
@@ -9,9 +9,8 @@ pub struct ConstantAssigningDiagnostic {
 }
 
 impl PrintDiagnostic for ConstantAssigningDiagnostic {
-    fn print_diagnostic(&self, source: &str) {
-        // TODO: add filename
-        report_symbol_diagnostic(ReportKind::Error, "assignment to constant variable.", &self.id_span, "a.js", source);
+    fn print_diagnostic(&self, source: &str, filename: &str) {
+        report_symbol_diagnostic(ReportKind::Error, "assignment to constant variable.", &self.id_span, filename, source);
     }
 }
 
@@ -22,10 +21,9 @@ pub struct UnusedVariableDiagnostic {
 }
 
 impl PrintDiagnostic for UnusedVariableDiagnostic {
-    fn print_diagnostic(&self, source: &str) {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
         let warning_message = format!("variable '{}' is never used", self.variable_name);
-        // TODO: add filename
-        report_symbol_diagnostic(ReportKind::Warning, warning_message.as_str(), &self.id_span, "a.js", source);
+        report_symbol_diagnostic(ReportKind::Warning, warning_message.as_str(), &self.id_span, filename, source);
     }
 }
 
@@ -33,13 +31,15 @@ impl PrintDiagnostic for UnusedVariableDiagnostic {
 pub struct VariableNotDefinedDiagnostic {
     pub variable_name: String,
     pub id_span: TextSpan,
+    /// Visible symbol or global with a similar name, e.g. `counter` for `countr`
+    pub suggestion: Option<String>,
 }
 
 impl PrintDiagnostic for VariableNotDefinedDiagnostic {
-    fn print_diagnostic(&self, source: &str) {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
         let warning_message = format!("variable '{}' is not defined", self.variable_name);
-        // TODO: add filename
-        report_symbol_diagnostic(ReportKind::Error, warning_message.as_str(), &self.id_span, "a.js", source);
+        let label = self.suggestion.as_ref().map(|x| format!("did you mean '{x}'?"));
+        report_labeled_symbol_diagnostic(ReportKind::Error, warning_message.as_str(), label.as_deref(), &self.id_span, filename, source);
     }
 }
 
@@ -50,10 +50,86 @@ pub struct MultipleAssignmentDiagnostic {
 }
 
 impl PrintDiagnostic for MultipleAssignmentDiagnostic {
-    fn print_diagnostic(&self, source: &str) {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
         let warning_message = format!("identifier '{}' has already been declared", self.symbol_name);
-        // TODO: add filename
-        report_symbol_diagnostic(ReportKind::Error, warning_message.as_str(), &self.id_span, "a.js", source);
+        report_symbol_diagnostic(ReportKind::Error, warning_message.as_str(), &self.id_span, filename, source);
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicateKeyDiagnostic {
+    pub key: String,
+    pub key_span: TextSpan,
+}
+
+impl PrintDiagnostic for DuplicateKeyDiagnostic {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
+        let warning_message = format!("duplicate key '{}' in object literal, only the last value is kept", self.key);
+        report_symbol_diagnostic(ReportKind::Warning, warning_message.as_str(), &self.key_span, filename, source);
+    }
+}
+
+#[derive(Debug)]
+pub struct ShadowedBuiltinDiagnostic {
+    pub symbol_name: String,
+    pub id_span: TextSpan,
+}
+
+impl PrintDiagnostic for ShadowedBuiltinDiagnostic {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
+        let warning_message = format!("declaration of '{}' shadows a builtin global", self.symbol_name);
+        report_symbol_diagnostic(ReportKind::Warning, warning_message.as_str(), &self.id_span, filename, source);
+    }
+}
+
+#[derive(Debug)]
+pub struct ReadOnlyBuiltinDiagnostic {
+    pub variable_name: String,
+    pub id_span: TextSpan,
+}
+
+impl PrintDiagnostic for ReadOnlyBuiltinDiagnostic {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
+        let error_message = format!("builtin global '{}' is read only, it can be reassigned with --mutable-globals", self.variable_name);
+        report_symbol_diagnostic(ReportKind::Error, error_message.as_str(), &self.id_span, filename, source);
+    }
+}
+
+/// Collection a variable is created as, e.g. `let a = [1, 2]` or `let m = new Map()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CollectionKind {
+    Array,
+    Map,
+    Set,
+}
+
+impl CollectionKind {
+    /// Method checking whether the collection contains an item, `in` only checks indices & properties
+    pub fn membership_method(&self) -> &'static str {
+        match self {
+            CollectionKind::Array => "includes",
+            CollectionKind::Map | CollectionKind::Set => "has",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InOperatorOnCollectionDiagnostic {
+    pub collection: CollectionKind,
+    /// Membership check which was likely meant, e.g. `items.includes(x)` for `x in items`
+    pub suggestion: String,
+    pub span: TextSpan,
+}
+
+impl PrintDiagnostic for InOperatorOnCollectionDiagnostic {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
+        let warning_message = match self.collection {
+            CollectionKind::Array => "'in' checks indices of an array, not its values",
+            CollectionKind::Map => "'in' checks properties of a map, not its keys",
+            CollectionKind::Set => "'in' checks properties of a set, not its values",
+        };
+        let label = format!("did you mean '{}'?", self.suggestion);
+        report_labeled_symbol_diagnostic(ReportKind::Warning, warning_message, Some(&label), &self.span, filename, source);
     }
 }
 
@@ -63,10 +139,8 @@ pub struct WrongThisContextDiagnostic {
 }
 
 impl PrintDiagnostic for WrongThisContextDiagnostic {
-    fn print_diagnostic(&self, source: &str) {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
         let span = &self.span;
-        // TODO: add filename
-        let filename = "a.js";
 
         report_wrong_keyword_context(
             THIS_KEYWORD,
@@ -84,14 +158,12 @@ pub struct WrongBreakContextDiagnostic {
 }
 
 impl PrintDiagnostic for WrongBreakContextDiagnostic {
-    fn print_diagnostic(&self, source: &str) {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
         let span = &self.span;
-        // TODO: add filename
-        let filename = "a.js";
 
         report_wrong_keyword_context(
             BREAK_KEYWORD,
-            "keyword 'break' can be used only inside while / for loops",
+            "keyword 'break' can be used only inside while / for loops or switch",
             span,
             filename,
             source,
@@ -99,6 +171,23 @@ impl PrintDiagnostic for WrongBreakContextDiagnostic {
     }
 }
 
+#[derive(Debug)]
+pub struct WrongContinueContextDiagnostic {
+    pub span: TextSpan,
+}
+
+impl PrintDiagnostic for WrongContinueContextDiagnostic {
+    fn print_diagnostic(&self, source: &str, filename: &str) {
+        report_wrong_keyword_context(
+            CONTINUE_KEYWORD,
+            "keyword 'continue' can be used only inside while / for loops",
+            &self.span,
+            filename,
+            source,
+        );
+    }
+}
+
 fn report_wrong_keyword_context(keyword: &str, note: &str, span: &TextSpan, filename: &str, source: &str) {
     let message = format!("keyword '{keyword}' is used inside invalid context");
 
@@ -115,17 +204,24 @@ fn report_wrong_keyword_context(keyword: &str, note: &str, span: &TextSpan, file
 }
 
 fn report_symbol_diagnostic(report_kind: ReportKind, message: &str, span: &TextSpan, filename: &str, source: &str) {
+    report_labeled_symbol_diagnostic(report_kind, message, None, span, filename, source);
+}
+
+fn report_labeled_symbol_diagnostic(report_kind: ReportKind, message: &str, label: Option<&str>, span: &TextSpan, filename: &str, source: &str) {
     let color = match report_kind {
         ReportKind::Error => Color::Red,
         _ => Color::Yellow
     };
 
+    let mut label_node = Label::new((filename, span.start.row..span.end.row)).with_color(color);
+
+    if let Some(label) = label {
+        label_node = label_node.with_message(label);
+    }
+
     Report::build(report_kind, filename, span.start.row)
         .with_message(message)
-        .with_label(
-            Label::new((filename, span.start.row..span.end.row))
-                .with_color(color),
-        )
+        .with_label(label_node)
         .finish()
         .print((filename, Source::from(source)))
         .unwrap();
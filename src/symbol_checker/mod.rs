@@ -1,2 +1,3 @@
 pub mod symbol_checker;
 pub mod diagnostics;
+pub mod pragma;
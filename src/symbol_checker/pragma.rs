@@ -0,0 +1,86 @@
+use std::ops::Range;
+use crate::scanner::{Scanner, TokenKind};
+
+const DISABLE_NEXT_LINE_PRAGMA: &str = "rustjs-disable-next-line";
+const DISABLE_PRAGMA: &str = "rustjs-disable";
+const ENABLE_PRAGMA: &str = "rustjs-enable";
+
+/// Diagnostics silenced by a pragma comment, e.g.
+/// ```js
+/// // rustjs-disable-next-line unused-variable
+/// let unused = 1;
+/// // rustjs-disable constant-assigning, duplicate-key
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppression {
+    /// Codes of silenced diagnostics, empty means every diagnostic
+    pub codes: Vec<String>,
+    /// Lines counted from 0, `rustjs-disable` lasts till `rustjs-enable` with the same codes or the end of the file
+    pub lines: Range<usize>,
+}
+
+impl Suppression {
+    pub fn is_suppressed(&self, code: &str, line: usize) -> bool {
+        return self.lines.contains(&line) && (self.codes.is_empty() || self.codes.iter().any(|x| x == code));
+    }
+}
+
+/// Collects suppressions from `//` comments of the source
+pub fn parse_pragmas(source: &str) -> Vec<Suppression> {
+    let mut scanner = Scanner::new(source.to_string());
+    let mut suppressions = vec![];
+    // indexes of `rustjs-disable` suppressions which are not closed yet
+    let mut open_suppressions: Vec<usize> = vec![];
+
+    while let Some(token) = scanner.next_token() {
+        let TokenKind::Comment(text) = token.token else {
+            continue;
+        };
+
+        let line = token.span.start.line;
+        let text = text.trim();
+        let (pragma, codes) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let codes: Vec<String> = codes.split(',')
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        match pragma {
+            DISABLE_NEXT_LINE_PRAGMA => suppressions.push(Suppression { codes, lines: line + 1..line + 2 }),
+            DISABLE_PRAGMA => {
+                open_suppressions.push(suppressions.len());
+                suppressions.push(Suppression { codes, lines: line..usize::MAX });
+            }
+            ENABLE_PRAGMA => open_suppressions.retain(|index| {
+                let suppression = &mut suppressions[*index];
+
+                if codes.is_empty() || suppression.codes == codes {
+                    suppression.lines.end = line;
+                    return false;
+                }
+
+                return true;
+            }),
+            _ => {}
+        }
+    }
+
+    return suppressions;
+}
+
+#[test]
+fn pragmas_are_parsed_into_line_ranges() {
+    let code = "
+        // rustjs-disable-next-line unused-variable, duplicate-key
+        let a = 1;
+        // rustjs-disable
+        a = 2;
+        // rustjs-enable
+        // just a comment
+    ";
+
+    assert_eq!(parse_pragmas(code), vec![
+        Suppression { codes: vec!["unused-variable".to_string(), "duplicate-key".to_string()], lines: 2..3 },
+        Suppression { codes: vec![], lines: 3..5 },
+    ]);
+}
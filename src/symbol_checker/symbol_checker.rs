@@ -2,10 +2,12 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::collections::HashMap;
 use crate::diagnostic::{Diagnostic, DiagnosticBagRef, DiagnosticKind};
+use crate::interpreter::ast_interpreter::BUILTIN_GLOBALS;
 use crate::nodes::*;
 // use crate::node::{AssignmentExpressionNode, AstExpression, AstStatement, BlockStatementNode, ClassDeclarationNode, ForStatementNode, FunctionDeclarationNode, GetSpan, IdentifierNode, VariableDeclarationKind, VariableDeclarationNode, WhileStatementNode};
 use crate::scanner::{TextSpan, Token};
-use crate::symbol_checker::diagnostics::{ConstantAssigningDiagnostic, MultipleAssignmentDiagnostic, UnusedVariableDiagnostic, VariableNotDefinedDiagnostic, WrongBreakContextDiagnostic, WrongThisContextDiagnostic};
+use crate::symbol_checker::diagnostics::{CollectionKind, ConstantAssigningDiagnostic, DuplicateKeyDiagnostic, InOperatorOnCollectionDiagnostic, MultipleAssignmentDiagnostic, ReadOnlyBuiltinDiagnostic, ShadowedBuiltinDiagnostic, UnusedVariableDiagnostic, VariableNotDefinedDiagnostic, WrongBreakContextDiagnostic, WrongContinueContextDiagnostic, WrongThisContextDiagnostic};
+use crate::symbol_checker::pragma::parse_pragmas;
 use crate::visitor::Visitor;
 
 /// Should traverse ast and find unused variables & assigning to constant variables
@@ -14,30 +16,65 @@ pub struct SymbolChecker<'a> {
     environment: RefCell<LightEnvironmentRef>,
     diagnostic_bag: DiagnosticBagRef<'a>,
     is_inside_this_context: bool,
-    break_context_stack: Vec<bool>,
+    break_context_stack: Vec<BreakContext>,
+    /// Unused top level declarations are reported, unless scripts run after this one can use them
+    is_checking_unused_top_level: bool,
+    /// Builtin globals can be reassigned, like with `--mutable-globals` of the interpreter
+    is_mutable_globals: bool,
+}
+
+/// Innermost construct `break` & `continue` statements belong to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BreakContext {
+    Loop,
+    /// `break` leaves switch, but `continue` refers to the loop around it
+    Switch,
+    /// Function body resets context, loops around the function can't be left from inside of it
+    Function,
 }
 
 impl<'a> SymbolChecker<'a> {
+    /// Pragma comments of the source are applied to the diagnostic bag, so suppressed diagnostics are never reported
     pub fn new(source: &'a str, diagnostic_bag: DiagnosticBagRef<'a>) -> Self {
+        diagnostic_bag.borrow_mut().add_suppressions(parse_pragmas(source));
+
         Self {
             environment: RefCell::new(Rc::new(RefCell::new(LightEnvironment::default()))),
             source,
             diagnostic_bag,
             is_inside_this_context: false,
             break_context_stack: vec![],
+            is_checking_unused_top_level: true,
+            is_mutable_globals: false,
         }
     }
 
     pub fn check_symbols(&mut self, stmt: &AstStatement) {
         self.visit_statement(stmt);
-        self.check_unused_symbols();
+
+        if self.is_checking_unused_top_level {
+            self.check_unused_symbols();
+        }
+    }
+
+    /// Disabled for all but the last of several scripts sharing globals, a later script can use declarations of an earlier one
+    pub fn set_top_level_unused_check(&mut self, is_enabled: bool) {
+        self.is_checking_unused_top_level = is_enabled;
+    }
+
+    pub fn set_mutable_globals(&mut self, is_mutable: bool) {
+        self.is_mutable_globals = is_mutable;
     }
 
     fn check_unused_symbols(&self) {
         let current_environment = self.environment.borrow();
         let current_environment = current_environment.borrow();
 
-        current_environment.symbols.keys().for_each(|symbol_name| {
+        // reported in source order, symbols are stored in a hash map
+        let mut symbol_names: Vec<&String> = current_environment.symbols.keys().collect();
+        symbol_names.sort_by_key(|x| current_environment.symbols[*x].span.start.row);
+
+        symbol_names.into_iter().for_each(|symbol_name| {
             let usage = current_environment.usages.get(symbol_name);
 
             if usage.is_none() {
@@ -54,9 +91,25 @@ impl<'a> SymbolChecker<'a> {
         });
     }
 
+    /// Declares a global coming from a previously executed script, it's considered used
+    pub fn define_external_global(&mut self, symbol_name: &str, is_const: bool) {
+        let environment = self.environment.borrow();
+        let mut environment = environment.borrow_mut();
+        environment.define_variable(symbol_name, Symbol { is_const, span: TextSpan::default(), collection: None });
+        environment.add_usage(symbol_name, TextSpan::default());
+    }
+
     fn define_variable(&mut self, symbol_name: &str, is_const: bool, span: TextSpan) {
+        if BUILTIN_GLOBALS.contains(&symbol_name) {
+            self.diagnostic_bag.borrow_mut().report_warning(
+                Diagnostic::new(DiagnosticKind::ShadowedBuiltin(
+                    ShadowedBuiltinDiagnostic { symbol_name: symbol_name.to_string(), id_span: span.clone() }
+                ), self.source)
+            );
+        }
+
         let error = self.environment.borrow().borrow_mut()
-            .define_variable(symbol_name, Symbol { is_const, span: span.clone() });
+            .define_variable(symbol_name, Symbol { is_const, span: span.clone(), collection: None });
 
         if error.is_some() {
             self.diagnostic_bag.borrow_mut().report_error(
@@ -67,12 +120,146 @@ impl<'a> SymbolChecker<'a> {
         }
     }
 
+    /// `x in items` checks indices or properties of an array, map or set, so it's likely meant as a membership check
+    fn check_in_operator(&mut self, node: &BinaryExpressionNode) {
+        let collection = match node.right.as_ref() {
+            AstExpression::Identifier(identifier) => self.environment.borrow().borrow().find_symbol(&identifier.id).and_then(|x| x.collection),
+            expression => collection_kind(expression),
+        };
+
+        let Some(collection) = collection else {
+            return;
+        };
+
+        let source_text = |span: TextSpan| self.source.chars().skip(span.start.row).take(span.end.row - span.start.row).collect::<String>();
+        let suggestion = format!(
+            "{}.{}({})",
+            source_text(node.right.get_span()),
+            collection.membership_method(),
+            source_text(node.left.get_span()),
+        );
+
+        self.diagnostic_bag.borrow_mut().report_warning(
+            Diagnostic::new(DiagnosticKind::InOperatorOnCollection(
+                InOperatorOnCollectionDiagnostic { collection, suggestion, span: node.left.get_span().join(&node.right.get_span()) }
+            ), self.source)
+        );
+    }
+
+    /// Closest visible symbol or builtin global to a misspelled name, names further than a third of its length aren't similar.
+    /// The name itself is never suggested
+    fn find_similar_name(&self, name: &str) -> Option<String> {
+        let mut candidates = self.environment.borrow().borrow().visible_names();
+        candidates.extend(BUILTIN_GLOBALS.iter().map(|x| x.to_string()));
+        candidates.retain(|x| x != name);
+        candidates.sort();
+
+        let max_distance = (name.chars().count() / 3).max(1);
+
+        return candidates.into_iter()
+            .map(|x| (levenshtein_distance(name, &x), x))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, x)| x);
+    }
+
+    /// Checks left side of an assignment, destructuring patterns assign to every nested target
+    fn check_assignment_target(&mut self, target: &AstExpression) {
+        match target {
+            AstExpression::Identifier(id_node) => {
+                self.visit_identifier_node(id_node);
+
+                let diagnostic = self.environment.borrow()
+                    .borrow_mut()
+                    .assign_variable(&id_node.id);
+
+                if diagnostic.is_some() {
+                    match diagnostic.unwrap() {
+                        AssignVariableResult::ConstantAssigning => {
+                            self.diagnostic_bag.borrow_mut().report_error(
+                                Diagnostic::new(DiagnosticKind::ConstantAssigning(
+                                    ConstantAssigningDiagnostic { id_span: target.get_span() }
+                                ), self.source)
+                            );
+                        }
+                        AssignVariableResult::VariableNotDefined if BUILTIN_GLOBALS.contains(&id_node.id.as_str()) => {
+                            if !self.is_mutable_globals {
+                                self.diagnostic_bag.borrow_mut().report_error(
+                                    Diagnostic::new(DiagnosticKind::ReadOnlyBuiltin(
+                                        ReadOnlyBuiltinDiagnostic { variable_name: id_node.id.clone(), id_span: target.get_span() }
+                                    ), self.source)
+                                );
+                            }
+                        }
+                        AssignVariableResult::VariableNotDefined => {
+                            self.diagnostic_bag.borrow_mut().report_error(
+                                Diagnostic::new(DiagnosticKind::VariableNotDefined(
+                                    VariableNotDefinedDiagnostic {
+                                        variable_name: id_node.id.clone(),
+                                        id_span: target.get_span(),
+                                        suggestion: self.find_similar_name(&id_node.id),
+                                    }
+                                ), self.source)
+                            );
+                        }
+                    }
+                }
+            }
+            AstExpression::MemberExpression(node) => {
+                self.visit_member_expression(node);
+            }
+            AstExpression::ArrayExpression(node) => {
+                node.items.iter().flatten().for_each(|x| self.check_assignment_target(x));
+            }
+            AstExpression::ObjectExpression(node) => {
+                for property in &node.properties {
+                    if property.computed {
+                        self.visit_expression(&property.key);
+                    }
+
+                    self.check_assignment_target(&property.value);
+                }
+            }
+            AstExpression::SpreadElement(node) => self.check_assignment_target(&node.argument),
+            // target with a default value inside of a destructuring pattern
+            AstExpression::AssignmentExpression(node) => {
+                self.visit_expression(&node.right);
+                self.check_assignment_target(&node.left);
+            }
+            // not a valid target, but variables it reads are still used
+            target => self.visit_expression(target),
+        }
+    }
+
+    /// Visits with `this` allowed or not, the enclosing context is restored afterwards,
+    /// so e.g. a function declared inside of a method doesn't disallow `this` in the rest of the method
+    fn with_this_context(&mut self, is_inside_this_context: bool, callback: impl FnOnce(&mut Self)) {
+        let previous = std::mem::replace(&mut self.is_inside_this_context, is_inside_this_context);
+        callback(self);
+        self.is_inside_this_context = previous;
+    }
+
+    /// Function expression stored into a property of an object is a method, `this` refers to the object it's called on
+    fn visit_method_value(&mut self, value: &AstExpression) {
+        match value {
+            AstExpression::FunctionExpression(node) => self.with_this_context(true, |checker| checker.visit_function_expression(node)),
+            _ => self.visit_expression(value),
+        }
+    }
+
     fn create_new_environment(&self) -> LightEnvironment {
         return LightEnvironment::new(Rc::clone(&self.environment.borrow().clone()));
     }
 
     fn set_environment(&self, environment: LightEnvironment) {
-        self.environment.replace(Rc::new(RefCell::new(environment)));
+        let environment = Rc::new(RefCell::new(environment));
+
+        // child scopes are retained by their parents, so the whole scope tree is available after checking
+        if let Some(parent) = environment.borrow().get_parent() {
+            parent.borrow_mut().children.push(Rc::clone(&environment));
+        }
+
+        self.environment.replace(environment);
     }
 
     fn pop_environment(&mut self) {
@@ -83,19 +270,54 @@ impl<'a> SymbolChecker<'a> {
             .borrow()
             .borrow()
             .get_parent()
-            .unwrap()
-            .borrow()
-            .to_owned();
+            .unwrap();
 
-        self.set_environment(parent_environment);
+        self.environment.replace(parent_environment);
     }
 
-    fn enter_break_context(&mut self) {
-        self.break_context_stack.push(true);
+    /// Returns a readable report of the symbol table: for every scope its declared symbols
+    /// with constness, declaration span and usage count, nested scopes are indented
+    pub fn dump_scopes(&self) -> String {
+        let mut result = String::new();
+        let mut root = Rc::clone(&self.environment.borrow());
+
+        while let Some(parent) = root.clone().borrow().get_parent() {
+            root = parent;
+        }
+
+        let mut scope_id = 0;
+        Self::dump_scope(&root.borrow(), 0, &mut scope_id, &mut result);
+        result
     }
 
-    fn out_break_context(&mut self) {
-        self.break_context_stack.push(false);
+    fn dump_scope(environment: &LightEnvironment, depth: usize, scope_id: &mut usize, result: &mut String) {
+        let indent = "  ".repeat(depth);
+        let kind = if depth == 0 { "global" } else { "block" };
+        result.push_str(&format!("{indent}scope #{scope_id} ({kind})\n"));
+        *scope_id += 1;
+
+        let mut symbols: Vec<(&String, &Symbol)> = environment.symbols.iter().collect();
+        symbols.sort_by_key(|(_, symbol)| symbol.span.start.row);
+
+        for (name, symbol) in symbols {
+            let usages = environment.usages.get(name).map_or(0, |x| x.len());
+
+            result.push_str(&format!(
+                "{indent}  {} {name} (line {}, {}..{}) used {usages} time(s)\n",
+                if symbol.is_const { "const" } else { "let" },
+                symbol.span.start.line + 1,
+                symbol.span.start.row,
+                symbol.span.end.row,
+            ));
+        }
+
+        for child in &environment.children {
+            Self::dump_scope(&child.borrow(), depth + 1, scope_id, result);
+        }
+    }
+
+    fn enter_break_context(&mut self, context: BreakContext) {
+        self.break_context_stack.push(context);
     }
 
     fn pop_break_context(&mut self) {
@@ -106,7 +328,9 @@ impl<'a> SymbolChecker<'a> {
 #[derive(Debug, Clone)]
 struct Symbol {
     span: TextSpan,
-    is_const: bool
+    is_const: bool,
+    /// Collection the variable is initialized with, see [`collection_kind`]
+    collection: Option<CollectionKind>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -114,6 +338,7 @@ struct LightEnvironment {
     parent: Option<LightEnvironmentRef>,
     symbols: HashMap<String, Symbol>,
     usages: HashMap<String, Vec<TextSpan>>,
+    children: Vec<LightEnvironmentRef>,
 }
 
 type LightEnvironmentRef = Rc<RefCell<LightEnvironment>>;
@@ -130,6 +355,7 @@ impl LightEnvironment {
             parent: Some(parent),
             symbols: HashMap::new(),
             usages: HashMap::new(),
+            children: vec![],
         }
     }
 
@@ -156,6 +382,14 @@ impl LightEnvironment {
         }
     }
 
+    fn find_symbol(&self, variable_name: &str) -> Option<Symbol> {
+        if let Some(symbol) = self.symbols.get(variable_name) {
+            return Some(symbol.clone());
+        }
+
+        return self.parent.as_ref().and_then(|x| x.borrow().find_symbol(variable_name));
+    }
+
     fn assign_variable(&mut self, variable_name: &str) -> Option<AssignVariableResult> {
         if self.symbols.contains_key(variable_name) {
             let symbol = self.symbols.get(variable_name).unwrap();
@@ -180,6 +414,53 @@ impl LightEnvironment {
     pub fn get_parent(&self) -> Option<LightEnvironmentRef> {
         self.parent.as_ref().map(|x| Rc::clone(x))
     }
+
+    /// Names of symbols of this environment & all enclosing ones
+    fn visible_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.symbols.keys().cloned().collect();
+
+        if let Some(parent) = &self.parent {
+            names.extend(parent.borrow().visible_names());
+        }
+
+        return names;
+    }
+}
+
+/// Number of single character insertions, deletions & substitutions turning one string into another
+/// Collection created by the expression, arrays by literals & collections by `new` of their builtin constructors
+fn collection_kind(expression: &AstExpression) -> Option<CollectionKind> {
+    let AstExpression::NewExpression(node) = expression else {
+        return matches!(expression, AstExpression::ArrayExpression(_)).then_some(CollectionKind::Array);
+    };
+
+    match node.callee.as_ref() {
+        AstExpression::Identifier(identifier) => match identifier.id.as_str() {
+            "Array" => Some(CollectionKind::Array),
+            "Map" | "WeakMap" => Some(CollectionKind::Map),
+            "Set" | "WeakSet" => Some(CollectionKind::Set),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + if a_char == *b_char { 0 } else { 1 };
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+
+        previous_row = row;
+    }
+
+    return previous_row[b.len()];
 }
 
 impl<'a> Visitor for SymbolChecker<'a> {
@@ -187,6 +468,10 @@ impl<'a> Visitor for SymbolChecker<'a> {
         let variable_name = &stmt.id.id;
         self.define_variable(&variable_name, matches!(stmt.kind, VariableDeclarationKind::Const), stmt.id.get_span());
 
+        if let Some(symbol) = self.environment.borrow().borrow_mut().symbols.get_mut(variable_name) {
+            symbol.collection = stmt.value.as_ref().and_then(|x| collection_kind(x));
+        }
+
         if let Some(value) = &stmt.value {
             self.visit_expression(value);
         }
@@ -199,38 +484,31 @@ impl<'a> Visitor for SymbolChecker<'a> {
     }
 
     fn visit_assignment_expression(&mut self, stmt: &AssignmentExpressionNode) {
-        match &stmt.left.as_ref() {
-            AstExpression::Identifier(id_node) => {
-                self.visit_identifier_node(id_node);
+        if matches!(stmt.left.as_ref(), AstExpression::MemberExpression(_)) {
+            self.visit_method_value(&stmt.right);
+        } else {
+            self.visit_expression(&stmt.right);
+        }
 
-                let diagnostic = self.environment.borrow()
-                    .borrow_mut()
-                    .assign_variable(&id_node.id);
+        self.check_assignment_target(&stmt.left);
+    }
 
-                if diagnostic.is_some() {
-                    match diagnostic.unwrap() {
-                        AssignVariableResult::ConstantAssigning => {
-                            self.diagnostic_bag.borrow_mut().report_error(
-                                Diagnostic::new(DiagnosticKind::ConstantAssigning(
-                                    ConstantAssigningDiagnostic { id_span: stmt.left.get_span() }
-                                ), self.source)
-                            );
-                        }
-                        AssignVariableResult::VariableNotDefined => {
-                            self.diagnostic_bag.borrow_mut().report_error(
-                                Diagnostic::new(DiagnosticKind::VariableNotDefined(
-                                    VariableNotDefinedDiagnostic { variable_name: id_node.id.clone(), id_span: stmt.left.get_span() }
-                                ), self.source)
-                            );
-                        }
-                    }
-                }
-            }
-            AstExpression::MemberExpression(node) => {
-                self.visit_member_expression(node);
-            }
-            _ => todo!(),
+    /// Property of `a.b` is a name, not a variable, only computed keys like `a[b]` use variables
+    fn visit_member_expression(&mut self, stmt: &MemberExpressionNode) {
+        self.visit_expression(&stmt.object);
+
+        if stmt.computed {
+            self.visit_expression(&stmt.property);
+        }
+    }
+
+    fn visit_binary_expression(&mut self, stmt: &BinaryExpressionNode) {
+        if stmt.operator == BinaryOperator::In {
+            self.check_in_operator(stmt);
         }
+
+        self.visit_expression(&stmt.left);
+        self.visit_expression(&stmt.right);
     }
 
     fn visit_identifier_node(&mut self, stmt: &IdentifierNode) {
@@ -244,16 +522,15 @@ impl<'a> Visitor for SymbolChecker<'a> {
             self.visit_identifier_node(parent);
         }
 
-        self.is_inside_this_context = true;
-        stmt.methods.iter().for_each(|x| self.visit_class_method(x));
-        self.is_inside_this_context = false;
+        self.with_this_context(true, |checker| {
+            stmt.fields.iter().for_each(|x| checker.visit_class_field(x));
+            stmt.methods.iter().for_each(|x| checker.visit_class_method(x));
+        });
     }
 
     fn visit_function_declaration(&mut self, stmt: &FunctionDeclarationNode) {
-        self.out_break_context();
-        self.is_inside_this_context = true;
-        self.visit_function_signature(&stmt.function_signature);
-        self.is_inside_this_context = false;
+        self.enter_break_context(BreakContext::Function);
+        self.with_this_context(true, |checker| checker.visit_function_signature(&stmt.function_signature));
         self.define_variable(stmt.function_signature.name.id.as_str(), false, stmt.function_signature.name.get_span());
         self.pop_break_context();
     }
@@ -269,7 +546,7 @@ impl<'a> Visitor for SymbolChecker<'a> {
     }
 
     fn visit_while_statement(&mut self, node: &WhileStatementNode) {
-        self.enter_break_context();
+        self.enter_break_context(BreakContext::Loop);
         self.visit_expression(&node.condition);
         self.visit_statement(&node.body);
         self.pop_break_context();
@@ -288,14 +565,86 @@ impl<'a> Visitor for SymbolChecker<'a> {
             self.visit_expression(update);
         }
 
-        self.enter_break_context();
+        self.enter_break_context(BreakContext::Loop);
         self.visit_statement(&stmt.body);
         self.pop_break_context();
     }
 
+    fn visit_object_expression(&mut self, node: &ObjectExpressionNode) {
+        let mut keys: Vec<String> = vec![];
+
+        for property in &node.properties {
+            self.visit_object_property(property);
+
+            let key = match static_property_key(&property.key) {
+                Some(key) if !property.computed => key.into_owned(),
+                _ => continue,
+            };
+
+            if keys.contains(&key) {
+                self.diagnostic_bag.borrow_mut().report_warning(
+                    Diagnostic::new(DiagnosticKind::DuplicateKey(
+                        DuplicateKeyDiagnostic { key: key.clone(), key_span: property.key.get_span() }
+                    ), self.source)
+                );
+            }
+
+            keys.push(key);
+        }
+    }
+
+    fn visit_object_property(&mut self, node: &ObjectPropertyNode) {
+        self.visit_method_value(&node.value);
+        self.visit_expression(&node.key);
+    }
+
+    fn visit_function_expression(&mut self, node: &FunctionExpressionNode) {
+        self.enter_break_context(BreakContext::Function);
+        node.arguments.iter().for_each(|x| self.visit_function_argument(x));
+        self.visit_statement(&node.body);
+        self.pop_break_context();
+    }
+
+    fn visit_class_method(&mut self, stmt: &ClassMethodNode) {
+        self.enter_break_context(BreakContext::Function);
+        self.visit_function_signature(&stmt.function_signature);
+        self.pop_break_context();
+    }
+
+    fn visit_switch_statement(&mut self, node: &SwitchStatementNode) {
+        self.visit_expression(&node.discriminant);
+        self.set_environment(self.create_new_environment());
+        self.enter_break_context(BreakContext::Switch);
+
+        for case in &node.cases {
+            if let Some(test) = &case.test {
+                self.visit_expression(test);
+            }
+
+            case.consequent.iter().for_each(|x| self.visit_statement(x));
+        }
+
+        self.pop_break_context();
+        self.pop_environment();
+    }
+
+    fn visit_continue_statement(&mut self, token: &Token) {
+        let context = self.break_context_stack.iter().rev().find(|x| **x != BreakContext::Switch);
+
+        if context != Some(&BreakContext::Loop) {
+            self.diagnostic_bag.borrow_mut().report_error(
+                Diagnostic::new(DiagnosticKind::WrongContinueContext(
+                    WrongContinueContextDiagnostic { span: token.span.clone() }
+                ), self.source)
+            );
+        }
+    }
+
     fn visit_break_statement(&mut self, token: &Token) {
-        let break_context_state = self.break_context_stack.last();
-        let is_inside_break_context = break_context_state.is_some() && *break_context_state.unwrap();
+        let is_inside_break_context = matches!(
+            self.break_context_stack.last(),
+            Some(BreakContext::Loop | BreakContext::Switch)
+        );
 
         if !is_inside_break_context {
             self.diagnostic_bag.borrow_mut().report_error(
@@ -306,3 +655,289 @@ impl<'a> Visitor for SymbolChecker<'a> {
         }
     }
 }
+
+#[test]
+fn dump_scopes_keeps_nested_scopes() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "let a = 5; { const b = a; { let c = b; } }";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    let mut symbol_checker = SymbolChecker::new(code, Rc::clone(&diagnostic_bag));
+    symbol_checker.check_symbols(&ast);
+
+    assert_eq!(
+        symbol_checker.dump_scopes(),
+        "scope #0 (global)\n  let a (line 1, 4..5) used 1 time(s)\n  scope #1 (block)\n    const b (line 1, 19..20) used 1 time(s)\n    scope #2 (block)\n      let c (line 1, 32..33) used 0 time(s)\n"
+    );
+}
+
+#[test]
+fn duplicate_object_keys_are_reported() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "let a = { x: 1, 'y': 2, 1: 3, ['x']: 4, '1': 5, x: 6 }; a;";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let duplicate_keys: Vec<String> = diagnostic_bag.borrow().warnings.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::DuplicateKey(diagnostic) => Some(diagnostic.key.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(duplicate_keys, vec!["1".to_string(), "x".to_string()]);
+}
+
+#[test]
+fn break_and_continue_contexts_are_checked() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "
+        while (true) { switch (1) { case 1: continue; default: break; } break; }
+        switch (1) { case 1: continue; }
+        while (true) { let f = function() { break; }; f(); }
+        break;
+    ";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let errors: Vec<&str> = diagnostic_bag.borrow().errors.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::WrongBreakContext(_) => Some("break"),
+            DiagnosticKind::WrongContinueContext(_) => Some("continue"),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(errors, vec!["continue", "break", "break"]);
+}
+
+#[test]
+fn this_is_allowed_in_methods_of_object_literals() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "
+        let item = { abc: 1, getAbc: function () { return this.abc; } };
+        item.setAbc = function (value) { this.abc = value; };
+        class Counter { increment() { function helper() {} helper(); return this; } }
+        let detached = function () { return this; };
+        this;
+        item; Counter; detached;
+    ";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let lines: Vec<usize> = diagnostic_bag.borrow().errors.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::WrongThisContext(diagnostic) => Some(diagnostic.span.start.line + 1),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(lines, vec![5, 6]);
+}
+
+#[test]
+fn shadowing_builtin_globals_is_reported() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "let console = 1; function eval(code) { return code; } eval(console); let math = 2; math;";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let shadowed: Vec<String> = diagnostic_bag.borrow().warnings.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::ShadowedBuiltin(diagnostic) => Some(diagnostic.symbol_name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(shadowed, vec!["console", "eval"]);
+}
+
+#[test]
+fn pragma_comments_suppress_diagnostics() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "
+        // rustjs-disable-next-line unused-variable
+        let first = 1;
+        let second = 2;
+        const c = 3;
+        // rustjs-disable constant-assigning
+        c = 4;
+        // rustjs-enable constant-assigning
+        c = 5;
+    ";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let diagnostic_bag = diagnostic_bag.borrow();
+    let reported: Vec<(&str, usize)> = diagnostic_bag.warnings.iter()
+        .chain(diagnostic_bag.errors.iter())
+        .map(|x| (x.kind().code(), x.kind().span().start.line))
+        .collect();
+
+    assert_eq!(reported, vec![("unused-variable", 3), ("constant-assigning", 8)]);
+}
+
+#[test]
+fn member_assignment_targets_track_usages() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "
+        let key = 'a';
+        let inner = 'b';
+        let name = 1;
+        let object = { nested: {} };
+        object[key] = 1;
+        object.nested[inner] = 2;
+        object.name = 3;
+        [object.nested[key]] = [4];
+    ";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let unused: Vec<String> = diagnostic_bag.borrow().warnings.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::UnusedVariable(diagnostic) => Some(diagnostic.variable_name.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(unused, vec!["name"]);
+    assert!(diagnostic_bag.borrow().errors.is_empty());
+}
+
+#[test]
+fn not_defined_variable_suggests_similar_name() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "
+        let counter = 0;
+        function f() { let total = 1; totl = counter; }
+        countr = 1;
+        Mat = 2;
+        somethingElse = 3;
+        f();
+    ";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let suggestions: Vec<(String, Option<String>)> = diagnostic_bag.borrow().errors.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::VariableNotDefined(diagnostic) => Some((diagnostic.variable_name.clone(), diagnostic.suggestion.clone())),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(suggestions, vec![
+        ("totl".to_string(), Some("total".to_string())),
+        ("countr".to_string(), Some("counter".to_string())),
+        ("Mat".to_string(), Some("Math".to_string())),
+        ("somethingElse".to_string(), None),
+    ]);
+    assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+
+    let checker = SymbolChecker::new("", Rc::new(RefCell::new(DiagnosticBag::new())));
+    assert_eq!(checker.find_similar_name("console"), None);
+    assert_eq!(checker.find_similar_name("consle"), Some("console".to_string()));
+}
+
+#[test]
+fn in_operator_on_collections_is_reported() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let code = "
+const items = [1, 2];
+let seen = new WeakSet();
+let options = { verbose: true };
+2 in items;
+'verbose' in options;
+items in seen;
+0 in [3, 4];
+";
+    let ast = Parser::parse_code_to_ast(code).unwrap();
+    let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+    SymbolChecker::new(code, Rc::clone(&diagnostic_bag)).check_symbols(&ast);
+
+    let suggestions: Vec<(String, usize)> = diagnostic_bag.borrow().warnings.iter()
+        .filter_map(|x| match x.kind() {
+            DiagnosticKind::InOperatorOnCollection(diagnostic) => Some((diagnostic.suggestion.clone(), diagnostic.span.start.line)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(suggestions, vec![
+        ("items.includes(2)".to_string(), 4),
+        ("seen.has(items)".to_string(), 6),
+        ("[3, 4].includes(0)".to_string(), 7),
+    ]);
+}
+
+#[test]
+fn top_level_unused_check_can_be_left_to_following_scripts() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let check = |code: &str, is_checking_unused_top_level: bool| {
+        let ast = Parser::parse_code_to_ast(code).unwrap();
+        let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+        let mut checker = SymbolChecker::new(code, Rc::clone(&diagnostic_bag));
+        checker.set_top_level_unused_check(is_checking_unused_top_level);
+        checker.check_symbols(&ast);
+
+        let unused: Vec<String> = diagnostic_bag.borrow().warnings.iter()
+            .filter_map(|x| match x.kind() {
+                DiagnosticKind::UnusedVariable(diagnostic) => Some(diagnostic.variable_name.clone()),
+                _ => None,
+            })
+            .collect();
+        unused
+    };
+
+    let code = "function greet() { let unused = 1; }";
+    assert_eq!(check(code, true), vec!["unused".to_string(), "greet".to_string()]);
+    assert_eq!(check(code, false), vec!["unused".to_string()]);
+}
+
+#[test]
+fn assigning_to_builtin_globals_depends_on_mutable_globals() {
+    use crate::diagnostic::DiagnosticBag;
+    use crate::parser::Parser;
+
+    let check = |is_mutable_globals: bool| {
+        let code = "console = 5; Math = 1; missing = 2;";
+        let ast = Parser::parse_code_to_ast(code).unwrap();
+        let diagnostic_bag = Rc::new(RefCell::new(DiagnosticBag::new()));
+        let mut checker = SymbolChecker::new(code, Rc::clone(&diagnostic_bag));
+        checker.set_mutable_globals(is_mutable_globals);
+        checker.check_symbols(&ast);
+
+        let errors: Vec<(&'static str, usize)> = diagnostic_bag.borrow().errors.iter()
+            .map(|x| (x.kind().code(), x.kind().span().start.row))
+            .collect();
+        errors
+    };
+
+    assert_eq!(check(false), vec![("read-only-builtin", 0), ("read-only-builtin", 13), ("variable-not-defined", 23)]);
+    assert_eq!(check(true), vec![("variable-not-defined", 23)]);
+}
@@ -2,15 +2,16 @@ use std::cell::RefCell;
 use std::fmt::{Debug, Formatter};
 use std::rc::Rc;
 use crate::interpreter::environment::*;
-use crate::interpreter::ast_interpreter::{Execute, Interpreter};
-use crate::nodes::{AstStatement, BlockStatementNode};
+use crate::interpreter::ast_interpreter::{Completion, Execute, Interpreter, ScriptSource};
+use crate::nodes::{AstStatement, BlockStatementNode, ClassFieldNode, GetSpan, TextSpan};
 use crate::value::JsValue;
-use crate::value::object::{JsObject, ObjectKind};
+use crate::value::object::{JsObject, JsObjectRef, ObjectKind};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum JsFunction {
     Ordinary(OrdinaryFunction),
     Native(NativeFunction),
+    Bound(BoundFunction),
 }
 
 impl JsFunction {
@@ -18,7 +19,7 @@ impl JsFunction {
         Self::Native(NativeFunction { function })
     }
 
-    pub fn ordinary_function(arguments: Vec<JsFunctionArg>, body: Box<AstStatement>, environment: EnvironmentRef) -> Self {
+    pub fn ordinary_function(arguments: Vec<JsFunctionArg>, body: Rc<AstStatement>, environment: EnvironmentRef) -> Self {
         OrdinaryFunction::new(arguments, body, environment).into()
     }
 
@@ -37,28 +38,161 @@ impl Into<JsValue> for JsFunction {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct OrdinaryFunction {
+    /// Name of a declaration or a method, empty for anonymous functions
+    pub name: String,
     pub arguments: Vec<JsFunctionArg>,
-    pub body: Box<AstStatement>,
+    pub body: Rc<AstStatement>,
     pub environment: EnvironmentRef,
+    /// Set for constructors of classes, they can be called only with `new`
+    pub class_name: Option<String>,
+    /// Instance fields of a class, initialized before the constructor body runs
+    pub fields: Rc<Vec<ClassFieldNode>>,
+    /// Set for synthesized constructors of derived classes, they pass their arguments to the parent constructor
+    pub parent_constructor: Option<JsObjectRef>,
+    /// Script the function is declared in, errors inside of it are shown with an excerpt of that script
+    pub source: Option<Rc<ScriptSource>>,
 }
 
 impl OrdinaryFunction {
-    pub fn new(arguments: Vec<JsFunctionArg>, body: Box<AstStatement>, environment: EnvironmentRef) -> Self {
+    pub fn new(arguments: Vec<JsFunctionArg>, body: Rc<AstStatement>, environment: EnvironmentRef) -> Self {
         Self {
+            name: String::new(),
             arguments,
             body,
             environment,
+            class_name: None,
+            fields: Rc::new(vec![]),
+            parent_constructor: None,
+            source: None,
         }
     }
 
     pub fn empty_function() -> Self {
         Self {
+            name: String::new(),
             arguments: vec![],
-            body: Box::new(AstStatement::BlockStatement(BlockStatementNode { statements: vec![] })),
+            body: Rc::new(AstStatement::BlockStatement(BlockStatementNode { statements: vec![], span: TextSpan::default() })),
             environment: Rc::new(RefCell::new(Environment::default())),
+            class_name: None,
+            fields: Rc::new(vec![]),
+            parent_constructor: None,
+            source: None,
+        }
+    }
+
+    /// Runs parent constructor of a synthesized derived constructor & initializes own fields of the new instance,
+    /// fields of the parent are initialized by the parent constructor before own ones,
+    /// every field value is evaluated in its own scope where `this` is the instance
+    fn initialize_instance(&self, interpreter: &Interpreter, this: &JsValue, arguments: &[JsValue]) -> Result<(), String> {
+        if let Some(parent_constructor) = &self.parent_constructor {
+            // cloned out of the object, so the parent constructor can access its own function object
+            let parent_function = match &parent_constructor.borrow().kind {
+                ObjectKind::Function(function) => function.clone(),
+                _ => return Err("TypeError: Parent class is not a constructor".to_string()),
+            };
+
+            let result = parent_function.call(interpreter, this.clone(), arguments)?;
+
+            // built-in constructors, e.g. of errors, create their own object, instance takes over its kind & properties
+            if let (JsValue::Object(instance), JsValue::Object(result)) = (this, &result) {
+                if !Rc::ptr_eq(instance, result) {
+                    let (kind, properties) = {
+                        let result = result.borrow();
+                        (result.kind.clone(), result.properties.clone())
+                    };
+
+                    instance.borrow_mut().kind = kind;
+
+                    for (key, value) in properties {
+                        interpreter.set_object_property(instance, &key, value)?;
+                    }
+                }
+            }
+        }
+
+        let JsValue::Object(instance) = this else {
+            return Ok(());
+        };
+
+        for field in self.fields.iter() {
+            let value = match &field.value {
+                Some(value) => {
+                    let mut environment = Environment::new(Rc::clone(&self.environment));
+                    environment.set_context(this.clone());
+                    interpreter.with_environment(environment, |interpreter| value.execute(interpreter))?
+                }
+                None => JsValue::Undefined,
+            };
+
+            instance.borrow_mut().add_property(&field.name.id, value);
         }
+
+        return Ok(());
+    }
+}
+
+impl OrdinaryFunction {
+    /// Self tail calls run the body again with new arguments instead of nesting a call,
+    /// tail calls of other functions are made once the body is left
+    fn run_body(&self, interpreter: &Interpreter, mut this: JsValue, mut arguments: Vec<JsValue>) -> Result<JsValue, String> {
+        loop {
+            let mut environment = Environment::new(Rc::clone(&self.environment));
+            environment.set_context(this);
+
+            for (index, argument) in self.arguments.iter().enumerate() {
+                let value = if argument.is_rest {
+                    JsObject::array(arguments.iter().skip(index).cloned().collect()).into()
+                } else {
+                    match arguments.get(index) {
+                        None | Some(JsValue::Undefined) => argument.default_value.clone(),
+                        Some(value) => value.clone(),
+                    }
+                };
+
+                environment.define_variable(argument.name.clone(), value, false)?;
+            }
+
+            interpreter.execute_function_body(environment, &self.body)?;
+
+            // function without `return` results in undefined
+            match interpreter.take_completion() {
+                Completion::Return(value) => return Ok(value),
+                Completion::TailCall { function, this: next_this, arguments: next_arguments } => {
+                    if !self.is_same_function(&function) {
+                        return interpreter.call_function_value(&function, next_this, next_arguments, false);
+                    }
+
+                    this = next_this;
+                    arguments = next_arguments;
+                }
+                _ => return Ok(JsValue::Undefined),
+            }
+        }
+    }
+
+    fn is_same_function(&self, value: &JsValue) -> bool {
+        let JsValue::Object(object) = value else {
+            return false;
+        };
+
+        return matches!(&object.borrow().kind, ObjectKind::Function(JsFunction::Ordinary(function)) if function == self);
+    }
+}
+
+/// Environments are compared by identity, they can reference the function itself
+/// (e.g. through the global object), so comparing them structurally may never end.
+/// Bodies are shared with the syntax tree, so they are compared by identity as well
+impl PartialEq for OrdinaryFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.arguments == other.arguments
+            && Rc::ptr_eq(&self.body, &other.body)
+            && Rc::ptr_eq(&self.environment, &other.environment)
+            && self.class_name == other.class_name
+            && Rc::ptr_eq(&self.fields, &other.fields)
+            && self.parent_constructor.as_ref().map(Rc::as_ptr) == other.parent_constructor.as_ref().map(Rc::as_ptr)
     }
 }
 
@@ -72,6 +206,7 @@ impl Into<JsFunction> for OrdinaryFunction {
 pub struct JsFunctionArg {
     pub name: String,
     pub default_value: JsValue,
+    pub is_rest: bool,
 }
 
 impl Debug for JsFunctionArg {
@@ -81,10 +216,20 @@ impl Debug for JsFunctionArg {
 }
 
 impl Callable for OrdinaryFunction {
-    fn call(&self, interpreter: &Interpreter, _: &Vec<JsValue>) -> Result<JsValue, String> {
-        self.body.as_ref().execute(interpreter)
-        // let result = self.body.as_ref().execute(interpreter);
-        // return result.map(|x| x.unwrap_or(JsValue::Undefined));
+    /// Body runs in a new scope of the environment function was created in,
+    /// missing or undefined arguments take default values & rest argument collects the remaining ones
+    fn call(&self, interpreter: &Interpreter, this: JsValue, arguments: &[JsValue]) -> Result<JsValue, String> {
+        interpreter.with_source(&self.source, |interpreter| {
+            if self.class_name.is_some() {
+                self.initialize_instance(interpreter, &this, arguments)?;
+            }
+
+            let span = self.body.get_span();
+            interpreter.run_hooks(|hooks, interpreter| hooks.on_function_enter(interpreter, &self.name, &span));
+            let result = self.run_body(interpreter, this, arguments.to_vec());
+            interpreter.run_hooks(|hooks, interpreter| hooks.on_function_exit(interpreter, &self.name, &span));
+            return result;
+        })
     }
 }
 
@@ -95,29 +240,60 @@ impl Debug for NativeFunction {
 }
 
 impl Callable for NativeFunction {
-    fn call(&self, interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
-        (self.function)(interpreter, arguments)
+    /// Natives read `this` from the current environment, so it's defined in a scope of the caller
+    fn call(&self, interpreter: &Interpreter, this: JsValue, arguments: &[JsValue]) -> Result<JsValue, String> {
+        let mut environment = interpreter.create_new_environment();
+        environment.set_context(this);
+
+        interpreter.with_environment(environment, |interpreter| (self.function)(interpreter, &arguments.to_vec()))
     }
 }
 
 impl Callable for JsFunction {
-    fn call(&self, interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String> {
+    fn call(&self, interpreter: &Interpreter, this: JsValue, arguments: &[JsValue]) -> Result<JsValue, String> {
         match self {
-            JsFunction::Ordinary(function) => function.call(interpreter, arguments),
-            JsFunction::Native(function) => function.call(interpreter, arguments)
+            JsFunction::Ordinary(function) => function.call(interpreter, this, arguments),
+            JsFunction::Native(function) => function.call(interpreter, this, arguments),
+            JsFunction::Bound(function) => function.call(interpreter, this, arguments),
         }
     }
 }
 
-pub trait Callable: Sized {
-    fn call(&self, interpreter: &Interpreter, arguments: &Vec<JsValue>) -> Result<JsValue, String>;
+/// Anything which can be invoked from script. Arguments are already evaluated,
+/// `this` is always bound by the caller, plain calls like `f()` pass undefined
+pub trait Callable {
+    fn call(&self, interpreter: &Interpreter, this: JsValue, arguments: &[JsValue]) -> Result<JsValue, String>;
 }
 
-#[derive(Clone, PartialEq)]
+/// Result of `Function.prototype.bind`, calls target with fixed `this` & leading arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundFunction {
+    pub target: JsValue,
+    pub this: JsValue,
+    pub arguments: Vec<JsValue>,
+}
+
+impl Callable for BoundFunction {
+    fn call(&self, interpreter: &Interpreter, _: JsValue, arguments: &[JsValue]) -> Result<JsValue, String> {
+        let mut bound_arguments = self.arguments.clone();
+        bound_arguments.extend_from_slice(arguments);
+
+        interpreter.call_function_value(&self.target, self.this.clone(), bound_arguments, false)
+    }
+}
+
+#[derive(Clone)]
 pub struct NativeFunction {
     pub function: fn(&Interpreter, &Vec<JsValue>) -> Result<JsValue, String>,
 }
 
+impl PartialEq for NativeFunction {
+    /// Compared by address of the function, like `to_primitive` hooks of objects
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::fn_addr_eq(self.function, other.function)
+    }
+}
+
 // impl Into<JsValue> for OrdinaryFunction {
 //     fn into(self) -> JsValue {
 //         JsValue::Object(JsObject::JsFunction::Ordinary(self))
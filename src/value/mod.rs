@@ -1,9 +1,14 @@
 pub mod object;
 pub mod function;
+pub mod weak_collection;
+pub mod native_object;
+pub mod proxy;
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::ops;
+use std::rc::Rc;
 use crate::keywords::{NULL_KEYWORD, UNDEFINED_KEYWORD};
 use crate::nodes::Interpreter;
 use crate::value::function::JsFunction;
@@ -13,7 +18,7 @@ use crate::value::object::{JsObject, JsObjectRef, ObjectKind};
 pub enum JsValue {
     Undefined,
     Null,
-    String(String),
+    String(Rc<str>),
     Number(f64),
     Boolean(bool),
     Object(JsObjectRef),
@@ -35,6 +40,35 @@ impl JsValue {
         JsObject::new(ObjectKind::Ordinary, properties).into()
     }
 
+    /// `===` comparison, objects are equal only to themselves & NaN isn't equal to anything
+    pub fn is_strictly_equal(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Undefined, JsValue::Undefined) | (JsValue::Null, JsValue::Null) => true,
+            (JsValue::String(left), JsValue::String(right)) => left == right,
+            (JsValue::Number(left), JsValue::Number(right)) => left == right,
+            (JsValue::Boolean(left), JsValue::Boolean(right)) => left == right,
+            (JsValue::Object(left), JsValue::Object(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+
+    /// SameValue comparison of `Object.is`, unlike `===` NaN is equal to NaN & 0 isn't equal to -0
+    pub fn is_same_value(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Number(left), JsValue::Number(right)) if left.is_nan() && right.is_nan() => true,
+            (JsValue::Number(left), JsValue::Number(right)) => left == right && left.is_sign_negative() == right.is_sign_negative(),
+            _ => self.is_strictly_equal(other),
+        }
+    }
+
+    /// SameValueZero comparison of keys of collections & `includes`, NaN is equal to NaN & 0 is equal to -0
+    pub fn is_same_value_zero(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Number(left), JsValue::Number(right)) if left.is_nan() && right.is_nan() => true,
+            _ => self.is_strictly_equal(other),
+        }
+    }
+
     pub fn get_type_as_str(&self) -> String {
         match self {
             JsValue::Undefined => UNDEFINED_KEYWORD.to_string(),
@@ -52,33 +86,217 @@ impl JsValue {
         }
     }
 
+    /// Result of `typeof` operator, unlike `get_type_as_str` null is an object here
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            JsValue::Undefined => "undefined",
+            JsValue::Null => "object",
+            JsValue::String(_) => "string",
+            JsValue::Number(_) => "number",
+            JsValue::Boolean(_) => "boolean",
+            JsValue::Object(_) if self.is_function() => "function",
+            JsValue::Object(_) => "object",
+        }
+    }
+
+    /// Converts value to boolean according to ToBoolean abstract operation, used by every condition & logical operator:
+    /// undefined, null, `false`, `0`, `-0`, `NaN` & empty string are false, everything else including any object is true
     pub fn to_bool(&self) -> bool {
         match self {
             JsValue::Undefined => false,
             JsValue::Null => false,
             JsValue::String(value) => value.len() != 0,
-            JsValue::Number(value) => *value != 0.0,
+            // `-0.0 == 0.0`, so negative zero is false too
+            JsValue::Number(value) => *value != 0.0 && !value.is_nan(),
             JsValue::Boolean(value) => *value,
             JsValue::Object(_) => true,
         }
     }
 
+    /// Converts value to number according to ToNumber abstract operation
+    pub fn to_number(&self) -> f64 {
+        match self {
+            JsValue::Undefined => f64::NAN,
+            JsValue::Null => 0.0,
+            JsValue::String(value) => string_to_number(value),
+            JsValue::Number(value) => *value,
+            JsValue::Boolean(value) => if *value { 1.0 } else { 0.0 },
+            JsValue::Object(_) => string_to_number(&self.to_js_string()),
+        }
+    }
+
+    /// Converts value to string according to ToString abstract operation,
+    /// objects are converted as by the default `toString` implementations
+    pub fn to_js_string(&self) -> String {
+        match self {
+            JsValue::Undefined => UNDEFINED_KEYWORD.to_string(),
+            JsValue::Null => NULL_KEYWORD.to_string(),
+            JsValue::String(value) => value.to_string(),
+            JsValue::Number(value) => number_to_string(*value),
+            JsValue::Boolean(value) => value.to_string(),
+            JsValue::Object(object) => {
+                let object = object.borrow();
+
+                match &object.kind {
+                    ObjectKind::Ordinary | ObjectKind::Native(_) | ObjectKind::Proxy(_) => "[object Object]".to_string(),
+                    ObjectKind::WeakMap(_) => "[object WeakMap]".to_string(),
+                    ObjectKind::WeakSet(_) => "[object WeakSet]".to_string(),
+                    ObjectKind::Error => object.error_to_string(),
+                    ObjectKind::Function(_) => "function () { [native code] }".to_string(),
+                    ObjectKind::Array => object.array_items()
+                        .iter()
+                        .map(|x| match x {
+                            JsValue::Undefined | JsValue::Null => String::new(),
+                            _ => x.to_js_string(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(","),
+                }
+            }
+        }
+    }
+
+    /// Abstract Relational Comparison: strings are compared lexicographically,
+    /// everything else is compared numerically after ToNumber conversion.
+    /// Returns `None` when values are not comparable (one of the sides is NaN).
+    ///
+    /// It is intentionally not a `PartialOrd` implementation, because `"1"` and `1`
+    /// are equal in terms of this comparison but not equal as values.
+    pub fn relational_comparison(&self, rhs: &JsValue) -> Option<Ordering> {
+        match (self, rhs) {
+            (JsValue::String(left_string), JsValue::String(right_string)) => {
+                Some(left_string.encode_utf16().cmp(right_string.encode_utf16()))
+            }
+            _ => self.to_number().partial_cmp(&rhs.to_number()),
+        }
+    }
+
+    /// `instanceof` operator, checks whether `prototype` of the constructor is in the prototype chain of the value
+    pub fn instance_of(&self, constructor: &JsValue) -> Result<bool, String> {
+        if !constructor.is_function() {
+            return Err("TypeError: Right-hand side of 'instanceof' is not callable".to_string());
+        }
+
+        let JsValue::Object(object) = self else {
+            return Ok(false);
+        };
+
+        let JsValue::Object(constructor) = constructor else { unreachable!() };
+
+        let JsValue::Object(prototype) = constructor.borrow().get_prototype() else {
+            return Err("TypeError: Function has non-object prototype in instanceof check".to_string());
+        };
+
+        let mut current = object.borrow().get_proto();
+
+        while let Some(current_prototype) = current {
+            if Rc::ptr_eq(&current_prototype, &prototype) {
+                return Ok(true);
+            }
+
+            current = current_prototype.borrow().get_proto();
+        }
+
+        return Ok(false);
+    }
+
     pub fn to_bool_js_value(&self) -> Self {
         JsValue::Boolean(self.to_bool())
     }
 
     pub fn exponentiation(&self, rhs: &JsValue) -> Result<JsValue, String> {
-        match (self, rhs) {
-            (JsValue::Number(left_number), JsValue::Number(right_number)) => {
-                Ok(JsValue::Number(left_number.powf(*right_number)))
-            }
-            _ => Err(format!(
-                "exponentiation of types '{}' and '{}' is not possible",
-                self.get_type_as_str(),
-                rhs.get_type_as_str()
-            )),
+        Ok(JsValue::Number(number_exponentiation(self.to_number(), rhs.to_number())))
+    }
+}
+
+/// Number::exponentiate, differs from `f64::powf` for NaN exponents and `±1 ** ±Infinity`,
+/// which are NaN in js but 1 in Rust
+pub fn number_exponentiation(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return f64::NAN;
+    }
+
+    base.powf(exponent)
+}
+
+/// Number::toString abstract operation, used by every conversion & display of numbers.
+/// Integers are written without fraction, e.g. `5`, exponent form is used from `1e+21` & below `1e-6`
+pub fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity".to_string() } else { "-Infinity".to_string() };
+    }
+
+    if value == 0.0 {
+        // both +0 and -0 are converted to "0"
+        return "0".to_string();
+    }
+
+    if value < 0.0 {
+        return format!("-{}", number_to_string(-value));
+    }
+
+    // shortest digits which round trip to the same number, e.g. "1.2345e3"
+    let scientific = format!("{value:e}");
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let digit_count = digits.len() as i32;
+    // position of the decimal point relative to the first digit
+    let point = exponent.parse::<i32>().unwrap() + 1;
+
+    if digit_count <= point && point <= 21 {
+        return format!("{digits}{}", "0".repeat((point - digit_count) as usize));
+    }
+
+    if 0 < point && point <= 21 {
+        return format!("{}.{}", &digits[..point as usize], &digits[point as usize..]);
+    }
+
+    if -6 < point && point <= 0 {
+        return format!("0.{}{digits}", "0".repeat(-point as usize));
+    }
+
+    let exponent = point - 1;
+    let sign = if exponent < 0 { '-' } else { '+' };
+
+    match digits.split_at(1) {
+        (first, "") => format!("{first}e{sign}{}", exponent.abs()),
+        (first, rest) => format!("{first}.{rest}e{sign}{}", exponent.abs()),
+    }
+}
+
+/// StringToNumber abstract operation: surrounding whitespace is ignored, empty string is 0,
+/// supports decimal, `Infinity` and `0x`/`0o`/`0b` prefixed literals, anything else is NaN
+fn string_to_number(value: &str) -> f64 {
+    let value = value.trim();
+
+    if value.is_empty() {
+        return 0.0;
+    }
+
+    let radix_prefixes = [("0x", 16), ("0X", 16), ("0o", 8), ("0O", 8), ("0b", 2), ("0B", 2)];
+
+    for (prefix, radix) in radix_prefixes {
+        if let Some(digits) = value.strip_prefix(prefix) {
+            return u64::from_str_radix(digits, radix).map_or(f64::NAN, |x| x as f64);
         }
     }
+
+    let unsigned_value = value.trim_start_matches(['+', '-']);
+
+    if unsigned_value == "Infinity" && value.len() - unsigned_value.len() <= 1 {
+        return if value.starts_with('-') { f64::NEG_INFINITY } else { f64::INFINITY };
+    }
+
+    // Rust parser also accepts "inf" and "NaN" which are not valid numeric literals in js
+    if !value.chars().all(|x| x.is_ascii_digit() || matches!(x, '.' | 'e' | 'E' | '+' | '-')) {
+        return f64::NAN;
+    }
+
+    value.parse::<f64>().unwrap_or(f64::NAN)
 }
 
 impl From<f64> for JsValue {
@@ -95,7 +313,7 @@ impl From<bool> for JsValue {
 
 impl From<String> for JsValue {
     fn from(value: String) -> Self {
-        JsValue::String(value)
+        JsValue::String(value.into())
     }
 }
 
@@ -103,18 +321,15 @@ impl ops::Add<&JsValue> for &JsValue {
     type Output = Result<JsValue, String>;
 
     fn add(self, rhs: &JsValue) -> Self::Output {
-        match (self, rhs) {
-            (JsValue::Number(first_number), JsValue::Number(second_number)) => Ok(JsValue::Number(first_number + second_number)),
-            (JsValue::String(first_string), JsValue::String(second_string)) => Ok(JsValue::String(format!("{}{}", first_string, second_string.as_str()))),
-            (JsValue::String(left_string), JsValue::Number(right_number)) => {
-                Ok(JsValue::String(format!("{}{}", left_string, right_number.to_string())))
-            }
-            _ => Err(format!(
-                "addition of types '{}' and '{}' is not possible",
-                &self.get_type_as_str(),
-                &rhs.get_type_as_str()
-            ))
+        // objects are converted to primitives first, which is always a string for them
+        let is_string_concatenation = [self, rhs].iter()
+            .any(|x| matches!(x, JsValue::String(_) | JsValue::Object(_)));
+
+        if is_string_concatenation {
+            return Ok(JsValue::String((self.to_js_string() + &rhs.to_js_string()).into()));
         }
+
+        Ok(JsValue::Number(self.to_number() + rhs.to_number()))
     }
 }
 
@@ -122,14 +337,7 @@ impl ops::Sub<&JsValue> for &JsValue {
     type Output = Result<JsValue, String>;
 
     fn sub(self, rhs: &JsValue) -> Self::Output {
-        match (self, rhs) {
-            (JsValue::Number(first_number), JsValue::Number(second_number)) => Ok(JsValue::Number(first_number - second_number)),
-            _ => Err(format!(
-                "subtraction of types '{}' and '{}' is not possible",
-                &self.get_type_as_str(),
-                &rhs.get_type_as_str()
-            ))
-        }
+        Ok(JsValue::Number(self.to_number() - rhs.to_number()))
     }
 }
 
@@ -137,15 +345,7 @@ impl ops::Mul<&JsValue> for &JsValue {
     type Output = Result<JsValue, String>;
 
     fn mul(self, rhs: &JsValue) -> Self::Output {
-        match (self, rhs) {
-            (JsValue::Number(first_number), JsValue::Number(second_number)) => Ok(JsValue::Number(first_number * second_number)),
-            (JsValue::String(string), JsValue::Number(number)) => Ok(JsValue::String(string.repeat(*number as usize))),
-            _ => Err(format!(
-                "multiplication of types '{}' and '{}' is not possible",
-                &self.get_type_as_str(),
-                &rhs.get_type_as_str()
-            ))
-        }
+        Ok(JsValue::Number(self.to_number() * rhs.to_number()))
     }
 }
 
@@ -153,51 +353,320 @@ impl ops::Div<&JsValue> for &JsValue {
     type Output = Result<JsValue, String>;
 
     fn div(self, rhs: &JsValue) -> Self::Output {
-        match (self, rhs) {
-            (JsValue::Number(first_number), JsValue::Number(second_number)) => Ok(JsValue::Number(first_number / second_number)),
-            _ => Err(format!(
-                "division of types '{}' and '{}' is not possible",
-                &self.get_type_as_str(),
-                &rhs.get_type_as_str()
-            ))
+        Ok(JsValue::Number(self.to_number() / rhs.to_number()))
+    }
+}
+
+impl ops::Rem<&JsValue> for &JsValue {
+    type Output = Result<JsValue, String>;
+
+    /// Same as `f64` remainder: result has the sign of the dividend
+    fn rem(self, rhs: &JsValue) -> Self::Output {
+        Ok(JsValue::Number(self.to_number() % rhs.to_number()))
+    }
+}
+
+/// How values are printed by `console.log` & the REPL
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DisplayMode {
+    /// Human readable with ANSI colors
+    #[default]
+    Colored,
+    /// Human readable without escape sequences, e.g. for logs
+    Plain,
+    /// Every value is printed as JSON, so output can be consumed by other tools
+    Json,
+}
+
+impl DisplayMode {
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "colored" => Ok(Self::Colored),
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown display mode '{mode}', expected colored, plain or json")),
+        }
+    }
+}
+
+/// How much of long strings & arrays is printed in human readable display modes,
+/// the rest is replaced with e.g. `... 9990 more items`. JSON output is never truncated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayLimits {
+    pub max_items: usize,
+    pub max_string_length: usize,
+}
+
+impl Default for DisplayLimits {
+    /// Same limits as node's `util.inspect`
+    fn default() -> Self {
+        Self {
+            max_items: 100,
+            max_string_length: 10000,
+        }
+    }
+}
+
+impl DisplayLimits {
+    /// Nothing is truncated
+    pub fn unlimited() -> Self {
+        Self {
+            max_items: usize::MAX,
+            max_string_length: usize::MAX,
+        }
+    }
+}
+
+/// Value printed in the given display mode, created by [`JsValue::display`]
+pub struct DisplayValue<'a> {
+    value: &'a JsValue,
+    mode: DisplayMode,
+    limits: DisplayLimits,
+}
+
+impl DisplayValue<'_> {
+    pub fn with_limits(mut self, limits: DisplayLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Display for DisplayValue<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.mode {
+            DisplayMode::Json => self.value.format_json(f, &mut vec![]),
+            _ => self.value.format_value(f, self.mode == DisplayMode::Colored, &self.limits, &mut vec![]),
         }
     }
 }
 
 impl Display for JsValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.display(DisplayMode::Colored).fmt(f)
+    }
+}
+
+/// Wraps text into ANSI color escape sequence when colors are enabled
+fn write_colored(f: &mut Formatter<'_>, text: &str, color: u8, is_colored: bool) -> std::fmt::Result {
+    if is_colored {
+        write!(f, "\x1b[{color}m{text}\x1b[0m")
+    } else {
+        write!(f, "{text}")
+    }
+}
+
+/// Printed item of an array, consecutive holes are printed as a single item like in node, e.g. `[1, <2 empty items>, 4]`
+enum ArrayDisplayItem {
+    Value(JsValue),
+    Holes(usize),
+}
+
+fn array_display_items(array: &JsObject) -> Vec<ArrayDisplayItem> {
+    let mut items = vec![];
+    let mut next_index = 0;
+
+    for index in array.array_indices() {
+        if index > next_index {
+            items.push(ArrayDisplayItem::Holes(index - next_index));
+        }
+
+        items.push(ArrayDisplayItem::Value(array.get_property_value(&index.to_string())));
+        next_index = index + 1;
+    }
+
+    if array.array_length() > next_index {
+        items.push(ArrayDisplayItem::Holes(array.array_length() - next_index));
+    }
+
+    return items;
+}
+
+impl JsValue {
+    /// Displays value with default limits, see [`DisplayValue::with_limits`]
+    pub fn display(&self, mode: DisplayMode) -> DisplayValue<'_> {
+        DisplayValue { value: self, mode, limits: DisplayLimits::default() }
+    }
+
+    /// Formats value for console output, `visited` holds objects which are being printed,
+    /// so cyclic references (e.g. `globalThis.globalThis`) are printed as `[Circular]`
+    fn format_value(&self, f: &mut Formatter<'_>, is_colored: bool, limits: &DisplayLimits, visited: &mut Vec<*const JsObject>) -> std::fmt::Result {
         match self {
-            JsValue::Undefined => write!(f, "\x1b[37m{UNDEFINED_KEYWORD}\x1b[0m"),
+            JsValue::Undefined => write_colored(f, UNDEFINED_KEYWORD, 37, is_colored),
             JsValue::Null => write!(f, "{NULL_KEYWORD}"),
-            JsValue::String(str) => write!(f, "\x1b[93m\"{}\"\x1b[0m", str),
-            JsValue::Number(number) => write!(f, "\x1b[36m{}\x1b[0m", number),
-            JsValue::Boolean(value) => write!(f, "\x1b[35m{}\x1b[0m", if *value { "true" } else { "false" }),
+            JsValue::String(str) => {
+                let length = str.chars().count();
+
+                if length <= limits.max_string_length {
+                    return write_colored(f, &format!("\"{str}\""), 93, is_colored);
+                }
+
+                let shown: String = str.chars().take(limits.max_string_length).collect();
+                write_colored(f, &format!("\"{shown}\""), 93, is_colored)?;
+                write!(f, "... {} more characters", length - limits.max_string_length)
+            },
+            JsValue::Number(number) => {
+                // -0 is printed with sign like in node's console, even though it's converted to "0" as a string
+                let number = if *number == 0.0 && number.is_sign_negative() { "-0".to_string() } else { number_to_string(*number) };
+                write_colored(f, &number, 36, is_colored)
+            },
+            JsValue::Boolean(value) => write_colored(f, if *value { "true" } else { "false" }, 35, is_colored),
             JsValue::Object(object) => {
-                match &object.borrow().kind {
-                    ObjectKind::Ordinary => {
-                        let result: Vec<String> = object.borrow().properties
-                            .iter()
-                            .map(|(key, value)| format!("{key}: {value}"))
-                            .collect();
-                        let result = result.join(", ");
-                        write!(f, "{{ {result} }}")
+                let object_pointer = object.as_ptr() as *const JsObject;
+
+                if visited.contains(&object_pointer) {
+                    return write!(f, "[Circular]");
+                }
+
+                visited.push(object_pointer);
+
+                let result = match &object.borrow().kind {
+                    ObjectKind::Ordinary | ObjectKind::Native(_) => {
+                        write!(f, "{{ ")?;
+
+                        let entries = object.borrow().enumerable_entries();
+
+                        for (i, (key, value)) in entries.iter().enumerate() {
+                            if i != 0 {
+                                write!(f, ", ")?;
+                            }
+
+                            write!(f, "{key}: ")?;
+                            value.format_value(f, is_colored, limits, visited)?;
+                        }
+
+                        write!(f, " }}")
                     },
                     ObjectKind::Function(function) => {
                         match function {
-                            JsFunction::Ordinary(_) => write!(f, "[function]"),
+                            JsFunction::Ordinary(function) => match &function.class_name {
+                                Some(class_name) => write!(f, "[class {class_name}]"),
+                                None => write!(f, "[function]"),
+                            },
                             JsFunction::Native(_) => write!(f, "[native function]"),
+                            JsFunction::Bound(_) => write!(f, "[bound function]"),
                         }
                     },
+                    // entries are not listed like in node, keys may be dropped at any moment
+                    // like in node, proxy is printed as its target
+                    ObjectKind::Proxy(proxy) => JsValue::Object(Rc::clone(&proxy.target)).format_value(f, is_colored, limits, visited),
+                    ObjectKind::WeakMap(_) => write!(f, "WeakMap {{ <items unknown> }}"),
+                    ObjectKind::WeakSet(_) => write!(f, "WeakSet {{ <items unknown> }}"),
+                    ObjectKind::Error => {
+                        match object.borrow().get_property_value("stack") {
+                            JsValue::String(stack) => write!(f, "{stack}"),
+                            _ => write!(f, "{}", object.borrow().error_to_string()),
+                        }
+                    }
                     ObjectKind::Array => {
-                        let result: Vec<String> = object.borrow().properties
-                            .values()
-                            .map(|x| format!("{x}"))
-                            .collect();
-                        let result = result.join(", ");
-                        write!(f, "[{result}]")
+                        write!(f, "[")?;
+
+                        let items = array_display_items(&object.borrow());
+
+                        for (i, item) in items.iter().take(limits.max_items).enumerate() {
+                            if i != 0 {
+                                write!(f, ", ")?;
+                            }
+
+                            match item {
+                                ArrayDisplayItem::Value(value) => value.format_value(f, is_colored, limits, visited)?,
+                                ArrayDisplayItem::Holes(1) => write!(f, "<1 empty item>")?,
+                                ArrayDisplayItem::Holes(count) => write!(f, "<{count} empty items>")?,
+                            }
+                        }
+
+                        if items.len() > limits.max_items {
+                            let separator = if limits.max_items == 0 { "" } else { ", " };
+                            write!(f, "{separator}... {} more items", items.len() - limits.max_items)?;
+                        }
+
+                        write!(f, "]")
                     }
-                }
+                };
+
+                visited.pop();
+                result
             },
         }
     }
+
+    /// Formats value like `JSON.stringify`, except that values which have no JSON representation
+    /// (undefined, functions) are printed as `null` instead of being skipped at the top level
+    fn format_json(&self, f: &mut Formatter<'_>, visited: &mut Vec<*const JsObject>) -> std::fmt::Result {
+        match self {
+            JsValue::Undefined | JsValue::Null => write!(f, "null"),
+            JsValue::String(string) => write_json_string(f, string),
+            JsValue::Number(number) if number.is_finite() => write!(f, "{}", number_to_string(*number)),
+            JsValue::Number(_) => write!(f, "null"),
+            JsValue::Boolean(value) => write!(f, "{value}"),
+            JsValue::Object(object) => {
+                let object_pointer = object.as_ptr() as *const JsObject;
+
+                if visited.contains(&object_pointer) {
+                    return write_json_string(f, "[Circular]");
+                }
+
+                visited.push(object_pointer);
+
+                let result = match &object.borrow().kind {
+                    ObjectKind::Function(_) => write!(f, "null"),
+                    ObjectKind::Proxy(proxy) => JsValue::Object(Rc::clone(&proxy.target)).format_json(f, visited),
+                    ObjectKind::Error => write_json_string(f, &object.borrow().error_to_string()),
+                    ObjectKind::Array => {
+                        write!(f, "[")?;
+
+                        for (i, value) in object.borrow().array_items().iter().enumerate() {
+                            if i != 0 {
+                                write!(f, ",")?;
+                            }
+
+                            value.format_json(f, visited)?;
+                        }
+
+                        write!(f, "]")
+                    }
+                    // like in `JSON.stringify`, entries of weak collections are not own properties
+                    ObjectKind::Ordinary | ObjectKind::Native(_) | ObjectKind::WeakMap(_) | ObjectKind::WeakSet(_) => {
+                        write!(f, "{{")?;
+
+                        let properties = object.borrow().enumerable_entries().into_iter()
+                            .filter(|(_, value)| !matches!(value, JsValue::Undefined) && !value.is_function())
+                            .collect::<Vec<_>>();
+
+                        for (i, (key, value)) in properties.iter().enumerate() {
+                            if i != 0 {
+                                write!(f, ",")?;
+                            }
+
+                            write_json_string(f, key)?;
+                            write!(f, ":")?;
+                            value.format_json(f, visited)?;
+                        }
+
+                        write!(f, "}}")
+                    }
+                };
+
+                visited.pop();
+                result
+            }
+        }
+    }
+}
+
+fn write_json_string(f: &mut Formatter<'_>, string: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+
+    for char in string.chars() {
+        match char {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            char if (char as u32) < 0x20 => write!(f, "\\u{:04x}", char as u32)?,
+            char => write!(f, "{char}")?,
+        }
+    }
+
+    write!(f, "\"")
 }
@@ -0,0 +1,52 @@
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use crate::value::JsValue;
+
+/// Properties of a host object which live in Rust, e.g. configuration or game state shared with the embedder.
+/// Scripts read & write them through callbacks, so they always see the current state without copying it
+pub trait NativeProperties {
+    /// Value of the property, `None` if the host doesn't provide it, then own properties & prototype are looked up
+    fn get(&self, key: &str) -> Option<JsValue>;
+
+    /// Stores the value, returns `Ok(false)` if the host doesn't provide the property, then it's stored as an own property.
+    /// Error is thrown to the script, e.g. when the value has a wrong type
+    fn set(&self, key: &str, value: JsValue) -> Result<bool, String>;
+
+    /// Keys listed by `Object.keys`, JSON & printing
+    fn keys(&self) -> Vec<String>;
+}
+
+/// Kind of host objects, clones share the same host properties
+#[derive(Clone)]
+pub struct NativeObject(Rc<dyn NativeProperties>);
+
+impl NativeObject {
+    pub fn new(properties: impl NativeProperties + 'static) -> Self {
+        Self(Rc::new(properties))
+    }
+
+    pub fn get(&self, key: &str) -> Option<JsValue> {
+        self.0.get(key)
+    }
+
+    pub fn set(&self, key: &str, value: JsValue) -> Result<bool, String> {
+        self.0.set(key, value)
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.0.keys()
+    }
+}
+
+impl Debug for NativeObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("native object")
+    }
+}
+
+impl PartialEq for NativeObject {
+    /// Host objects are equal when they are backed by the same host properties
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
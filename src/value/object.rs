@@ -1,25 +1,109 @@
 use std::cell::{RefCell};
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use crate::interpreter::ast_interpreter::Interpreter;
 use crate::value::function::{JsFunction};
 use crate::value::JsValue;
+use crate::value::native_object::{NativeObject, NativeProperties};
+use crate::value::proxy::JsProxy;
+use crate::value::weak_collection::WeakCollection;
 
 const PROTOTYPE_PROPERTY: &'static str = "prototype";
+const CONSTRUCTOR_PROPERTY: &str = "constructor";
+/// Accessor of the prototype unless an object has own property with this key
+pub const PROTO_PROPERTY: &str = "__proto__";
+pub const LENGTH_PROPERTY: &str = "length";
 
-#[derive(Debug, Clone, PartialEq)]
+/// Sets `prototype` of a constructor & non-enumerable `constructor` of the prototype pointing back at it,
+/// so instances reach their constructor through the prototype chain
+pub fn link_constructor_prototype(constructor: &JsObjectRef, prototype: &JsObjectRef) {
+    constructor.borrow_mut().set_prototype(Rc::clone(prototype));
+    prototype.borrow_mut().add_non_enumerable_property(CONSTRUCTOR_PROPERTY, JsValue::Object(Rc::clone(constructor)));
+}
+
+/// Order of keys in listings: array indices ascending, then other keys alphabetically.
+/// Properties are stored in a hash map, without sorting listings would differ between runs
+fn compare_keys(a: &str, b: &str) -> Ordering {
+    match (array_index(a), array_index(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Whether the value can be assigned to `length` of an array
+pub(crate) fn is_valid_array_length(value: &JsValue) -> bool {
+    let length = value.to_number();
+    return length >= 0.0 && length <= u32::MAX as f64 && length.fract() == 0.0;
+}
+
+/// Index of a key in canonical form, e.g. "01" is an ordinary key
+pub(crate) fn array_index(key: &str) -> Option<u32> {
+    key.parse::<u32>().ok().filter(|x| x.to_string() == key)
+}
+
+#[derive(Debug, Clone)]
 pub struct JsObject {
     pub kind: ObjectKind,
     pub properties: HashMap<String, JsValue>,
+    /// Own properties skipped by `Object.keys`, JSON & printing, e.g. methods of class prototypes
+    non_enumerable_keys: HashSet<String>,
+    /// Own properties scripts can't assign, e.g. builtin bindings of the global object
+    read_only_keys: HashSet<String>,
+    /// None of the properties & the prototype can be changed by scripts, e.g. builtin `Math`
+    is_frozen: bool,
+    to_primitive: Option<ToPrimitiveHook>,
     __proto__: Option<JsObjectRef>,
 }
 
+impl PartialEq for JsObject {
+    /// Hooks are compared by address, the same hook may have different addresses in different codegen units
+    fn eq(&self, other: &Self) -> bool {
+        let is_same_hook = match (self.to_primitive, other.to_primitive) {
+            (Some(a), Some(b)) => std::ptr::fn_addr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+
+        self.kind == other.kind
+            && self.properties == other.properties
+            && self.non_enumerable_keys == other.non_enumerable_keys
+            && self.read_only_keys == other.read_only_keys
+            && self.is_frozen == other.is_frozen
+            && is_same_hook
+            && self.__proto__ == other.__proto__
+    }
+}
+
 pub type JsObjectRef = Rc<RefCell<JsObject>>;
 
+/// Type of primitive preferred by the place where an object is converted, e.g. `String(x)` prefers string
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrimitiveHint {
+    /// `+` operator, which either adds numbers or concatenates strings
+    Default,
+    Number,
+    String,
+}
+
+/// Native conversion of a host object to a primitive, it replaces `valueOf` & `toString` methods,
+/// so types provided by an embedder, e.g. a vector, can be used in arithmetic & string concatenation
+pub type ToPrimitiveHook = fn(&Interpreter, &JsObjectRef, PrimitiveHint) -> Result<JsValue, String>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ObjectKind {
     Ordinary,
     Function(JsFunction),
     Array,
+    /// Instance created by one of the built-in error constructors, printed with its stack
+    Error,
+    WeakMap(WeakCollection),
+    /// Values of the collection are not used, only keys
+    WeakSet(WeakCollection),
+    /// Host object whose properties are provided by the embedder, see [`NativeProperties`]
+    Native(NativeObject),
+    Proxy(JsProxy),
 }
 
 impl JsObject {
@@ -27,6 +111,10 @@ impl JsObject {
         Self {
             kind,
             properties: properties.into(),
+            non_enumerable_keys: HashSet::new(),
+            read_only_keys: HashSet::new(),
+            is_frozen: false,
+            to_primitive: None,
             __proto__: None,
         }
     }
@@ -44,12 +132,52 @@ impl JsObject {
         Self::new(ObjectKind::Ordinary, []).to_ref()
     }
 
+    /// Host object, reads & writes of properties provided by the host call back into it
+    pub fn native(properties: impl NativeProperties + 'static) -> Self {
+        Self::new(ObjectKind::Native(NativeObject::new(properties)), [])
+    }
+
+    pub fn proxy(target: JsObjectRef, handler: JsObjectRef) -> Self {
+        Self::new(ObjectKind::Proxy(JsProxy { target, handler }), [])
+    }
+
     pub fn array(properties: Vec<JsValue>) -> Self {
-        let properties_with_keys: HashMap<String, JsValue> = properties
+        Self::sparse_array(properties.into_iter().map(Some).collect())
+    }
+
+    /// Array with holes in place of `None` items, e.g. `[1, , 3]`, holes have no own property
+    pub fn sparse_array(items: Vec<Option<JsValue>>) -> Self {
+        let length = items.len();
+        let properties_with_keys: HashMap<String, JsValue> = items
             .into_iter()
             .enumerate()
-            .map(|(i, x)| (i.to_string(), x)).collect();
-        Self::new(ObjectKind::Array, properties_with_keys)
+            .filter_map(|(i, x)| x.map(|x| (i.to_string(), x))).collect();
+        let mut array = Self::new(ObjectKind::Array, properties_with_keys);
+        array.add_non_enumerable_property(LENGTH_PROPERTY, JsValue::Number(length as f64));
+        array
+    }
+
+    pub fn array_length(&self) -> usize {
+        match self.properties.get(LENGTH_PROPERTY) {
+            Some(JsValue::Number(length)) => *length as usize,
+            _ => 0,
+        }
+    }
+
+    /// Elements of an array in index order, holes are filled with undefined
+    pub fn array_items(&self) -> Vec<JsValue> {
+        (0..self.array_length()).map(|i| self.get_property_value(&i.to_string())).collect()
+    }
+
+    /// Indices of an array which have an element in ascending order, holes are skipped
+    pub fn array_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.properties.keys()
+            .filter_map(|x| array_index(x))
+            .map(|x| x as usize)
+            .filter(|x| *x < self.array_length())
+            .collect();
+        indices.sort();
+        indices
     }
 
     pub fn set_proto(&mut self, prototype: JsObjectRef) {
@@ -69,10 +197,133 @@ impl JsObject {
     }
 
     pub fn add_property(&mut self, key: &str, value: JsValue) {
+        if matches!(self.kind, ObjectKind::Array) {
+            self.update_array_length(key, &value);
+        }
+
         self.properties.insert(key.to_string(), value);
     }
 
+    /// Assigning past the end of an array grows it, assigning a smaller `length` removes elements after it
+    fn update_array_length(&mut self, key: &str, value: &JsValue) {
+        if key == LENGTH_PROPERTY {
+            let length = value.to_number() as usize;
+            self.properties.retain(|key, _| array_index(key).is_none_or(|x| (x as usize) < length));
+            return;
+        }
+
+        if let Some(index) = array_index(key).filter(|x| *x != u32::MAX) {
+            if index as usize >= self.array_length() {
+                self.properties.insert(LENGTH_PROPERTY.to_string(), JsValue::Number(index as f64 + 1.0));
+            }
+        }
+    }
+
+    pub fn add_non_enumerable_property(&mut self, key: &str, value: JsValue) {
+        self.non_enumerable_keys.insert(key.to_string());
+        self.add_property(key, value);
+    }
+
+    pub fn mark_read_only(&mut self, key: &str) {
+        self.read_only_keys.insert(key.to_string());
+    }
+
+    pub fn freeze(&mut self) {
+        self.is_frozen = true;
+    }
+
+    pub fn with_to_primitive(mut self, hook: ToPrimitiveHook) -> Self {
+        self.to_primitive = Some(hook);
+        self
+    }
+
+    pub fn to_primitive_hook(&self) -> Option<ToPrimitiveHook> {
+        self.to_primitive
+    }
+
+    /// Whether scripts may assign the property, native code & embedders are not restricted
+    pub fn is_writable(&self, key: &str) -> bool {
+        !self.is_frozen && !self.read_only_keys.contains(key)
+    }
+
+    /// Keys of enumerable properties stored on the object itself in listing order, inherited ones are not included
+    pub fn enumerable_keys(&self) -> Vec<String> {
+        if let Some(proxy) = self.as_proxy() {
+            return proxy.target.borrow().enumerable_keys();
+        }
+
+        let mut keys: Vec<String> = self.properties.keys().filter(|x| !self.non_enumerable_keys.contains(*x)).cloned().collect();
+
+        if let Some(native) = self.native_object() {
+            keys.extend(native.keys().into_iter().filter(|x| !self.properties.contains_key(x)));
+        }
+
+        keys.sort_by(|a, b| compare_keys(a, b));
+        keys
+    }
+
+    pub fn has_own_property(&self, key: &str) -> bool {
+        if let Some(proxy) = self.as_proxy() {
+            return proxy.target.borrow().has_own_property(key);
+        }
+
+        self.properties.contains_key(key) || self.native_object().is_some_and(|x| x.get(key).is_some())
+    }
+
+    /// Own key & value pairs as seen by `Object.entries`, JSON & printing, in listing order
+    pub fn enumerable_entries(&self) -> Vec<(String, JsValue)> {
+        if let Some(proxy) = self.as_proxy() {
+            return proxy.target.borrow().enumerable_entries();
+        }
+
+        let mut entries: Vec<(String, JsValue)> = self.properties.iter()
+            .filter(|(key, _)| !self.non_enumerable_keys.contains(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        if let Some(native) = self.native_object() {
+            let native_entries = native.keys().into_iter()
+                .filter(|x| !self.properties.contains_key(x))
+                .filter_map(|key| native.get(&key).map(|value| (key, value)));
+            entries.extend(native_entries);
+        }
+
+        entries.sort_by(|a, b| compare_keys(&a.0, &b.0));
+        entries
+    }
+
+    /// Host properties of a native object
+    pub fn native_object(&self) -> Option<&NativeObject> {
+        match &self.kind {
+            ObjectKind::Native(native) => Some(native),
+            _ => None,
+        }
+    }
+
+    /// Target & handler of a proxy, listings & lookups of native code which don't go through
+    /// the interpreter are forwarded to the target without calling traps
+    pub fn as_proxy(&self) -> Option<&JsProxy> {
+        match &self.kind {
+            ObjectKind::Proxy(proxy) => Some(proxy),
+            _ => None,
+        }
+    }
+
+    /// Removes own property, returns false if there was no such property
+    pub fn remove_property(&mut self, key: &str) -> bool {
+        self.non_enumerable_keys.remove(key);
+        self.properties.remove(key).is_some()
+    }
+
     pub fn get_property_value(&self, key: &str) -> JsValue {
+        if let Some(proxy) = self.as_proxy() {
+            return proxy.target.borrow().get_property_value(key);
+        }
+
+        if let Some(value) = self.native_object().and_then(|x| x.get(key)) {
+            return value;
+        }
+
         if self.properties.contains_key(key) {
             return self.properties.get(key).map_or(JsValue::Undefined, |x| x.clone());
         }
@@ -84,6 +335,28 @@ impl JsObject {
         return JsValue::Undefined;
     }
 
+    /// `name: message` like `Error.prototype.toString`, name or message is omitted when empty
+    pub fn error_to_string(&self) -> String {
+        let name = match self.get_property_value("name") {
+            JsValue::Undefined => "Error".to_string(),
+            name => name.to_js_string(),
+        };
+        let message = match self.get_property_value("message") {
+            JsValue::Undefined => String::new(),
+            message => message.to_js_string(),
+        };
+
+        if message.is_empty() {
+            return name;
+        }
+
+        if name.is_empty() {
+            return message;
+        }
+
+        return format!("{name}: {message}");
+    }
+
     pub fn is_function(&self) -> bool {
         matches!(self.kind, ObjectKind::Function(_))
     }
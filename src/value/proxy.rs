@@ -0,0 +1,24 @@
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use crate::value::object::JsObjectRef;
+
+/// Object created by `new Proxy(target, handler)`, property operations call traps of the handler,
+/// operations without a trap are forwarded to the target
+#[derive(Clone)]
+pub struct JsProxy {
+    pub target: JsObjectRef,
+    pub handler: JsObjectRef,
+}
+
+impl Debug for JsProxy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("proxy")
+    }
+}
+
+impl PartialEq for JsProxy {
+    /// Compared by identity, target may reference the proxy itself
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.target, &other.target) && Rc::ptr_eq(&self.handler, &other.handler)
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use crate::value::JsValue;
+use crate::value::object::{JsObject, JsObjectRef};
+
+/// Storage of `WeakMap` & `WeakSet`, keys are objects compared by identity and held weakly,
+/// so an entry doesn't keep its key alive. Entries of dropped keys are removed on the next mutation
+#[derive(Clone, Default)]
+pub struct WeakCollection {
+    entries: HashMap<usize, (Weak<RefCell<JsObject>>, JsValue)>,
+}
+
+/// Identity of an object, address of its allocation
+fn object_key(object: &JsObjectRef) -> usize {
+    Rc::as_ptr(object) as usize
+}
+
+impl WeakCollection {
+    /// Entry of the key, an entry whose key was dropped doesn't match even if the address was reused
+    fn find(&self, key: &JsObjectRef) -> Option<&JsValue> {
+        let (weak_key, value) = self.entries.get(&object_key(key))?;
+        weak_key.upgrade().is_some_and(|x| Rc::ptr_eq(&x, key)).then_some(value)
+    }
+
+    pub fn get(&self, key: &JsObjectRef) -> JsValue {
+        self.find(key).cloned().unwrap_or(JsValue::Undefined)
+    }
+
+    pub fn has(&self, key: &JsObjectRef) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub fn set(&mut self, key: &JsObjectRef, value: JsValue) {
+        self.entries.retain(|_, (weak_key, _)| weak_key.strong_count() > 0);
+        self.entries.insert(object_key(key), (Rc::downgrade(key), value));
+    }
+
+    pub fn delete(&mut self, key: &JsObjectRef) -> bool {
+        if !self.has(key) {
+            return false;
+        }
+
+        self.entries.remove(&object_key(key));
+        return true;
+    }
+}
+
+impl Debug for WeakCollection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("weak collection")
+    }
+}
+
+impl PartialEq for WeakCollection {
+    /// Collections are equal when they hold the same keys, compared by identity
+    fn eq(&self, other: &Self) -> bool {
+        self.entries.len() == other.entries.len()
+            && self.entries.iter().all(|(key, (_, value))| other.entries.get(key).is_some_and(|(_, x)| x == value))
+    }
+}
+
+#[test]
+fn weak_collection_does_not_keep_keys_alive() {
+    let key = JsObject::empty_ref();
+    let mut collection = WeakCollection::default();
+    collection.set(&key, JsValue::Number(1.0));
+
+    assert_eq!(Rc::strong_count(&key), 1);
+    assert_eq!(collection.get(&key), JsValue::Number(1.0));
+    assert!(!collection.has(&JsObject::empty_ref()));
+
+    drop(key);
+    collection.set(&JsObject::empty_ref(), JsValue::Undefined);
+    assert_eq!(collection.entries.len(), 1);
+}
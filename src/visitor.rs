@@ -3,31 +3,40 @@ use crate::scanner::Token;
 
 pub trait Visitor {
     fn visit_statement(&mut self, stmt: &AstStatement) {
-        match stmt {
-            AstStatement::ProgramStatement(stmt) => self.visit_program_statement(stmt),
-            AstStatement::VariableDeclaration(stmt) => self.visit_variable_declaration(stmt),
-            AstStatement::BlockStatement(stmt) => self.visit_block_statement(stmt),
-            AstStatement::WhileStatement(node) => self.visit_while_statement(node),
-            AstStatement::ForStatement(stmt) => self.visit_for_statement(stmt),
-            AstStatement::FunctionDeclaration(stmt) => self.visit_function_declaration(stmt),
-            AstStatement::ReturnStatement(node) => self.visit_return_statement(node),
-            AstStatement::ExpressionStatement(stmt) => self.visit_expression_statement(stmt),
-            AstStatement::IfStatement(stmt) => self.visit_if_statement(stmt),
-            AstStatement::BreakStatement(token) => self.visit_break_statement(token),
-        }
+        walk_statement(self, stmt);
     }
 
     fn visit_break_statement(&mut self, _: &Token) {}
 
+    fn visit_continue_statement(&mut self, _: &Token) {}
+
+    fn visit_empty_statement(&mut self, _: &Token) {}
+
     fn visit_while_statement(&mut self, node: &WhileStatementNode) {
         self.visit_expression(&node.condition);
         self.visit_statement(&node.body);
     }
 
+    fn visit_switch_statement(&mut self, node: &SwitchStatementNode) {
+        self.visit_expression(&node.discriminant);
+
+        for case in &node.cases {
+            if let Some(test) = &case.test {
+                self.visit_expression(test);
+            }
+
+            case.consequent.iter().for_each(|x| self.visit_statement(x));
+        }
+    }
+
     fn visit_return_statement(&mut self, node: &ReturnStatementNode) {
         self.visit_expression(&node.expression);
     }
 
+    fn visit_throw_statement(&mut self, node: &ThrowStatementNode) {
+        self.visit_expression(&node.expression);
+    }
+
     fn visit_for_statement(&mut self, stmt: &ForStatementNode) {
         if let Some(init) = &stmt.init {
             self.visit_statement(init);
@@ -49,6 +58,7 @@ pub trait Visitor {
         if let Some(parent) = &stmt.parent {
             self.visit_identifier_node(parent);
         }
+        stmt.fields.iter().for_each(|x| self.visit_class_field(x));
         stmt.methods.iter().for_each(|x| self.visit_class_method(x));
     }
 
@@ -56,6 +66,12 @@ pub trait Visitor {
         self.visit_function_signature(&stmt.function_signature);
     }
 
+    fn visit_class_field(&mut self, stmt: &ClassFieldNode) {
+        if let Some(value) = &stmt.value {
+            self.visit_expression(value);
+        }
+    }
+
     fn visit_function_declaration(&mut self, stmt: &FunctionDeclarationNode) {
         self.visit_function_signature(&stmt.function_signature);
     }
@@ -96,25 +112,7 @@ pub trait Visitor {
     fn visit_number_literal(&mut self, _: &NumberLiteralNode) {}
 
     fn visit_expression(&mut self, stmt: &AstExpression) {
-        match stmt {
-            AstExpression::StringLiteral(node) => self.visit_string_literal(node),
-            AstExpression::NumberLiteral(node) => self.visit_number_literal(node),
-            AstExpression::BooleanLiteral(node) => self.visit_boolean_literal(node),
-            AstExpression::NullLiteral(_) => self.visit_null_literal(),
-            AstExpression::UndefinedLiteral(_) => self.visit_undefined_literal(),
-            AstExpression::ThisExpression(node) => self.visit_this_expression(node),
-            AstExpression::Identifier(node) => self.visit_identifier_node(node),
-            AstExpression::BinaryExpression(node) => self.visit_binary_expression(node),
-            AstExpression::AssignmentExpression(node) => self.visit_assignment_expression(node),
-            AstExpression::FunctionExpression(node) => self.visit_function_expression(node),
-            AstExpression::CallExpression(node) => self.visit_call_expression(node),
-            AstExpression::ConditionalExpression(node) => self.visit_conditional_expression(node),
-            AstExpression::MemberExpression(node) => self.visit_member_expression(node),
-            AstExpression::NewExpression(node) => self.visit_new_expression(node),
-            AstExpression::ObjectExpression(node) => self.visit_object_expression(node),
-            AstExpression::ClassDeclaration(node) => self.visit_class_declaration(node),
-            AstExpression::ArrayExpression(node) => self.visit_array_expression(node),
-        }
+        walk_expression(self, stmt);
     }
 
     fn visit_conditional_expression(&mut self, node: &ConditionalExpressionNode) {
@@ -124,7 +122,15 @@ pub trait Visitor {
     }
 
     fn visit_array_expression(&mut self, node: &ArrayExpressionNode) {
-        node.items.iter().for_each(|x| self.visit_expression(x));
+        node.items.iter().flatten().for_each(|x| self.visit_expression(x));
+    }
+
+    fn visit_unary_expression(&mut self, node: &UnaryExpressionNode) {
+        self.visit_expression(&node.argument);
+    }
+
+    fn visit_spread_element(&mut self, node: &SpreadElementNode) {
+        self.visit_expression(&node.argument);
     }
 
     fn visit_function_expression(&mut self, node: &FunctionExpressionNode) {
@@ -182,3 +188,50 @@ pub trait Visitor {
 
     fn visit_identifier_node(&mut self, _: &IdentifierNode) {}
 }
+
+/// Dispatches statement to the corresponding visit method,
+/// so visitors overriding `visit_statement` can still descend into children
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, stmt: &AstStatement) {
+    match stmt {
+        AstStatement::ProgramStatement(stmt) => visitor.visit_program_statement(stmt),
+        AstStatement::VariableDeclaration(stmt) => visitor.visit_variable_declaration(stmt),
+        AstStatement::BlockStatement(stmt) => visitor.visit_block_statement(stmt),
+        AstStatement::WhileStatement(node) => visitor.visit_while_statement(node),
+        AstStatement::ForStatement(stmt) => visitor.visit_for_statement(stmt),
+        AstStatement::FunctionDeclaration(stmt) => visitor.visit_function_declaration(stmt),
+        AstStatement::ReturnStatement(node) => visitor.visit_return_statement(node),
+        AstStatement::ExpressionStatement(stmt) => visitor.visit_expression_statement(stmt),
+        AstStatement::IfStatement(stmt) => visitor.visit_if_statement(stmt),
+        AstStatement::BreakStatement(token) => visitor.visit_break_statement(token),
+        AstStatement::ContinueStatement(token) => visitor.visit_continue_statement(token),
+        AstStatement::SwitchStatement(node) => visitor.visit_switch_statement(node),
+        AstStatement::ThrowStatement(node) => visitor.visit_throw_statement(node),
+        AstStatement::EmptyStatement(token) => visitor.visit_empty_statement(token),
+    }
+}
+
+/// Dispatches expression to the corresponding visit method,
+/// so visitors overriding `visit_expression` can still descend into children
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, stmt: &AstExpression) {
+    match stmt {
+        AstExpression::StringLiteral(node) => visitor.visit_string_literal(node),
+        AstExpression::NumberLiteral(node) => visitor.visit_number_literal(node),
+        AstExpression::BooleanLiteral(node) => visitor.visit_boolean_literal(node),
+        AstExpression::NullLiteral(_) => visitor.visit_null_literal(),
+        AstExpression::UndefinedLiteral(_) => visitor.visit_undefined_literal(),
+        AstExpression::ThisExpression(node) => visitor.visit_this_expression(node),
+        AstExpression::Identifier(node) => visitor.visit_identifier_node(node),
+        AstExpression::BinaryExpression(node) => visitor.visit_binary_expression(node),
+        AstExpression::AssignmentExpression(node) => visitor.visit_assignment_expression(node),
+        AstExpression::FunctionExpression(node) => visitor.visit_function_expression(node),
+        AstExpression::CallExpression(node) => visitor.visit_call_expression(node),
+        AstExpression::ConditionalExpression(node) => visitor.visit_conditional_expression(node),
+        AstExpression::MemberExpression(node) => visitor.visit_member_expression(node),
+        AstExpression::NewExpression(node) => visitor.visit_new_expression(node),
+        AstExpression::ObjectExpression(node) => visitor.visit_object_expression(node),
+        AstExpression::ClassDeclaration(node) => visitor.visit_class_declaration(node),
+        AstExpression::ArrayExpression(node) => visitor.visit_array_expression(node),
+        AstExpression::SpreadElement(node) => visitor.visit_spread_element(node),
+        AstExpression::UnaryExpression(node) => visitor.visit_unary_expression(node),
+    }
+}